@@ -0,0 +1,17 @@
+//! Read arbitrary bytes as a map file, and write back whatever could be read.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sanctum_solver::map::Map;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(json) = std::str::from_utf8(data)
+	{
+		if let Ok(mut map) = Map::from_json(json)
+		{
+			map.pad();
+			let _ = serde_json::to_string(&map);
+		}
+	}
+});
@@ -0,0 +1,46 @@
+//! Solve arbitrary grids, which should either succeed or return an error.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sanctum_solver::{
+	map::{Map, Tile},
+	solve,
+	SolveOptionsBuilder,
+};
+
+/// # Summary
+///
+/// The most rows and columns of a fuzzed grid, so that each input solves quickly.
+const MAX_SIZE: usize = 16;
+
+fuzz_target!(|input: (bool, u8, Vec<Vec<u8>>)| {
+	let (diagonals, max_blocks, rows) = input;
+	let grid: Vec<Vec<Tile>> = rows
+		.iter()
+		.take(MAX_SIZE)
+		.map(|row| {
+			row.iter()
+				.take(MAX_SIZE)
+				.filter_map(|code| Tile::from_number(usize::from(code % 10)).ok())
+				.collect()
+		})
+		.collect();
+
+	let map: Map = match serde_json::from_value(serde_json::json!({"grid": grid, "name": "fuzz"}))
+	{
+		Ok(map) => map,
+		Err(_) => return,
+	};
+
+	let options = SolveOptionsBuilder::new()
+		.display(true)
+		.max_blocks(usize::from(max_blocks % 32))
+		.movement(diagonals)
+		.build();
+
+	if let Ok(solution) = solve(map, options)
+	{
+		assert!(solution.build.blocks.len() <= usize::from(max_blocks % 32));
+	}
+});
@@ -1,75 +1,602 @@
+mod analyze;
+mod cache;
+mod campaign;
+mod check_build;
+mod compare;
+mod compression;
+mod config;
+mod daemon;
+mod dashboard;
 mod error;
+mod export;
+mod failure;
+mod fmt;
+mod format;
+mod hook;
+mod import;
+mod import_screenshot;
+mod improve;
+mod plan;
+mod remote;
+mod report;
+mod selftest;
+mod simulate;
+mod solve;
 
-use std::{fs, path::PathBuf};
+use std::{
+	ffi::OsStr,
+	io::{self, Write},
+	path::{Path, PathBuf},
+	process,
+	result::Result as StdResult,
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		OnceLock,
+	},
+};
 
-use error::Result;
+use analyze::Analyze;
+use campaign::Campaign;
+use check_build::CheckBuild;
+use compare::Compare;
+use compression::Compression;
+use config::Config;
+use daemon::Daemon;
+use error::{Error, Result};
+use export::Export;
+use fmt::Fmt;
+use format::ErrorFormat;
+use import::Import;
+use import_screenshot::ImportScreenshot;
+use improve::Improve;
+use plan::Plan;
+use sanctum_solver::map::{CancellationToken, Map, ObjectCoordinates};
+use selftest::Selftest;
+use serde::Serialize;
+use simulate::Simulate;
+use solve::Solve;
 use structopt::StructOpt;
 
-use crate::map::{tileset::Tileset, Build, Map, ShortestPath};
+/// # Summary
+///
+/// Whether results are written as MessagePack instead of JSON. See [`App::binary`].
+static BINARY: AtomicBool = AtomicBool::new(false);
+
+/// # Summary
+///
+/// The token which every solve is given. See [`cancellation`].
+static CANCELLATION: OnceLock<CancellationToken> = OnceLock::new();
+
+/// # Summary
+///
+/// Whether JSON results are written without whitespace. See [`App::compact`].
+static COMPACT: AtomicBool = AtomicBool::new(false);
+
+/// # Summary
+///
+/// Whether coordinates are written as objects instead of pairs. See [`App::object_coordinates`].
+static OBJECT_COORDINATES: AtomicBool = AtomicBool::new(false);
+
+/// # Summary
+///
+/// Whether maps given as URLs are downloaded again. See [`App::refresh`].
+static REFRESH: AtomicBool = AtomicBool::new(false);
+
+/// # Summary
+///
+/// How many solves are running, so that Ctrl-C only cancels when there is something to cancel.
+static SOLVING: AtomicUsize = AtomicUsize::new(0);
+
+/// # Summary
+///
+/// Whether [`read_map`] rejects maps which had to be repaired. See [`App::strict`].
+static STRICT: AtomicBool = AtomicBool::new(false);
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
 #[structopt(name = "sanctum_solver", about = "A tool to find optimal layouts for a Sanctum map")]
 pub struct App
 {
-	#[structopt(help = "The maximum number of blocks to place", long, short)]
-	blocks: Option<usize>,
+	#[structopt(
+		global = true,
+		help = "Write results as MessagePack instead of JSON, which is faster for large grids. \
+		        Maps are read as either",
+		long,
+		overrides_with = "no-binary"
+	)]
+	binary: bool,
+
+	#[structopt(
+		global = true,
+		help = "Write JSON results on a single line, without any whitespace",
+		long,
+		overrides_with = "no-compact"
+	)]
+	compact: bool,
+
+	#[structopt(
+		global = true,
+		help = "A TOML file of default options. Defaults to `~/.config/sanctum-solver/config.toml`",
+		long
+	)]
+	config: Option<PathBuf>,
+
+	#[structopt(
+		default_value = "human",
+		global = true,
+		help = "How to write an error which stops the program. `json` also includes its kind",
+		long,
+		possible_values = &["human", "json"]
+	)]
+	error_format: ErrorFormat,
+
+	#[structopt(
+		global = true,
+		help = "Write results as JSON, even if the config sets `binary`",
+		long,
+		overrides_with = "binary"
+	)]
+	no_binary: bool,
+
+	#[structopt(
+		global = true,
+		help = "Write JSON results with whitespace, even if the config sets `compact`",
+		long,
+		overrides_with = "compact"
+	)]
+	no_compact: bool,
+
+	#[structopt(
+		global = true,
+		help = "Write coordinates as pairs, even if the config sets `object-coordinates`",
+		long,
+		overrides_with = "object-coordinates"
+	)]
+	no_object_coordinates: bool,
+
+	#[structopt(
+		global = true,
+		help = "Repair maps with a warning, even if the config sets `strict`",
+		long,
+		overrides_with = "strict"
+	)]
+	no_strict: bool,
 
-	#[structopt(help = "Allow diagonal movement when calculating shortest paths", long, short)]
-	diagonals: bool,
+	#[structopt(
+		global = true,
+		help = "Write coordinates as `{\"x\": 4, \"y\": 1}` objects instead of `[4, 1]` pairs",
+		long,
+		overrides_with = "no-object-coordinates"
+	)]
+	object_coordinates: bool,
 
 	#[structopt(
-		help = "Where to save the output. If not specified, goes to `stdout`",
+		global = true,
+		help = "Download maps given as URLs again, rather than reading the copy saved last time",
+		long
+	)]
+	refresh: bool,
+
+	#[structopt(
+		global = true,
+		help = "Reject maps with unknown tiles, ragged rows, or unreachable cores, instead of \
+		        repairing them with a warning",
 		long,
-		short
+		overrides_with = "no-strict"
 	)]
-	output: Option<PathBuf>,
+	strict: bool,
 
-	#[structopt(help = "Prioritize spawn regions with shorter paths to the core", long, short)]
-	prioritize: bool,
+	#[structopt(
+		global = true,
+		help = "How many threads to solve with. Defaults to one per CPU",
+		long
+	)]
+	threads: Option<usize>,
 
-	#[structopt(help = "A JSON file containing the map layout")]
-	map_json: PathBuf,
+	#[structopt(subcommand)]
+	command: Command,
 }
 
 impl App
 {
+	/// # Summary
+	///
+	/// Run the application, and exit with a status which depends on the kind of error, if any.
+	/// See [`Failure::code`](failure::Failure::code).
+	pub fn main(self) -> !
+	{
+		let error_format = self.error_format;
+		match self.run()
+		{
+			Ok(()) => process::exit(0),
+			Err(err) =>
+			{
+				error_format.print(&err);
+				process::exit(failure::Failure::from(&err).code())
+			},
+		}
+	}
+
 	/// # Summary
 	///
 	/// Run the application and parse its provided arguments / flags.
 	pub fn run(self) -> Result<()>
 	{
-		let mut map: Map = serde_json::from_slice(&fs::read(self.map_json)?)?;
-		let tileset = Tileset::new(map.grid);
+		// The self-test always uses the built-in defaults, so that its results can be compared.
+		if !matches!(self.command, Command::Selftest(_))
+		{
+			Config::load(self.config.as_deref())?;
+		}
+
+		let config = Config::get();
+		BINARY.store(Config::switch(self.binary, self.no_binary, config.binary), Ordering::Relaxed);
+		COMPACT.store(
+			Config::switch(self.compact, self.no_compact, config.compact),
+			Ordering::Relaxed,
+		);
+		OBJECT_COORDINATES.store(
+			Config::switch(
+				self.object_coordinates,
+				self.no_object_coordinates,
+				config.object_coordinates,
+			),
+			Ordering::Relaxed,
+		);
+		REFRESH.store(self.refresh, Ordering::Relaxed);
+		STRICT.store(Config::switch(self.strict, self.no_strict, config.strict), Ordering::Relaxed);
 
-		let build = if self.prioritize
+		if let Some(threads) = self.threads.or(config.threads)
 		{
-			Build::from_entrances_to_any_core_with_priority(&tileset, self.diagonals, self.blocks)
+			rayon::ThreadPoolBuilder::new()
+				.num_threads(threads)
+				.build_global()
+				.expect("Expected the thread pool to only be built once");
 		}
-		else
+
+		self.command.run()
+	}
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+enum Command
+{
+	#[structopt(about = "Inspect an existing build on a map")]
+	Analyze(Analyze),
+
+	#[structopt(about = "Solve several maps in order, carrying unspent blocks between them")]
+	Campaign(Campaign),
+
+	#[structopt(about = "Check that a build can be placed on a map, and describe it")]
+	CheckBuild(CheckBuild),
+
+	#[structopt(about = "Draw the differences between two builds of the same map")]
+	Compare(Compare),
+
+	#[structopt(about = "Solve each map which appears in a directory, until stopped")]
+	Daemon(Daemon),
+
+	#[structopt(about = "Write the grid of a map as a delimited file, e.g. for a spreadsheet")]
+	Export(Export),
+
+	#[structopt(about = "Rewrite a map in a normalized form, so that revisions can be compared")]
+	Fmt(Fmt),
+
+	#[structopt(about = "Read a map from a delimited file, e.g. one exported from a spreadsheet")]
+	Import(Import),
+
+	#[structopt(
+		about = "Guess a map from a top-down screenshot, for cleaning up by hand (experimental)"
+	)]
+	ImportScreenshot(ImportScreenshot),
+
+	#[structopt(
+		about = "Keep working on the build of a map, without making it worse, and list the changes"
+	)]
+	Improve(Improve),
+
+	#[structopt(about = "Plan which blocks to place before each wave, as blocks are gained")]
+	Plan(Plan),
+
+	#[structopt(about = "Solve the maps built into this program, and check the results are sane")]
+	Selftest(Selftest),
+
+	#[structopt(about = "Walk a wave of enemies through an existing build on a map")]
+	Simulate(Simulate),
+
+	#[structopt(about = "Find a long path for a map")]
+	Solve(Solve),
+}
+
+impl Command
+{
+	/// # Summary
+	///
+	/// Run the selected subcommand.
+	fn run(self) -> Result<()>
+	{
+		match self
 		{
-			Build::from_entrances_to_any_core(&tileset, self.diagonals, self.blocks)
-		};
+			Command::Analyze(analyze) => analyze.run(),
+			Command::Campaign(campaign) => campaign.run(),
+			Command::CheckBuild(check_build) => check_build.run(),
+			Command::Compare(compare) => compare.run(),
+			Command::Daemon(daemon) => daemon.run(),
+			Command::Export(export) => export.run(),
+			Command::Fmt(fmt) => fmt.run(),
+			Command::Import(import) => import.run(),
+			Command::ImportScreenshot(import) => import.run(),
+			Command::Improve(improve) => improve.run(),
+			Command::Plan(plan) => plan.run(),
+			Command::Selftest(selftest) => selftest.run(),
+			Command::Simulate(simulate) => simulate.run(),
+			Command::Solve(solve) => solve.run(),
+		}
+	}
+}
 
-		map.shortest_path_length = Some(
-			ShortestPath::from_entrances_to_any_core(&tileset, Some(&build.blocks), self.diagonals)
-				.into_iter()
-				.map(|path| path.map(|p| p.len()))
-				.collect(),
-		);
+/// # Summary
+///
+/// The [`CancellationToken`] which every solve is given.
+///
+/// # Remarks
+///
+/// The first time this is called, Ctrl-C is set up to cancel the running solves, which then finish
+/// with the best build found so far. Pressing Ctrl-C again, or while nothing is being solved,
+/// exits as usual.
+fn cancellation() -> &'static CancellationToken
+{
+	CANCELLATION.get_or_init(|| {
+		let token = CancellationToken::new();
+		let handler = token.clone();
+
+		// If the handler cannot be set, Ctrl-C exits straight away, as it would have anyway.
+		let _ = ctrlc::set_handler(move || {
+			if handler.is_cancelled() || SOLVING.load(Ordering::Relaxed) == 0
+			{
+				process::exit(130);
+			}
 
-		map.grid = tileset.grid;
-		build.apply_to(&mut map.grid);
+			eprintln!("Finishing with the best build so far. Press Ctrl-C again to quit.");
+			handler.cancel();
+		});
 
-		let map_json = serde_json::to_string_pretty(&map)?;
-		if let Some(output) = self.output
+		token
+	})
+}
+
+/// # Summary
+///
+/// Make sure a `map` which was read from `path` had nothing repaired, if `--strict` was passed.
+///
+/// # Errors
+///
+/// * [`Error::Strict`], if `--strict` was passed and the map has any [`Map::warnings`].
+fn check_strict(path: &Path, map: Map) -> Result<Map>
+{
+	if STRICT.load(Ordering::Relaxed)
+	{
+		if let Some(warning) = map.warnings.first()
 		{
-			fs::write(output, map_json)?;
+			return Err(Error::Strict { path: path.into(), warning: warning.clone() });
 		}
-		else
+	}
+
+	Ok(map)
+}
+
+/// # Summary
+///
+/// Quote a `field` of a delimited file if it would otherwise be read as more than one.
+fn escape(field: &str, delimiter: char) -> String
+{
+	if field.contains([delimiter, '"', '\n'])
+	{
+		format!("\"{}\"", field.replace('"', "\"\""))
+	}
+	else
+	{
+		field.into()
+	}
+}
+
+/// # Summary
+///
+/// Read the `--delimiter` of a delimited file, which is any single character or `tab`.
+fn parse_delimiter(delimiter: &str) -> StdResult<char, String>
+{
+	let mut chars = delimiter.chars();
+	match (delimiter, chars.next(), chars.next())
+	{
+		("tab", ..) => Ok('\t'),
+		(_, Some(c), None) => Ok(c),
+		_ => Err(format!("{:?} is not a single character, nor `tab`", delimiter)),
+	}
+}
+
+/// # Summary
+///
+/// Read a [`Map`] from the JSON or MessagePack file at `path`, which may be compressed (see
+/// [`Compression`]) or a URL (see [`remote::fetch`]).
+///
+/// # Remarks
+///
+/// A file named `.json`, `.msgpack`, or `.mpk` (before any compression) is read as that format.
+/// Otherwise, the format is guessed from the first byte.
+///
+/// # Errors
+///
+/// See [`check_strict`].
+fn read_map(path: &Path) -> Result<Map>
+{
+	let bytes = if remote::is_url(path)
+	{
+		compression::read(&remote::fetch(
+			&path.to_string_lossy(),
+			REFRESH.load(Ordering::Relaxed),
+		)?)?
+	}
+	else
+	{
+		compression::read(path)?
+	};
+
+	// Some editors start UTF-8 files with a byte order mark, which is not part of the JSON.
+	let json = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+
+	// JSON maps start with `{` (or whitespace, or a comment), whereas MessagePack maps start with
+	// a byte which is not ASCII.
+	let extension = Compression::inner_extension(path).and_then(OsStr::to_str);
+	let message_pack = match extension.map(str::to_ascii_lowercase).as_deref()
+	{
+		Some("json") => false,
+		Some("mpk" | "msgpack") => true,
+		_ => json.first().is_some_and(|b| !b.is_ascii()),
+	};
+
+	let map = if message_pack
+	{
+		rmp_serde::from_slice(&bytes)?
+	}
+	else
+	{
+		let text = String::from_utf8_lossy(json);
+		Map::from_json(&text).map_err(|err| Error::syntax(path, &text, err))?
+	};
+
+	check_strict(path, map)
+}
+
+/// # Summary
+///
+/// Write a `header` and some `rows` as CSV to the `output` file, or `stdout` if there is none.
+fn write_csv(
+	output: Option<&Path>,
+	header: &[&str],
+	rows: impl Iterator<Item = Vec<String>>,
+) -> Result<()>
+{
+	let mut lines = vec![header.join(",")];
+	lines.extend(rows.map(|row| row.iter().map(|f| escape(f, ',')).collect::<Vec<_>>().join(",")));
+
+	write_text(output, &lines.join("\n"))
+}
+
+/// # Summary
+///
+/// Convert `value` to JSON, with its coordinates written as objects if `--object-coordinates` was
+/// passed.
+fn to_value(value: &impl Serialize) -> serde_json::Result<serde_json::Value>
+{
+	if OBJECT_COORDINATES.load(Ordering::Relaxed)
+	{
+		serde_json::to_value(ObjectCoordinates(value))
+	}
+	else
+	{
+		serde_json::to_value(value)
+	}
+}
+
+/// # Summary
+///
+/// Write `value` as JSON (or MessagePack, if `--binary` was passed) to the `output` file, or
+/// `stdout` if there is none.
+///
+/// # Remarks
+///
+/// The JSON is indented, unless `--compact` was passed. Coordinates are written as objects if
+/// `--object-coordinates` was passed.
+fn write_value(output: Option<&Path>, value: &impl Serialize) -> Result<()>
+{
+	if OBJECT_COORDINATES.load(Ordering::Relaxed)
+	{
+		write_serialized(output, &ObjectCoordinates(value))
+	}
+	else
+	{
+		write_serialized(output, value)
+	}
+}
+
+/// # Summary
+///
+/// [`write_value`], once the form of coordinates has been chosen.
+fn write_serialized(output: Option<&Path>, value: &impl Serialize) -> Result<()>
+{
+	if !BINARY.load(Ordering::Relaxed)
+	{
+		let json = if COMPACT.load(Ordering::Relaxed)
 		{
-			println!("{}", map_json);
+			serde_json::to_string(value)?
 		}
+		else
+		{
+			serde_json::to_string_pretty(value)?
+		};
+
+		return write_text(output, &json);
+	}
+
+	let bytes = rmp_serde::to_vec_named(value)?;
+	match output
+	{
+		Some(o) => compression::write(o, &bytes)?,
+		None => io::stdout().write_all(&bytes)?,
+	}
+
+	Ok(())
+}
+
+/// # Summary
+///
+/// Write some `text` to the `output` file, or `stdout` if there is none.
+fn write_text(output: Option<&Path>, text: &str) -> Result<()>
+{
+	if let Some(o) = output
+	{
+		compression::write(o, text.as_bytes())?;
+	}
+	else
+	{
+		println!("{}", text);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{env, fs, path::PathBuf};
+
+	use sanctum_solver::map::Map;
+
+	#[test]
+	fn read_map()
+	{
+		let dir: PathBuf = env::temp_dir().join(format!("sanctum-read-map-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+
+		let json = r#"{"name": "bom", "grid": ["S_C"]}"#;
+		let map = Map::from_json(json).unwrap();
+		let write = |name: &str, bytes: &[u8]| {
+			let path = dir.join(name);
+			fs::write(&path, bytes).unwrap();
+			path
+		};
+
+		// The byte order mark is not mistaken for the start of a MessagePack map.
+		let bom = [b"\xEF\xBB\xBF", json.as_bytes()].concat();
+		assert_eq!(super::read_map(&write("bom.json", &bom)).unwrap().grid, map.grid);
+		assert_eq!(super::read_map(&write("bom", &bom)).unwrap().grid, map.grid);
+
+		// The extension decides the format.
+		let message_pack = rmp_serde::to_vec_named(&map).unwrap();
+		assert_eq!(super::read_map(&write("map.mpk", &message_pack)).unwrap().grid, map.grid);
+		assert!(matches!(
+			super::read_map(&write("map.json", &message_pack)),
+			Err(super::Error::Syntax { .. })
+		));
 
-		Ok(())
+		fs::remove_dir_all(dir).unwrap();
 	}
 }
@@ -0,0 +1,76 @@
+mod budget;
+mod choke_points;
+mod core_entrances;
+mod graph;
+mod heatmap;
+mod sensitivity;
+mod threat;
+mod tiles;
+
+use budget::Budget;
+use choke_points::ChokePoints;
+use core_entrances::CoreEntrances;
+use graph::Graph;
+use heatmap::Heatmap;
+use sensitivity::Sensitivity;
+use structopt::StructOpt;
+use threat::Threat;
+use tiles::Tiles;
+
+use super::error::Result;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub enum Analyze
+{
+	#[structopt(about = "Recommend how many of a budget of blocks to spend on each region")]
+	Budget(Budget),
+
+	#[structopt(
+		about = "List the tiles every path crosses, and the blocks which must never be sold"
+	)]
+	ChokePoints(ChokePoints),
+
+	#[structopt(about = "List the tiles beside each core which enemies step onto it from, and \
+	                     which of them the build leaves open")]
+	CoreEntrances(CoreEntrances),
+
+	#[structopt(about = "Write the regions, entrances, and choke points as a Graphviz DOT graph")]
+	Graph(Graph),
+
+	#[structopt(about = "Draw how far each tile is from the nearest core, as a colored grid")]
+	Heatmap(Heatmap),
+
+	#[structopt(about = "Remove each block in turn and report the impact on path length")]
+	Sensitivity(Sensitivity),
+
+	#[structopt(
+		about = "Draw how many towers can hit enemies on each tile of the paths, to compare builds"
+	)]
+	Threat(Threat),
+
+	#[structopt(
+		about = "Describe every tile: its type, distance to a core, and which paths cross it"
+	)]
+	Tiles(Tiles),
+}
+
+impl Analyze
+{
+	/// # Summary
+	///
+	/// Run the selected analysis.
+	pub fn run(self) -> Result<()>
+	{
+		match self
+		{
+			Analyze::Budget(budget) => budget.run(),
+			Analyze::ChokePoints(choke_points) => choke_points.run(),
+			Analyze::CoreEntrances(core_entrances) => core_entrances.run(),
+			Analyze::Graph(graph) => graph.run(),
+			Analyze::Heatmap(heatmap) => heatmap.run(),
+			Analyze::Sensitivity(sensitivity) => sensitivity.run(),
+			Analyze::Threat(threat) => threat.run(),
+			Analyze::Tiles(tiles) => tiles.run(),
+		}
+	}
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, CoreEntrance};
+use structopt::StructOpt;
+
+use crate::app::{config::Config, error::Result, read_map, write_value};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct CoreEntrances
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl CoreEntrances
+{
+	/// # Summary
+	///
+	/// Find which tiles beside each core are still open through the map's build.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		write_value(self.output.as_deref(), &CoreEntrance::of(&tileset, &build, diagonals))
+	}
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, RegionGraph};
+use structopt::StructOpt;
+
+use crate::app::{config::Config, error::Result, read_map, write_text};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Graph
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Graph
+{
+	/// # Summary
+	///
+	/// Write the regions, entrances, and choke points of the map as a Graphviz DOT graph.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		write_text(
+			self.output.as_deref(),
+			&RegionGraph::of(&tileset, &build, diagonals).to_string(),
+		)
+	}
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{Build, DistanceField, Tile};
+use structopt::StructOpt;
+
+use crate::app::{config::Config, error::Result, read_map};
+
+/// # Summary
+///
+/// The background colors of the heatmap, from nearest to furthest from a core.
+pub(super) const RAMP: [u8; 11] = [196, 202, 208, 214, 220, 226, 190, 154, 118, 82, 46];
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Heatmap
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Heatmap
+{
+	/// # Summary
+	///
+	/// Draw how far each tile of the map is from the nearest core.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let field = DistanceField::to_tile(&map.grid, Some(&build.blocks), Tile::Core, diagonals);
+
+		build.apply_to(&mut map.grid)?;
+		print!("{}", render(&map.grid, &field));
+
+		Ok(())
+	}
+}
+
+/// # Summary
+///
+/// Draw each distance of the `field` on a colored background, and every other tile of the `grid`
+/// as its [`char`].
+fn render(grid: &[Vec<Tile>], field: &DistanceField) -> String
+{
+	let max = field.max().unwrap_or(0).max(1);
+
+	let mut rendered = String::new();
+	grid.iter().zip(field.rows()).for_each(|(tiles, distances)| {
+		tiles.iter().zip(distances).for_each(|(tile, distance)| match distance
+		{
+			Some(d) => rendered.push_str(&format!(
+				"\x1b[30;48;5;{}m{:>3}\x1b[0m",
+				RAMP[d * (RAMP.len() - 1) / max],
+				d
+			)),
+			None => rendered.push_str(&format!("{:>3}", char::from(*tile))),
+		});
+
+		rendered.push('\n');
+	});
+
+	rendered
+}
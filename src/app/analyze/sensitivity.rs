@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, Sensitivity as BuildSensitivity};
+use structopt::StructOpt;
+
+use crate::app::{config::Config, error::Result, format::Format, read_map, write_csv, write_value};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Sensitivity
+{
+	#[structopt(
+		default_value = "25",
+		help = "The percentage of a region's path length which must be lost for a block to be \
+		        critical",
+		long,
+		short
+	)]
+	critical: usize,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "How to write the output. CSV has one row per block. Defaults to `json`",
+		long,
+		short,
+		possible_values = &["csv", "json"]
+	)]
+	format: Option<Format>,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Sensitivity
+{
+	/// # Summary
+	///
+	/// Measure how much each block of the map's build contributes to its path lengths.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		let sensitivity =
+			BuildSensitivity::of(&tileset, &build, diagonals, self.critical as f64 / 100.0);
+
+		match self.format.or(Config::get().format).unwrap_or_default()
+		{
+			Format::Csv => write_csv(
+				self.output.as_deref(),
+				&["x", "y", "critical", "loss", "shortest_path_length"],
+				sensitivity.impacts.iter().map(|impact| {
+					vec![
+						impact.block.0.to_string(),
+						impact.block.1.to_string(),
+						impact.critical.to_string(),
+						impact.loss.to_string(),
+						impact
+							.shortest_path_length
+							.iter()
+							.map(|length| length.map(|l| l.to_string()).unwrap_or_default())
+							.collect::<Vec<_>>()
+							.join(";"),
+					]
+				}),
+			),
+			Format::Json => write_value(self.output.as_deref(), &sensitivity),
+		}
+	}
+}
@@ -0,0 +1,133 @@
+use std::{path::PathBuf, result::Result as StdResult};
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, Coordinate, ThreatMap, Tile, Tower};
+use structopt::StructOpt;
+
+use super::heatmap::RAMP;
+use crate::app::{config::Config, error::Result, read_map};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Threat
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		default_value = "4",
+		help = "How many tiles away a tower can hit, unless its tile sets a `range` in the \
+		        metadata",
+		long
+	)]
+	range: usize,
+
+	#[structopt(
+		help = "A tower as `x,y`, or `x,y:range`, in addition to every `TowerBase` of the map. May \
+		        be given more than once",
+		long,
+		parse(try_from_str = parse_tower)
+	)]
+	tower: Vec<(Coordinate, Option<usize>)>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Threat
+{
+	/// # Summary
+	///
+	/// Draw how many towers can hit the enemies on each tile of the paths, followed by how much
+	/// each region's path is covered in total.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		let mut towers = Tower::on_tower_bases(&tileset, self.range);
+		towers.extend(
+			self.tower.into_iter().map(|(coordinate, range)| Tower {
+				coordinate,
+				range: range.unwrap_or(self.range),
+			}),
+		);
+
+		let threat = ThreatMap::of(&tileset, &build, &towers, diagonals);
+		let mut grid = tileset.grid;
+		build.apply_to(&mut grid)?;
+		print!("{}", render(&grid, &threat));
+
+		println!();
+		threat.exposure.iter().enumerate().for_each(|(region, exposure)| match exposure
+		{
+			Some(e) => println!("region {}: {}", region, e),
+			None => println!("region {}: none", region),
+		});
+		println!("total: {}", threat.total);
+
+		Ok(())
+	}
+}
+
+/// # Summary
+///
+/// Read a `--tower`, which is a coordinate and an optional range.
+fn parse_tower(tower: &str) -> StdResult<(Coordinate, Option<usize>), String>
+{
+	let (coordinate, range) = match tower.split_once(':')
+	{
+		Some((coordinate, range)) => (coordinate, Some(range)),
+		None => (tower, None),
+	};
+
+	let error = || format!("{:?} is not of the form `x,y` or `x,y:range`", tower);
+	let (x, y) = coordinate.split_once(',').ok_or_else(error)?;
+	let coordinate =
+		Coordinate(x.trim().parse().map_err(|_| error())?, y.trim().parse().map_err(|_| error())?);
+
+	match range
+	{
+		Some(r) => Ok((coordinate, Some(r.trim().parse().map_err(|_| error())?))),
+		None => Ok((coordinate, None)),
+	}
+}
+
+/// # Summary
+///
+/// Draw each tile of the `threat`'s opportunity on a colored background, and every tile which no
+/// tower can hit enemies on as its [`char`] in the `grid`.
+fn render(grid: &[Vec<Tile>], threat: &ThreatMap) -> String
+{
+	let max = threat.opportunity.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+	let mut rendered = String::new();
+	grid.iter().zip(&threat.opportunity).for_each(|(tiles, opportunity)| {
+		tiles.iter().zip(opportunity).for_each(|(tile, o)| match o
+		{
+			0 => rendered.push_str(&format!("{:>3}", char::from(*tile))),
+			o => rendered.push_str(&format!(
+				"\x1b[30;48;5;{}m{:>3}\x1b[0m",
+				RAMP[o * (RAMP.len() - 1) / max],
+				o
+			)),
+		});
+
+		rendered.push('\n');
+	});
+
+	rendered
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, Simulation, TileStats};
+use structopt::StructOpt;
+
+use crate::app::{config::Config, error::Result, format::Format, read_map, write_csv, write_value};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Tiles
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		default_value = "1",
+		help = "How many enemies leave each spawn region when counting visits",
+		long,
+		short
+	)]
+	enemies: usize,
+
+	#[structopt(
+		help = "How to write the output. Defaults to `json`",
+		long,
+		short,
+		possible_values = &["csv", "json"]
+	)]
+	format: Option<Format>,
+
+	#[structopt(
+		default_value = "1",
+		help = "How many ticks pass between enemies leaving",
+		long,
+		short
+	)]
+	interval: usize,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Tiles
+{
+	/// # Summary
+	///
+	/// Describe every tile of the map's build.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		let simulation = Simulation::new(&tileset, &build, diagonals, self.enemies, self.interval);
+		let stats = TileStats::of(&tileset, &build, &simulation, diagonals)?;
+
+		match self.format.or(Config::get().format).unwrap_or_default()
+		{
+			Format::Csv => write_csv(
+				self.output.as_deref(),
+				&["x", "y", "tile", "distance_to_core", "paths", "visits"],
+				stats.iter().map(|s| {
+					vec![
+						s.coordinate.0.to_string(),
+						s.coordinate.1.to_string(),
+						format!("{:?}", s.tile),
+						s.distance_to_core.map(|d| d.to_string()).unwrap_or_default(),
+						s.paths.iter().map(usize::to_string).collect::<Vec<_>>().join(";"),
+						s.visits.to_string(),
+					]
+				}),
+			),
+			Format::Json => write_value(self.output.as_deref(), &stats),
+		}
+	}
+}
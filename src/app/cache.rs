@@ -0,0 +1,184 @@
+use std::{path::Path, time::Duration};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use sanctum_solver::{
+	map::{Build, Tile},
+	SolveOptions,
+};
+
+use super::error::Result;
+
+/// # Summary
+///
+/// A 64-bit [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// hash of some `bytes`, as hexadecimal. Unlike the standard library's hashers, this is the same
+/// on every platform and version.
+pub fn hash(bytes: &[u8]) -> String
+{
+	let hash = bytes.iter().fold(0xCBF2_9CE4_8422_2325_u64, |hash, byte| {
+		(hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01B3)
+	});
+
+	format!("{:016x}", hash)
+}
+
+/// # Summary
+///
+/// What a [`Build`] is stored under in a [`Cache`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Key
+{
+	/// # Summary
+	///
+	/// The hash of the grid.
+	map: String,
+
+	/// # Summary
+	///
+	/// The hash of the [`SolveOptions`] which change what a good build is (e.g. diagonal
+	/// movement).
+	options: String,
+
+	/// # Summary
+	///
+	/// The `(width, height)` of the grid.
+	size: (usize, usize),
+}
+
+impl Key
+{
+	/// # Summary
+	///
+	/// The [`Key`] for solving some `grid` with the given `options`, where each
+	/// [`Tile::Hazard`] deals `hazard_damage`.
+	///
+	/// # Remarks
+	///
+	/// The block limit and [`Strategy`](sanctum_solver::map::Strategy) are left out, so that
+	/// builds found with one can be reused or improved with another.
+	pub fn new(grid: &[Vec<Tile>], options: &SolveOptions, hazard_damage: usize) -> Self
+	{
+		let size = (grid.iter().map(Vec::len).max().unwrap_or(0), grid.len());
+		let tiles: Vec<u8> = grid.iter().flatten().map(|tile| tile.number() as u8).collect();
+		// Only included when set, so that builds cached before there was a `--fairness`,
+		// `--corner-cutting`, `--objective`, or `--constraints` are found.
+		let fairness = options.fairness.map(|f| format!(";fairness={}", f)).unwrap_or_default();
+		let formula =
+			options.formula.as_ref().map(|f| format!(";formula={}", f)).unwrap_or_default();
+		let constraints = options
+			.constraints
+			.as_ref()
+			.map(|c| format!(";constraints={}", serde_json::to_string(c).unwrap_or_default()))
+			.unwrap_or_default();
+		let movement = options.movement;
+		let corner_cutting = match movement.corner_cutting
+		{
+			c if c.is_default() => String::new(),
+			c => format!(";corner_cutting={}", c),
+		};
+		let options = format!(
+			"diagonals={};hazard_damage={};metric={};robust={}{}{}{}{}",
+			movement.diagonals,
+			hazard_damage,
+			options.metric(),
+			options.robust,
+			fairness,
+			corner_cutting,
+			formula,
+			constraints
+		);
+
+		Self {
+			map: hash(&[format!("{}x{}:", size.0, size.1).as_bytes(), &tiles].concat()),
+			options: hash(options.as_bytes()),
+			size,
+		}
+	}
+}
+
+/// # Summary
+///
+/// A database of the best [`Build`] known for each map and set of [`SolveOptions`].
+#[derive(Debug)]
+pub struct Cache
+{
+	connection: Connection,
+}
+
+impl Cache
+{
+	/// # Summary
+	///
+	/// The best [`Build`] stored under some `key` which has at most `max_blocks`, and its score.
+	pub fn get(&self, key: &Key, max_blocks: Option<usize>) -> Result<Option<(Build, usize)>>
+	{
+		let found: Option<(String, i64, i64)> = self
+			.connection
+			.query_row(
+				"SELECT code, blocks, score FROM builds WHERE map = ?1 AND options = ?2",
+				params![key.map, key.options],
+				|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+			)
+			.optional()?;
+
+		Ok(found.and_then(|(code, blocks, score)| {
+			if max_blocks.is_some_and(|max| blocks as usize > max)
+			{
+				return None;
+			}
+
+			// A build for a grid of a different size is from a hash collision, so ignore it.
+			Build::from_code(&code)
+				.ok()
+				.filter(|(_, size)| *size == key.size)
+				.map(|(build, _)| (build, score as usize))
+		}))
+	}
+
+	/// # Summary
+	///
+	/// Open the database at `path`, creating it if it does not exist.
+	pub fn open(path: &Path) -> Result<Self>
+	{
+		let connection = Connection::open(path)?;
+
+		// Several daemon workers may share one database.
+		connection.busy_timeout(Duration::from_secs(30))?;
+		connection.execute_batch(
+			"CREATE TABLE IF NOT EXISTS builds (
+				map TEXT NOT NULL,
+				options TEXT NOT NULL,
+				code TEXT NOT NULL,
+				blocks INTEGER NOT NULL,
+				score INTEGER NOT NULL,
+				PRIMARY KEY (map, options)
+			)",
+		)?;
+
+		Ok(Self { connection })
+	}
+
+	/// # Summary
+	///
+	/// Store a `build` under some `key`, unless a better one is already stored. A build is better
+	/// if it has a higher `score`, or the same score with fewer blocks.
+	pub fn put(&self, key: &Key, build: &Build, score: usize) -> Result<()>
+	{
+		self.connection.execute(
+			"INSERT INTO builds (map, options, code, blocks, score) VALUES (?1, ?2, ?3, ?4, ?5)
+			ON CONFLICT (map, options) DO UPDATE SET
+				code = excluded.code, blocks = excluded.blocks, score = excluded.score
+			WHERE excluded.score > builds.score OR
+				(excluded.score = builds.score AND excluded.blocks < builds.blocks)",
+			params![
+				key.map,
+				key.options,
+				build.to_code(key.size.0, key.size.1),
+				build.blocks.len() as i64,
+				score as i64
+			],
+		)?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::Map;
+use serde::Serialize;
+use structopt::StructOpt;
+
+use super::{cancellation, error::Result, hook::Hooks, read_map, solve::Options, write_value};
+
+/// # Summary
+///
+/// One map of a [`Campaign`], after it has been solved.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Stage
+{
+	/// # Summary
+	///
+	/// How many blocks were available for this map.
+	budget: usize,
+
+	/// # Summary
+	///
+	/// How many blocks were left over to carry into the next map.
+	budget_left: usize,
+
+	map: Map,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Campaign
+{
+	#[structopt(
+		default_value = "0",
+		help = "How many blocks are available before the first map",
+		long
+	)]
+	budget: usize,
+
+	#[structopt(default_value = "0", help = "How many blocks are gained before each map", long)]
+	income: usize,
+
+	#[structopt(flatten)]
+	hooks: Hooks,
+
+	#[structopt(flatten)]
+	options: Options,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(
+		help = "JSON files containing the layout of each map, in the order they are played"
+	)]
+	map_json: Vec<PathBuf>,
+}
+
+impl Campaign
+{
+	/// # Summary
+	///
+	/// Solve each map in turn, carrying unspent blocks over to the next one.
+	pub fn run(self) -> Result<()>
+	{
+		let mut budget_left = self.budget;
+		let mut stages = Vec::with_capacity(self.map_json.len());
+
+		for path in self.map_json.iter()
+		{
+			let budget = budget_left + self.income;
+			let (map, build) = self.options.solve(read_map(path)?, Some(budget), |_| ())?;
+
+			budget_left = budget - build.blocks.len();
+			stages.push(Stage { budget, budget_left, map });
+
+			// Ctrl-C was pressed, so write out the stages which were solved so far.
+			if cancellation().is_cancelled()
+			{
+				break;
+			}
+		}
+
+		write_value(self.output.as_deref(), &stages)?;
+		self.hooks.fire(self.output.as_deref())
+	}
+}
@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{
+	tileset::TilesetBuilder,
+	Build,
+	Coordinate,
+	Metric,
+	Objective,
+	Stats,
+	Tile,
+};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use super::{
+	config::Config,
+	error::{Error, Result},
+	read_map,
+	write_value,
+};
+
+/// # Summary
+///
+/// A reason a build cannot be placed on a map as it is.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+enum Problem
+{
+	/// # Summary
+	///
+	/// The build code was made for a grid of another `size`.
+	GridSize
+	{
+		size: (usize, usize)
+	},
+
+	/// # Summary
+	///
+	/// Some spawn `region` cannot reach a core through the build.
+	NoPath
+	{
+		region: usize
+	},
+
+	/// # Summary
+	///
+	/// A block is on a `tile` which blocks cannot be placed on.
+	NotBuildable
+	{
+		coordinate: Coordinate, tile: Tile
+	},
+
+	/// # Summary
+	///
+	/// A block is not on the grid.
+	OutOfBounds
+	{
+		coordinate: Coordinate
+	},
+}
+
+/// # Summary
+///
+/// The result of [checking](CheckBuild) a build.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Check
+{
+	/// # Summary
+	///
+	/// Everything wrong with the build. If this is empty, the build is valid.
+	problems: Vec<Problem>,
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path through the build.
+	shortest_path_length: Vec<Option<usize>>,
+
+	stats: Stats,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct CheckBuild
+{
+	#[structopt(
+		help = "Check the blocks of a build code (see `solve --build-code`) instead of the \
+		        `Block`s on the map",
+		long
+	)]
+	build_code: Option<String>,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl CheckBuild
+{
+	/// # Summary
+	///
+	/// Check that a build can be placed on the map, and describe it.
+	///
+	/// # Errors
+	///
+	/// * [`Error::InvalidBuild`], if it cannot. The [`Check`] is still written.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		map.pad();
+
+		let mut build = Build::take_from(&mut map.grid);
+		let mut problems = Vec::new();
+
+		if let Some(code) = &self.build_code
+		{
+			let (from_code, size) = Build::from_code(code)?;
+			let width = map.grid.first().map(Vec::len).unwrap_or(0);
+			if size != (width, map.grid.len())
+			{
+				problems.push(Problem::GridSize { size });
+			}
+
+			build = from_code;
+		}
+
+		let mut blocks: Vec<_> = build.blocks.iter().copied().collect();
+		blocks.sort_unstable_by_key(|coord| (coord.1, coord.0));
+		problems.extend(blocks.into_iter().filter_map(|coordinate| {
+			match coordinate.get_from(&map.grid)
+			{
+				None => Some(Problem::OutOfBounds { coordinate }),
+				Some(tile) if !tile.is_buildable() =>
+				{
+					Some(Problem::NotBuildable { coordinate, tile })
+				},
+				_ => None,
+			}
+		}));
+
+		let metric = Metric::for_movement(diagonals);
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+		let objective = Objective {
+			fairness: None,
+			formula: None,
+			hazard_damage: map.hazard_damage.unwrap_or(1),
+			robust: false,
+		};
+
+		let shortest_path_length = build.shortest_path_lengths(&tileset, diagonals);
+		problems.extend(
+			shortest_path_length
+				.iter()
+				.enumerate()
+				.filter(|(_, length)| length.is_none())
+				.map(|(region, _)| Problem::NoPath { region }),
+		);
+
+		let check = Check {
+			stats: Stats::of(&tileset, &build, &objective, diagonals.into(), metric),
+			problems,
+			shortest_path_length,
+		};
+		write_value(self.output.as_deref(), &check)?;
+
+		match check.problems.len()
+		{
+			0 => Ok(()),
+			problems => Err(Error::InvalidBuild { problems }),
+		}
+	}
+}
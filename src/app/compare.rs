@@ -0,0 +1,122 @@
+use std::{fmt::Write, path::PathBuf};
+
+use sanctum_solver::map::{tileset::TilesetBuilder, Build, Coordinate, Tile};
+use structopt::StructOpt;
+
+use super::{
+	config::Config,
+	error::{Error, Result},
+	read_map,
+};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Compare
+{
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(help = "Draw the grid with symbols only, without any colors", long)]
+	plain: bool,
+
+	#[structopt(help = "A JSON file containing the map layout, with the first build's `Block`s")]
+	before: PathBuf,
+
+	#[structopt(help = "A JSON file containing the same map, with the second build's `Block`s")]
+	after: PathBuf,
+}
+
+impl Compare
+{
+	/// # Summary
+	///
+	/// Draw the blocks which the second build added, removed, and kept from the first, followed
+	/// by how long each region's path is in both.
+	///
+	/// # Errors
+	///
+	/// * [`Error::DifferentMaps`], if the builds are not on the same map.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let (mut before, mut after) = (read_map(&self.before)?, read_map(&self.after)?);
+		before.pad();
+		after.pad();
+
+		let (old, new) = (Build::take_from(&mut before.grid), Build::take_from(&mut after.grid));
+		if before.grid != after.grid
+		{
+			return Err(Error::DifferentMaps { after: self.after, before: self.before });
+		}
+
+		print!("{}", render(&before.grid, &old, &new, !self.plain));
+
+		let tileset = TilesetBuilder::from_map(&before, diagonals).build()?;
+		let show = |length: Option<usize>| length.map_or_else(|| "none".into(), |l| l.to_string());
+		println!(
+			"\n{} added, {} removed, {} unchanged",
+			new.blocks.difference(&old.blocks).count(),
+			old.blocks.difference(&new.blocks).count(),
+			old.blocks.intersection(&new.blocks).count(),
+		);
+		old.shortest_path_lengths(&tileset, diagonals)
+			.into_iter()
+			.zip(new.shortest_path_lengths(&tileset, diagonals))
+			.enumerate()
+			.for_each(|(region, (old, new))| {
+				println!("region {}: {} -> {}", region, show(old), show(new))
+			});
+
+		Ok(())
+	}
+}
+
+/// # Summary
+///
+/// Draw each tile of the `grid` as its [`char`], except for blocks: `+` if only the `new` build
+/// has it, `-` if only the `old` build has it, and `#` if both do.
+///
+/// # Parameters
+///
+/// * `color`, whether to also give added blocks a green background, and removed blocks a red one.
+fn render(grid: &[Vec<Tile>], old: &Build, new: &Build, color: bool) -> String
+{
+	let mut rendered = String::new();
+	grid.iter().enumerate().for_each(|(y, row)| {
+		row.iter().enumerate().for_each(|(x, tile)| {
+			let coord = Coordinate(x, y);
+			let (symbol, background) =
+				match (old.blocks.contains(&coord), new.blocks.contains(&coord))
+				{
+					(false, true) => ('+', Some(42)),
+					(true, false) => ('-', Some(41)),
+					(true, true) => (char::from(Tile::Block), None),
+					(false, false) => (char::from(*tile), None),
+				};
+
+			match background.filter(|_| color)
+			{
+				Some(b) =>
+				{
+					let _ = write!(rendered, "\x1b[30;{}m{}\x1b[0m", b, symbol);
+				},
+				None => rendered.push(symbol),
+			}
+		});
+
+		rendered.push('\n');
+	});
+
+	rendered
+}
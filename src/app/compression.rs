@@ -0,0 +1,118 @@
+use std::{
+	ffi::OsStr,
+	fs,
+	io::{self, Read, Write},
+	path::Path,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+
+use super::error::{Error, Result};
+
+/// # Summary
+///
+/// How a file is compressed, which is decided by its extension.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Compression
+{
+	/// # Summary
+	///
+	/// `.gz`
+	Gzip,
+
+	/// # Summary
+	///
+	/// Any other extension.
+	#[default]
+	None,
+
+	/// # Summary
+	///
+	/// `.zst`
+	Zstd,
+}
+
+impl Compression
+{
+	/// # Summary
+	///
+	/// Compress some `bytes`.
+	pub fn compress(&self, bytes: &[u8]) -> io::Result<Vec<u8>>
+	{
+		match self
+		{
+			Compression::Gzip =>
+			{
+				let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+				encoder.write_all(bytes)?;
+				encoder.finish()
+			},
+			Compression::None => Ok(bytes.to_vec()),
+			Compression::Zstd => zstd::encode_all(bytes, 0),
+		}
+	}
+
+	/// # Summary
+	///
+	/// Undo [`Compression::compress`].
+	pub fn decompress(&self, bytes: Vec<u8>) -> io::Result<Vec<u8>>
+	{
+		match self
+		{
+			Compression::Gzip =>
+			{
+				let mut decompressed = Vec::new();
+				GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+				Ok(decompressed)
+			},
+			Compression::None => Ok(bytes),
+			Compression::Zstd => zstd::decode_all(bytes.as_slice()),
+		}
+	}
+
+	/// # Summary
+	///
+	/// The extension of a `path` once its compression is taken off, e.g. `json` for `map.json.gz`.
+	pub fn inner_extension(path: &Path) -> Option<&OsStr>
+	{
+		match Self::of(path)
+		{
+			Compression::None => path.extension(),
+			_ => path.file_stem().map(Path::new).and_then(Path::extension),
+		}
+	}
+
+	/// # Summary
+	///
+	/// The [`Compression`] of the file at some `path`.
+	pub fn of(path: &Path) -> Self
+	{
+		match path.extension().and_then(OsStr::to_str)
+		{
+			Some("gz") => Compression::Gzip,
+			Some("zst") => Compression::Zstd,
+			_ => Compression::None,
+		}
+	}
+}
+
+/// # Summary
+///
+/// Read the file at `path`, decompressing it according to its [`Compression`].
+pub fn read(path: &Path) -> Result<Vec<u8>>
+{
+	fs::read(path)
+		.and_then(|bytes| Compression::of(path).decompress(bytes))
+		.map_err(|err| Error::File { err, path: path.into() })
+}
+
+/// # Summary
+///
+/// Write some `bytes` to the file at `path`, compressing them according to its [`Compression`].
+pub fn write(path: &Path, bytes: &[u8]) -> Result<()>
+{
+	Compression::of(path)
+		.compress(bytes)
+		.and_then(|compressed| fs::write(path, compressed))
+		.map_err(|err| Error::File { err, path: path.into() })
+}
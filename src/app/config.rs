@@ -0,0 +1,151 @@
+use std::{
+	env,
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	sync::OnceLock,
+};
+
+use sanctum_solver::map::{CornerCutting, Strategy};
+use serde::Deserialize;
+
+use super::{
+	error::{Error, Result},
+	format::Format,
+};
+
+/// # Summary
+///
+/// The [`Config`] which was [loaded](Config::load).
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// # Summary
+///
+/// Defaults for command-line options, so that they do not have to be passed every time.
+///
+/// # Remarks
+///
+/// Options passed on the command line take precedence. Switches (e.g. `diagonals`) which are
+/// turned on here can be turned off with their `--no-` flag (e.g. `--no-diagonals`).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config
+{
+	/// # Summary
+	///
+	/// See `--binary`.
+	pub binary: bool,
+
+	/// # Summary
+	///
+	/// See `--compact`.
+	pub compact: bool,
+
+	/// # Summary
+	///
+	/// See `--corner-cutting`.
+	pub corner_cutting: Option<CornerCutting>,
+
+	/// # Summary
+	///
+	/// See `--diagonals`.
+	pub diagonals: bool,
+
+	/// # Summary
+	///
+	/// See `--format`.
+	pub format: Option<Format>,
+
+	/// # Summary
+	///
+	/// See `--object-coordinates`.
+	pub object_coordinates: bool,
+
+	/// # Summary
+	///
+	/// See `--strategy`.
+	pub strategy: Option<Strategy>,
+
+	/// # Summary
+	///
+	/// See `--strict`.
+	pub strict: bool,
+
+	/// # Summary
+	///
+	/// See `--threads`.
+	pub threads: Option<usize>,
+}
+
+impl Config
+{
+	/// # Summary
+	///
+	/// The [`Config`] which was [loaded](Config::load), or the default if none was.
+	pub fn get() -> &'static Self
+	{
+		CONFIG.get_or_init(Self::default)
+	}
+
+	/// # Summary
+	///
+	/// Read the [`Config`] from `path`, or the [default path](Config::path) if there is none.
+	///
+	/// # Remarks
+	///
+	/// It is not an error for the default path not to exist.
+	///
+	/// # Errors
+	///
+	/// * [`Error::Config`], if the file is not a valid [`Config`].
+	/// * [`Error::File`], if the file cannot be read.
+	pub fn load(path: Option<&Path>) -> Result<()>
+	{
+		let (path, required) = match path
+		{
+			Some(p) => (p.to_path_buf(), true),
+			None => match Self::path()
+			{
+				Some(p) => (p, false),
+				None => return Ok(()),
+			},
+		};
+
+		let text = match fs::read_to_string(&path)
+		{
+			Ok(text) => text,
+			Err(err) if err.kind() == ErrorKind::NotFound && !required => return Ok(()),
+			Err(err) => return Err(Error::File { err, path }),
+		};
+
+		let config = toml::from_str(&text).map_err(|err| Error::Config { err, path })?;
+		CONFIG.get_or_init(|| config);
+		Ok(())
+	}
+
+	/// # Summary
+	///
+	/// Where the [`Config`] is read from by default: `sanctum-solver/config.toml` in
+	/// `$XDG_CONFIG_HOME`, or `~/.config`.
+	pub fn path() -> Option<PathBuf>
+	{
+		env::var_os("XDG_CONFIG_HOME")
+			.filter(|dir| !dir.is_empty())
+			.map(PathBuf::from)
+			.or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+			.map(|dir| dir.join("sanctum-solver").join("config.toml"))
+	}
+
+	/// # Summary
+	///
+	/// Whether a switch is on, given whether its flag (`on`) or its `--no-` flag (`off`) was passed
+	/// on the command line, and whether it is `configured` on.
+	///
+	/// # Remarks
+	///
+	/// The two flags override each other, so at most one of them is passed.
+	pub fn switch(on: bool, off: bool, configured: bool) -> bool
+	{
+		on || (configured && !off)
+	}
+}
@@ -0,0 +1,317 @@
+mod api;
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	net::TcpListener,
+	path::{Path, PathBuf},
+	sync::{mpsc, Arc, Mutex},
+	thread,
+	time::{Duration, SystemTime},
+};
+
+use structopt::StructOpt;
+
+use super::{
+	cancellation,
+	compression::Compression,
+	error::{Error, Result},
+	hook::Hooks,
+	read_map,
+	solve::Options,
+	write_value,
+};
+
+/// # Summary
+///
+/// How long a job must go unmodified before it is solved, so that one which is still being
+/// written is not read half-finished.
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// # Summary
+///
+/// The jobs which a [`Daemon`] has already seen.
+#[derive(Debug, Default)]
+struct Seen
+{
+	/// # Summary
+	///
+	/// The jobs which failed, and when each was last modified at the time. A job is tried again
+	/// once it has been modified since.
+	failed: HashMap<PathBuf, SystemTime>,
+
+	/// # Summary
+	///
+	/// The jobs which have been handed to a worker.
+	queued: HashSet<PathBuf>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Daemon
+{
+	#[structopt(help = "The maximum number of blocks to place in each map", long, short)]
+	blocks: Option<usize>,
+
+	#[structopt(default_value = "1", help = "How many jobs to solve at the same time", long)]
+	concurrency: usize,
+
+	#[structopt(flatten)]
+	hooks: Hooks,
+
+	#[structopt(
+		help = "An address (e.g. `127.0.0.1:8080`) to accept jobs at over HTTP. Maps are POSTed \
+		        to `/jobs/<name>`, and read back from `/results/<name>` once solved",
+		long
+	)]
+	listen: Option<String>,
+
+	#[structopt(help = "Solve the jobs which are already queued, and then exit", long)]
+	once: bool,
+
+	#[structopt(flatten)]
+	options: Options,
+
+	#[structopt(
+		default_value = "5",
+		help = "How many seconds to wait between checks for new jobs",
+		long
+	)]
+	poll_interval: u64,
+
+	#[structopt(help = "A directory to watch for map JSON files to solve")]
+	jobs: PathBuf,
+
+	#[structopt(help = "A directory to save each solved map to, under the same name as its job")]
+	results: PathBuf,
+}
+
+impl Daemon
+{
+	/// # Summary
+	///
+	/// Watch the `jobs` directory, solving each map which appears in it until stopped.
+	pub fn run(self) -> Result<()>
+	{
+		fs::create_dir_all(&self.results)
+			.map_err(|err| Error::File { err, path: self.results.clone() })?;
+
+		if let Some(address) = self.listen.as_ref()
+		{
+			let listener = TcpListener::bind(address)?;
+			let (jobs, results) = (self.jobs.clone(), self.results.clone());
+			thread::spawn(move || api::serve(listener, &jobs, &results));
+		}
+
+		let daemon = Arc::new(self);
+		let seen = Arc::new(Mutex::new(Seen::default()));
+		let (sender, receiver) = mpsc::channel::<PathBuf>();
+		let receiver = Arc::new(Mutex::new(receiver));
+
+		let workers: Vec<_> = (0..daemon.concurrency.max(1))
+			.map(|_| {
+				let daemon = Arc::clone(&daemon);
+				let receiver = Arc::clone(&receiver);
+				let seen = Arc::clone(&seen);
+				thread::spawn(move || {
+					loop
+					{
+						let job = match receiver.lock().expect("Expected no worker to panic").recv()
+						{
+							Ok(job) => job,
+							Err(_) => break,
+						};
+
+						if let Err(err) = daemon.solve(&job)
+						{
+							eprintln!("{}: {}", job.display(), err);
+
+							let mut seen = seen.lock().expect("Expected no worker to panic");
+							seen.queued.remove(&job);
+							if let Some(modified) = modified(&job)
+							{
+								seen.failed.insert(job, modified);
+							}
+						}
+
+						// Ctrl-C was pressed, so leave the rest of the queue for next time.
+						if cancellation().is_cancelled()
+						{
+							break;
+						}
+					}
+				})
+			})
+			.collect();
+
+		loop
+		{
+			let (pending, unsettled) = {
+				let mut seen = seen.lock().expect("Expected no worker to panic");
+				let (pending, unsettled) = daemon.pending(&seen, SystemTime::now())?;
+				seen.queued.extend(pending.iter().cloned());
+				(pending, unsettled)
+			};
+
+			pending
+				.into_iter()
+				.for_each(|job| sender.send(job).expect("Expected a worker to be running"));
+
+			if cancellation().is_cancelled()
+			{
+				break;
+			}
+
+			// Jobs which were still being written are waited for, even when running `--once`.
+			if daemon.once
+			{
+				if unsettled == 0
+				{
+					break;
+				}
+
+				thread::sleep(SETTLE);
+				continue;
+			}
+
+			thread::sleep(Duration::from_secs(daemon.poll_interval));
+		}
+
+		drop(sender);
+		workers.into_iter().for_each(|worker| worker.join().expect("Expected no worker to panic"));
+
+		Ok(())
+	}
+
+	/// # Summary
+	///
+	/// The jobs which have not been `seen` or solved before, oldest first.
+	///
+	/// # Remarks
+	///
+	/// Only `.json` files (which may be compressed) are jobs, so others (e.g. `map.json.tmp`) can
+	/// be written first, then renamed once they are finished. Jobs which were modified less than
+	/// [`SETTLE`] before `now` may still be being written, so they are left for later.
+	///
+	/// # Returns
+	///
+	/// The jobs to solve, and how many were left for later.
+	fn pending(&self, seen: &Seen, now: SystemTime) -> Result<(Vec<PathBuf>, usize)>
+	{
+		let mut pending = Vec::new();
+		let mut unsettled = 0;
+		for entry in
+			fs::read_dir(&self.jobs).map_err(|err| Error::File { err, path: self.jobs.clone() })?
+		{
+			let entry = entry?;
+			let path = entry.path();
+
+			if Compression::inner_extension(&path).is_none_or(|ext| ext != "json") ||
+				seen.queued.contains(&path) ||
+				self.result_of(&path).exists()
+			{
+				continue;
+			}
+
+			let modified = entry.metadata()?.modified()?;
+			if seen.failed.get(&path) == Some(&modified)
+			{
+				continue;
+			}
+
+			if now.duration_since(modified).unwrap_or_default() < SETTLE
+			{
+				unsettled += 1;
+				continue;
+			}
+
+			pending.push((modified, path));
+		}
+
+		pending.sort_unstable();
+		Ok((pending.into_iter().map(|(_, path)| path).collect(), unsettled))
+	}
+
+	/// # Summary
+	///
+	/// Where the solution to a `job` is saved.
+	fn result_of(&self, job: &Path) -> PathBuf
+	{
+		self.results.join(job.file_name().expect("Expected jobs to be files"))
+	}
+
+	/// # Summary
+	///
+	/// Solve a single `job`, save its result, and fire the [`Hooks`].
+	fn solve(&self, job: &Path) -> Result<()>
+	{
+		let (map, _) = self.options.solve(read_map(job)?, self.blocks, |_| ())?;
+
+		let result = self.result_of(job);
+		write_value(Some(&result), &map)?;
+		eprintln!("Solved {}", job.display());
+
+		self.hooks.fire(Some(&result))
+	}
+}
+
+/// # Summary
+///
+/// When the file at some `path` was last modified, if it can be read.
+fn modified(path: &Path) -> Option<SystemTime>
+{
+	fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{
+		env,
+		fs::{self, File},
+		path::PathBuf,
+		time::{Duration, SystemTime},
+	};
+
+	use structopt::StructOpt;
+
+	use super::{Daemon, Seen, SETTLE};
+
+	#[test]
+	fn pending()
+	{
+		let dir: PathBuf = env::temp_dir().join(format!("sanctum-daemon-{}", std::process::id()));
+		let (jobs, results) = (dir.join("jobs"), dir.join("results"));
+		fs::create_dir_all(&jobs).unwrap();
+		fs::create_dir_all(&results).unwrap();
+
+		let daemon =
+			Daemon::from_iter(["daemon", jobs.to_str().unwrap(), results.to_str().unwrap()]);
+		let now = SystemTime::now();
+		let write = |name: &str, age: Duration| {
+			let path = jobs.join(name);
+			fs::write(&path, "{}").unwrap();
+			File::options().write(true).open(&path).unwrap().set_modified(now - age).unwrap();
+			path
+		};
+
+		let old = write("old.json", SETTLE * 3);
+		let settled = write("settled.json", SETTLE * 2);
+		write("writing.json", Duration::ZERO);
+		write("renamed-later.json.tmp", SETTLE * 2);
+
+		// The job which is still being written is left for later, and the temporary file is not
+		// a job at all.
+		let mut seen = Seen::default();
+		assert_eq!(daemon.pending(&seen, now).unwrap(), (vec![old.clone(), settled.clone()], 1));
+
+		// A job which failed is only tried again once it has changed.
+		seen.failed.insert(old.clone(), now - SETTLE * 3);
+		seen.queued.insert(settled.clone());
+		assert_eq!(daemon.pending(&seen, now).unwrap(), (vec![], 1));
+
+		write("old.json", SETTLE * 2);
+		assert_eq!(daemon.pending(&seen, now).unwrap(), (vec![old], 1));
+
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
@@ -0,0 +1,256 @@
+use std::{
+	fs,
+	io::{self, BufRead, BufReader, Read, Write},
+	net::{TcpListener, TcpStream},
+	path::Path,
+	time::Duration,
+};
+
+use sanctum_solver::map::Map;
+use serde_json::json;
+
+/// # Summary
+///
+/// The largest map which may be submitted, so that a single request can't fill the disk.
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// # Summary
+///
+/// How long a client may take to send its request, or to read the response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// # Summary
+///
+/// Answer requests to the `listener` one at a time, until the program exits:
+///
+/// * `POST /jobs/<name>`, with a map as the body, queues it as `<name>.json` in the `jobs`
+///   directory.
+/// * `GET /results/<name>` gives back the solved map, once it has been saved to `results`.
+///
+/// # Remarks
+///
+/// Each job is written to a `.tmp` file and then renamed, so the daemon never picks up a job which
+/// is only half written.
+pub fn serve(listener: TcpListener, jobs: &Path, results: &Path)
+{
+	listener.incoming().flatten().for_each(|stream| {
+		if let Err(err) = respond(stream, jobs, results)
+		{
+			eprintln!("HTTP: {}", err);
+		}
+	});
+}
+
+/// # Summary
+///
+/// Whether a job `name` is safe to use as a file name in the `jobs` directory.
+fn is_name(name: &&str) -> bool
+{
+	!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// # Summary
+///
+/// Read one request from the `stream`, and answer it.
+fn respond(mut stream: TcpStream, jobs: &Path, results: &Path) -> io::Result<()>
+{
+	stream.set_read_timeout(Some(TIMEOUT))?;
+	stream.set_write_timeout(Some(TIMEOUT))?;
+
+	let mut reader = BufReader::new(&stream);
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+
+	let mut content_length = 0;
+	loop
+	{
+		let mut header = String::new();
+		if reader.read_line(&mut header)? == 0 || header.trim().is_empty()
+		{
+			break;
+		}
+
+		if let Some((name, value)) = header.split_once(':')
+		{
+			if name.trim().eq_ignore_ascii_case("content-length")
+			{
+				content_length = value.trim().parse().unwrap_or(0);
+			}
+		}
+	}
+
+	let request: Vec<_> = request_line.split_whitespace().take(2).collect();
+	let (status, body) = match request[..]
+	{
+		["POST", target] => match target.strip_prefix("/jobs/").filter(is_name)
+		{
+			Some(_) if content_length > MAX_BODY =>
+			{
+				error(413, format!("A map may be at most {} bytes", MAX_BODY))
+			},
+			Some(name) =>
+			{
+				let mut map = vec![0; content_length];
+				reader.read_exact(&mut map)?;
+				submit(jobs, results, name, &map)?
+			},
+			None => error(404, "Maps are submitted to `/jobs/<name>`".into()),
+		},
+		["GET", target] => match target.strip_prefix("/results/").filter(is_name)
+		{
+			Some(name) => result(jobs, results, name)?,
+			None => error(404, "Results are read from `/results/<name>`".into()),
+		},
+		_ => error(405, "Only `POST /jobs/<name>` and `GET /results/<name>` are supported".into()),
+	};
+
+	let reason = match status
+	{
+		200 => "OK",
+		202 => "Accepted",
+		400 => "Bad Request",
+		404 => "Not Found",
+		409 => "Conflict",
+		413 => "Payload Too Large",
+		_ => "Method Not Allowed",
+	};
+
+	write!(
+		stream,
+		"HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+		 close\r\n\r\n",
+		status,
+		reason,
+		body.len()
+	)?;
+	stream.write_all(&body)
+}
+
+/// # Summary
+///
+/// A response with some `status` which explains what went wrong.
+fn error(status: u16, message: String) -> (u16, Vec<u8>)
+{
+	(status, json!({ "error": message }).to_string().into_bytes())
+}
+
+/// # Summary
+///
+/// The solved map of the job `name`, or whether it is still queued.
+fn result(jobs: &Path, results: &Path, name: &str) -> io::Result<(u16, Vec<u8>)>
+{
+	let file = format!("{}.json", name);
+	let result = results.join(&file);
+
+	if result.exists()
+	{
+		return Ok((200, fs::read(result)?));
+	}
+
+	if jobs.join(&file).exists()
+	{
+		return Ok((202, json!({ "job": name, "status": "queued" }).to_string().into_bytes()));
+	}
+
+	Ok(error(404, format!("There is no job named {:?}", name)))
+}
+
+/// # Summary
+///
+/// Queue a `map` as the job `name`, unless there already is one by that name.
+fn submit(jobs: &Path, results: &Path, name: &str, map: &[u8]) -> io::Result<(u16, Vec<u8>)>
+{
+	if let Err(err) = Map::from_json(&String::from_utf8_lossy(map))
+	{
+		return Ok(error(400, err.to_string()));
+	}
+
+	let file = format!("{}.json", name);
+	let job = jobs.join(&file);
+	if job.exists() || results.join(&file).exists()
+	{
+		return Ok(error(409, format!("There is already a job named {:?}", name)));
+	}
+
+	let temp = jobs.join(format!("{}.tmp", file));
+	fs::write(&temp, map)?;
+	fs::rename(&temp, &job)?;
+
+	Ok((202, json!({ "job": name, "status": "queued" }).to_string().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{
+		env,
+		fs,
+		io::{Read, Write},
+		net::{TcpListener, TcpStream},
+		path::PathBuf,
+		thread,
+	};
+
+	use super::serve;
+
+	/// # Summary
+	///
+	/// Send a raw HTTP `request` to the `address`, and read the whole response.
+	fn send(address: &str, request: &str) -> String
+	{
+		let mut stream = TcpStream::connect(address).unwrap();
+		stream.write_all(request.as_bytes()).unwrap();
+
+		let mut response = String::new();
+		stream.read_to_string(&mut response).unwrap();
+		response
+	}
+
+	#[test]
+	fn submit_and_fetch()
+	{
+		let dir: PathBuf = env::temp_dir().join(format!("sanctum-api-{}", std::process::id()));
+		let (jobs, results) = (dir.join("jobs"), dir.join("results"));
+		fs::create_dir_all(&jobs).unwrap();
+		fs::create_dir_all(&results).unwrap();
+
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		{
+			let (jobs, results) = (jobs.clone(), results.clone());
+			thread::spawn(move || serve(listener, &jobs, &results));
+		}
+
+		let map = r#"{"name": "park", "grid": [["Spawn", "Empty", "Core"]]}"#;
+		let post = |name: &str, body: &str| {
+			send(
+				&address,
+				&format!(
+					"POST /jobs/{} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+					name,
+					body.len(),
+					body
+				),
+			)
+		};
+
+		// The job is renamed into place, so no temporary file is left for the daemon to find.
+		assert!(post("park", map).starts_with("HTTP/1.1 202"));
+		assert_eq!(fs::read_to_string(jobs.join("park.json")).unwrap(), map);
+		assert!(!jobs.join("park.json.tmp").exists());
+
+		assert!(post("park", map).starts_with("HTTP/1.1 409"));
+		assert!(post("broken", "{").starts_with("HTTP/1.1 400"));
+		assert!(post("..%2Fescape", map).starts_with("HTTP/1.1 404"));
+
+		let get = |name: &str| send(&address, &format!("GET /results/{} HTTP/1.1\r\n\r\n", name));
+		assert!(get("park").starts_with("HTTP/1.1 202"));
+		assert!(get("missing").starts_with("HTTP/1.1 404"));
+
+		fs::write(results.join("park.json"), "{}").unwrap();
+		assert!(get("park").starts_with("HTTP/1.1 200"));
+		assert!(get("park").ends_with("\r\n\r\n{}"));
+
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
@@ -0,0 +1,118 @@
+use std::{
+	io::{self, Write},
+	time::{Duration, Instant},
+};
+
+use sanctum_solver::map::Progress;
+
+/// # Summary
+///
+/// How often the [`Dashboard`] is redrawn.
+const REFRESH_RATE: Duration = Duration::from_millis(100);
+
+/// # Summary
+///
+/// A status view for long solves which is redrawn in place on `stderr`.
+pub struct Dashboard
+{
+	latest: Progress,
+	last_drawn: Option<Instant>,
+	lines_drawn: usize,
+	max_blocks: Option<usize>,
+	start: Instant,
+}
+
+impl Dashboard
+{
+	/// # Summary
+	///
+	/// Redraw the dashboard with the latest `progress`, unless it was drawn very recently.
+	pub fn draw(&mut self, progress: &Progress)
+	{
+		self.latest.clone_from(progress);
+
+		let now = Instant::now();
+		if self.last_drawn.map(|last| now.duration_since(last) < REFRESH_RATE).unwrap_or(false)
+		{
+			return;
+		}
+
+		self.last_drawn = Some(now);
+		self.force_draw(now);
+	}
+
+	/// # Summary
+	///
+	/// Draw the latest [`Progress`], regardless of when the dashboard was last drawn.
+	pub fn finish(&mut self)
+	{
+		self.force_draw(Instant::now());
+	}
+
+	/// # Summary
+	///
+	/// Replace the previously drawn lines with the latest [`Progress`].
+	fn force_draw(&mut self, now: Instant)
+	{
+		let progress = &self.latest;
+		let elapsed = now.duration_since(self.start).as_secs_f64();
+		let per_second = |count: usize| if elapsed > 0.0 { count as f64 / elapsed } else { 0.0 };
+
+		let mut lines = vec![
+			format!("elapsed     {:.1}s", elapsed),
+			format!("blocks      {}", progress.blocks),
+			format!(
+				"iterations  {} ({:.1}/s)",
+				progress.iterations,
+				per_second(progress.iterations)
+			),
+			format!(
+				"eta         {}",
+				self.max_blocks
+					.filter(|_| progress.blocks > 0)
+					.map(|max| format!(
+						"{:.1}s",
+						max.saturating_sub(progress.blocks) as f64 / per_second(progress.blocks)
+					))
+					.unwrap_or_else(|| String::from("unknown")),
+			),
+		];
+
+		progress.best_path_length.iter().enumerate().for_each(|(region, len)| {
+			lines.push(format!(
+				"region {:<4} {}",
+				region,
+				len.map(|l| l.to_string()).unwrap_or_else(|| String::from("-"))
+			))
+		});
+
+		let mut stderr = io::stderr();
+
+		// Move back up to the first line drawn last time, then overwrite everything.
+		if self.lines_drawn > 0
+		{
+			let _ = write!(stderr, "\x1b[{}A", self.lines_drawn);
+		}
+
+		lines.iter().for_each(|line| {
+			let _ = writeln!(stderr, "\x1b[2K{}", line);
+		});
+
+		self.lines_drawn = lines.len();
+	}
+
+	/// # Summary
+	///
+	/// Create a new [`Dashboard`], which will estimate the time remaining if there are
+	/// `max_blocks`.
+	pub fn new(max_blocks: Option<usize>) -> Self
+	{
+		Self {
+			latest: Progress::default(),
+			last_drawn: None,
+			lines_drawn: 0,
+			max_blocks,
+			start: Instant::now(),
+		}
+	}
+}
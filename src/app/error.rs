@@ -1,10 +1,77 @@
-use std::{io, result::Result as StdResult};
+use std::{
+	io,
+	path::{Path, PathBuf},
+	process::ExitStatus,
+	result::Result as StdResult,
+};
 
+use sanctum_solver::{
+	map::{build, tileset, Warning},
+	solve,
+};
 use snafu::Snafu;
 
 #[derive(Debug, Snafu)]
 pub enum Error
 {
+	#[snafu(display("{}", err))]
+	Build
+	{
+		err: build::Error
+	},
+
+	#[snafu(display("{}", err))]
+	Cache
+	{
+		err: rusqlite::Error
+	},
+
+	#[snafu(display("{}: {}", path.display(), err))]
+	Config
+	{
+		err: toml::de::Error, path: PathBuf
+	},
+
+	#[snafu(display(
+		"{} and {} are not the same map, so their builds cannot be compared",
+		before.display(),
+		after.display()
+	))]
+	DifferentMaps
+	{
+		after: PathBuf, before: PathBuf
+	},
+
+	#[snafu(display("Could not download {}: {}", url, reason))]
+	Download
+	{
+		reason: String, url: String
+	},
+
+	#[snafu(display("{}: {}", path.display(), err))]
+	File
+	{
+		err: io::Error, path: PathBuf
+	},
+
+	#[snafu(display("`{}` failed with {}", command, status))]
+	Hook
+	{
+		command: String, status: ExitStatus
+	},
+
+	#[snafu(display("{}", err))]
+	Image
+	{
+		err: image::ImageError
+	},
+
+	#[snafu(display("The build has {} problem(s)", problems))]
+	InvalidBuild
+	{
+		problems: usize
+	},
+
 	#[snafu(display("{}", err))]
 	Io
 	{
@@ -16,6 +83,123 @@ pub enum Error
 	{
 		err: serde_json::Error
 	},
+
+	#[snafu(display("{}", err))]
+	MessagePackDecode
+	{
+		err: rmp_serde::decode::Error
+	},
+
+	#[snafu(display("{}", err))]
+	MessagePackEncode
+	{
+		err: rmp_serde::encode::Error
+	},
+
+	#[snafu(display("Could not find a grid of tiles in {}", path.display()))]
+	NoGrid
+	{
+		path: PathBuf
+	},
+
+	#[snafu(display("{} self-test case(s) failed", failures))]
+	Selftest
+	{
+		failures: usize
+	},
+
+	#[snafu(display("{}", err))]
+	Solve
+	{
+		err: solve::Error
+	},
+
+	#[snafu(display("{}: {}\n{}", path.display(), err, snippet))]
+	Syntax
+	{
+		err: serde_json::Error, path: PathBuf, snippet: String
+	},
+
+	#[snafu(display("{}: {}", path.display(), warning))]
+	Strict
+	{
+		path: PathBuf, warning: Warning
+	},
+
+	#[snafu(display("{}", err))]
+	Tileset
+	{
+		err: tileset::Error
+	},
+
+	#[snafu(display("There is no output format named {:?}", name))]
+	UnknownFormat
+	{
+		name: String
+	},
+
+	#[snafu(display("Could not watch {}: {}", path.display(), err))]
+	Watch
+	{
+		err: notify::Error, path: PathBuf
+	},
+
+	#[snafu(display("Could not notify {}: {}", url, reason))]
+	Webhook
+	{
+		reason: String, url: String
+	},
+}
+
+impl Error
+{
+	/// # Summary
+	///
+	/// An [`Error::Syntax`] for some JSON `text` read from `path`, with the line the `err` is
+	/// on.
+	pub fn syntax(path: &Path, text: &str, err: serde_json::Error) -> Self
+	{
+		Self::Syntax { snippet: snippet(text, err.line(), err.column()), err, path: path.into() }
+	}
+}
+
+impl From<build::Error> for Error
+{
+	fn from(err: build::Error) -> Self
+	{
+		Self::Build { err }
+	}
+}
+
+impl From<solve::Error> for Error
+{
+	fn from(err: solve::Error) -> Self
+	{
+		match err
+		{
+			solve::Error::Build { err } => Self::Build { err },
+			solve::Error::Tileset { err } => Self::Tileset { err },
+			err @ (solve::Error::Constraints { .. } |
+			solve::Error::NotStepwise { .. } |
+			solve::Error::TimeSlice { .. }) => Self::Solve { err },
+		}
+	}
+}
+
+impl From<rusqlite::Error> for Error
+{
+	fn from(err: rusqlite::Error) -> Self
+	{
+		Self::Cache { err }
+	}
+}
+
+impl From<image::ImageError> for Error
+{
+	fn from(err: image::ImageError) -> Self
+	{
+		Self::Image { err }
+	}
 }
 
 impl From<io::Error> for Error
@@ -34,4 +218,76 @@ impl From<serde_json::Error> for Error
 	}
 }
 
+impl From<rmp_serde::decode::Error> for Error
+{
+	fn from(err: rmp_serde::decode::Error) -> Self
+	{
+		Self::MessagePackDecode { err }
+	}
+}
+
+impl From<rmp_serde::encode::Error> for Error
+{
+	fn from(err: rmp_serde::encode::Error) -> Self
+	{
+		Self::MessagePackEncode { err }
+	}
+}
+
+impl From<tileset::Error> for Error
+{
+	fn from(err: tileset::Error) -> Self
+	{
+		Self::Tileset { err }
+	}
+}
+
 pub type Result<T> = StdResult<T, Error>;
+
+/// # Summary
+///
+/// Show the `line` of some `text` (counting from one) with a `^` under the `column`.
+///
+/// # Remarks
+///
+/// Long lines (e.g. of minified JSON) are cut down to the characters around the `column`.
+fn snippet(text: &str, line: usize, column: usize) -> String
+{
+	const CONTEXT: usize = 40;
+
+	let source: Vec<_> = match text.lines().nth(line.saturating_sub(1))
+	{
+		Some(source) => source.chars().collect(),
+		None => return String::new(),
+	};
+
+	let column = column.saturating_sub(1).min(source.len());
+	let (start, end) = (column.saturating_sub(CONTEXT), (column + CONTEXT).min(source.len()));
+
+	// Keep tabs under the caret too, so that it lines up however wide they are shown.
+	let indent: String = source[start..column]
+		.iter()
+		.map(|c| {
+			if *c == '\t'
+			{
+				'\t'
+			}
+			else
+			{
+				' '
+			}
+		})
+		.collect();
+	let number = line.to_string();
+
+	format!(
+		"{} | {}{}{}\n{} | {}{}^",
+		number,
+		if start > 0 { "..." } else { "" },
+		source[start..end].iter().collect::<String>(),
+		if end < source.len() { "..." } else { "" },
+		" ".repeat(number.len()),
+		if start > 0 { "   " } else { "" },
+		indent,
+	)
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::Encoding;
+use structopt::StructOpt;
+
+use super::{error::Result, escape, parse_delimiter, read_map, write_text};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Export
+{
+	#[structopt(
+		default_value = "numeric",
+		help = "How to write each tile: as its character, number, or name",
+		long,
+		possible_values = &["compact", "numeric", "verbose"],
+		short
+	)]
+	cells: Encoding,
+
+	#[structopt(
+		default_value = ",",
+		help = "The character between tiles, or `tab`",
+		long,
+		parse(try_from_str = parse_delimiter),
+		short
+	)]
+	delimiter: char,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout")]
+	map_json: PathBuf,
+}
+
+impl Export
+{
+	/// # Summary
+	///
+	/// Write the grid of the map with one row per line.
+	pub fn run(self) -> Result<()>
+	{
+		let map = read_map(&self.map_json)?;
+
+		let lines: Vec<_> = map
+			.grid
+			.iter()
+			.map(|row| {
+				row.iter()
+					.map(|tile| {
+						let cell = match self.cells
+						{
+							Encoding::Compact => char::from(*tile).to_string(),
+							Encoding::Numeric => tile.number().to_string(),
+							// A spreadsheet has a cell for every tile, so it cannot be sparse.
+							Encoding::Sparse | Encoding::Verbose => format!("{:?}", tile),
+						};
+
+						escape(&cell, self.delimiter)
+					})
+					.collect::<Vec<_>>()
+					.join(&self.delimiter.to_string())
+			})
+			.collect();
+
+		write_text(self.output.as_deref(), &lines.join("\n"))
+	}
+}
@@ -0,0 +1,124 @@
+use std::{fmt, io::ErrorKind};
+
+use rusqlite::ErrorCode;
+use sanctum_solver::map::{build, tileset};
+use serde::Serialize;
+use serde_json::error::Category;
+
+use super::error::Error;
+
+/// # Summary
+///
+/// The kind of problem which stopped the program, so that scripts can tell them apart by exit
+/// status.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Failure
+{
+	/// # Summary
+	///
+	/// A map (or other input) could not be read, or does not make sense.
+	BadMap,
+
+	/// # Summary
+	///
+	/// A file or connection could not be used.
+	Io,
+
+	/// # Summary
+	///
+	/// Some spawn region cannot reach a core.
+	NoPath,
+
+	/// # Summary
+	///
+	/// Anything else.
+	Other,
+
+	/// # Summary
+	///
+	/// Something took too long, such as waiting for the `--cache` to be unlocked.
+	Timeout,
+}
+
+impl Failure
+{
+	/// # Summary
+	///
+	/// The exit status of the program when it stops because of this [`Failure`].
+	pub fn code(&self) -> i32
+	{
+		match self
+		{
+			Failure::BadMap => 2,
+			Failure::Io => 5,
+			Failure::NoPath => 3,
+			Failure::Other => 1,
+			Failure::Timeout => 4,
+		}
+	}
+}
+
+impl fmt::Display for Failure
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			Failure::BadMap => "bad-map",
+			Failure::Io => "io",
+			Failure::NoPath => "no-path",
+			Failure::Other => "other",
+			Failure::Timeout => "timeout",
+		})
+	}
+}
+
+impl From<&Error> for Failure
+{
+	fn from(err: &Error) -> Self
+	{
+		let io = |kind: ErrorKind| match kind
+		{
+			ErrorKind::TimedOut | ErrorKind::WouldBlock => Failure::Timeout,
+			_ => Failure::Io,
+		};
+
+		match err
+		{
+			Error::Build { err } => match err
+			{
+				build::Error::InvalidCode { .. } | build::Error::OutOfBounds { .. } =>
+				{
+					Failure::BadMap
+				},
+				build::Error::NoPathToCore { .. } => Failure::NoPath,
+				_ => Failure::Other,
+			},
+			Error::Cache { err } => match err.sqlite_error_code()
+			{
+				Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => Failure::Timeout,
+				_ => Failure::Io,
+			},
+			Error::DifferentMaps { .. } |
+			Error::Image { .. } |
+			Error::InvalidBuild { .. } |
+			Error::MessagePackDecode { .. } |
+			Error::NoGrid { .. } => Failure::BadMap,
+			Error::Download { .. } | Error::Watch { .. } => Failure::Io,
+			Error::File { err, .. } | Error::Io { err } => io(err.kind()),
+			Error::Json { err } => match err.classify()
+			{
+				Category::Io => Failure::Io,
+				_ => Failure::BadMap,
+			},
+			Error::Strict { .. } | Error::Syntax { .. } => Failure::BadMap,
+			Error::Tileset { err } => match err
+			{
+				tileset::Error::Unreachable { .. } => Failure::NoPath,
+				_ => Failure::BadMap,
+			},
+			_ => Failure::Other,
+		}
+	}
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{build::Error as BuildError, Build, Encoding};
+use structopt::StructOpt;
+
+use super::{error::Result, read_map, to_value, write_value};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Fmt
+{
+	#[structopt(
+		help = "Place the blocks of a build code (see `solve --build-code`) on the map",
+		long
+	)]
+	build_code: Option<String>,
+
+	#[structopt(
+		default_value = "compact",
+		help = "How to write the grid",
+		long,
+		possible_values = &["compact", "numeric", "sparse", "verbose"],
+		short
+	)]
+	encoding: Encoding,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A JSON file containing the map layout")]
+	map_json: PathBuf,
+}
+
+impl Fmt
+{
+	/// # Summary
+	///
+	/// Write the map back out in a normalized form, so that revisions of it can be compared.
+	pub fn run(self) -> Result<()>
+	{
+		let mut map = read_map(&self.map_json)?;
+		map.pad();
+
+		if let Some(code) = &self.build_code
+		{
+			let (build, size) = Build::from_code(code)?;
+			let width = map.grid.first().map(Vec::len).unwrap_or(0);
+			if size != (width, map.grid.len())
+			{
+				return Err(BuildError::InvalidCode { code: code.clone() }.into());
+			}
+
+			build.apply_to(&mut map.grid)?;
+		}
+		self.encoding.apply_to(&mut map);
+
+		// Going through a `Value` sorts the fields of every object by name.
+		write_value(self.output.as_deref(), &to_value(&map)?)
+	}
+}
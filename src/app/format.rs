@@ -0,0 +1,119 @@
+use std::{fmt, str::FromStr};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+	error::{Error, Result},
+	failure::Failure,
+};
+
+/// # Summary
+///
+/// How an error which stops the program is written to `stderr`.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ErrorFormat
+{
+	/// # Summary
+	///
+	/// Its message, for people to read.
+	#[default]
+	Human,
+
+	/// # Summary
+	///
+	/// An object with its exit status, kind of [`Failure`], and message, for scripts to read.
+	Json,
+}
+
+impl ErrorFormat
+{
+	/// # Summary
+	///
+	/// Write some `err` to `stderr`.
+	pub fn print(&self, err: &Error)
+	{
+		let failure = Failure::from(err);
+		match self
+		{
+			ErrorFormat::Human => eprintln!("error: {}", err),
+			ErrorFormat::Json => eprintln!(
+				"{}",
+				json!({"code": failure.code(), "kind": failure, "message": err.to_string()})
+			),
+		}
+	}
+}
+
+impl fmt::Display for ErrorFormat
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			ErrorFormat::Human => "human",
+			ErrorFormat::Json => "json",
+		})
+	}
+}
+
+impl FromStr for ErrorFormat
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"human" => ErrorFormat::Human,
+			"json" => ErrorFormat::Json,
+			_ => return Err(Error::UnknownFormat { name: name.into() }),
+		})
+	}
+}
+
+/// # Summary
+///
+/// How the output of an analysis is written.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum Format
+{
+	/// # Summary
+	///
+	/// One row per item, with a header, for use in spreadsheets.
+	Csv,
+
+	/// # Summary
+	///
+	/// Pretty-printed JSON.
+	#[default]
+	Json,
+}
+
+impl fmt::Display for Format
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			Format::Csv => "csv",
+			Format::Json => "json",
+		})
+	}
+}
+
+impl FromStr for Format
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"csv" => Format::Csv,
+			"json" => Format::Json,
+			_ => return Err(Error::UnknownFormat { name: name.into() }),
+		})
+	}
+}
@@ -0,0 +1,217 @@
+use std::{
+	io::{BufRead, BufReader, Write},
+	net::{TcpStream, ToSocketAddrs},
+	path::Path,
+	process::Command,
+	time::Duration,
+};
+
+use serde_json::json;
+use structopt::StructOpt;
+
+use super::error::{Error, Result};
+
+/// # Summary
+///
+/// How long a [`Hooks::webhook`] may take to connect, or to send or answer the request, before it
+/// fails.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// # Summary
+///
+/// Ways to be notified when a long-running command finishes.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Hooks
+{
+	#[structopt(
+		help = "A shell command to run once finished. The path of the output is passed as `$1`, \
+		        and is empty when writing to `stdout`",
+		long
+	)]
+	pub on_complete: Option<String>,
+
+	#[structopt(help = "An `http://` URL to POST `{\"output\": path}` to once finished", long)]
+	pub webhook: Option<String>,
+}
+
+impl Hooks
+{
+	/// # Summary
+	///
+	/// Run every hook which was specified, now that the `output` has been written.
+	pub fn fire(&self, output: Option<&Path>) -> Result<()>
+	{
+		let output = output.map(|o| o.display().to_string()).unwrap_or_default();
+
+		if let Some(command) = self.on_complete.as_ref()
+		{
+			let status =
+				Command::new("sh").args(["-c", command, "sanctum-solver", &output]).status()?;
+
+			if !status.success()
+			{
+				return Err(Error::Hook { command: command.clone(), status });
+			}
+		}
+
+		if let Some(url) = self.webhook.as_ref()
+		{
+			post(url, &json!({ "output": output }).to_string(), WEBHOOK_TIMEOUT)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// # Summary
+///
+/// Send a JSON `body` to an `http://` `url`, and check that the server accepted it.
+///
+/// # Remarks
+///
+/// Only the status line of the response is read, so a server which keeps the connection open
+/// does not stall the program. Each of connecting, writing, and reading gives up after the
+/// `timeout`.
+fn post(url: &str, body: &str, timeout: Duration) -> Result<()>
+{
+	let invalid = |reason: &str| Error::Webhook { reason: reason.into(), url: url.into() };
+
+	let rest = url.strip_prefix("http://").ok_or_else(|| invalid("only `http://` is supported"))?;
+	let (authority, path) = rest.find('/').map_or((rest, "/"), |i| rest.split_at(i));
+	let (host, port) =
+		split_authority(authority).ok_or_else(|| invalid("the host or port is not valid"))?;
+
+	let address = (host.trim_start_matches('[').trim_end_matches(']'), port.unwrap_or(80))
+		.to_socket_addrs()?
+		.next()
+		.ok_or_else(|| invalid("the host has no address"))?;
+	let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+	stream.set_read_timeout(Some(timeout))?;
+	stream.set_write_timeout(Some(timeout))?;
+	write!(
+		stream,
+		"POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: \
+		 {}\r\nConnection: close\r\n\r\n{}",
+		path,
+		authority,
+		body.len(),
+		body
+	)?;
+
+	let mut status = String::new();
+	BufReader::new(stream).read_line(&mut status)?;
+
+	let accepted = status
+		.split_whitespace()
+		.nth(1)
+		.and_then(|code| code.parse::<u16>().ok())
+		.is_some_and(|code| (200..300).contains(&code));
+
+	if !accepted
+	{
+		return Err(invalid(
+			status.lines().next().filter(|s| !s.is_empty()).unwrap_or("no response"),
+		));
+	}
+
+	Ok(())
+}
+
+/// # Summary
+///
+/// Split the `authority` of a URL into its host (keeping the brackets of an IPv6 address) and its
+/// port, if it has one.
+///
+/// # Returns
+///
+/// [`None`] if the port is not a number, or an IPv6 address is not closed.
+fn split_authority(authority: &str) -> Option<(&str, Option<u16>)>
+{
+	let (host, port) = match authority.strip_prefix('[')
+	{
+		Some(address) =>
+		{
+			let (host, rest) = authority.split_at(address.find(']')? + 2);
+			match rest
+			{
+				"" => (host, None),
+				_ => (host, Some(rest.strip_prefix(':')?)),
+			}
+		},
+		None => authority.rsplit_once(':').map_or((authority, None), |(h, p)| (h, Some(p))),
+	};
+
+	Some((host, port.map(str::parse).transpose().ok()?))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{
+		io::{BufRead, BufReader, Write},
+		net::TcpListener,
+		sync::mpsc,
+		thread,
+		time::Duration,
+	};
+
+	use super::{post, split_authority};
+
+	/// # Summary
+	///
+	/// A server which answers one request with some `status` line (if any), then holds the
+	/// connection open until the returned sender is dropped.
+	fn serve(status: Option<&'static str>) -> (String, mpsc::Sender<()>)
+	{
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}/hook", listener.local_addr().unwrap());
+		let (done, wait) = mpsc::channel::<()>();
+
+		thread::spawn(move || {
+			let (stream, _) = listener.accept().unwrap();
+			let mut reader = BufReader::new(stream);
+			let mut line = String::new();
+			while reader.read_line(&mut line).unwrap() > 2
+			{
+				line.clear();
+			}
+
+			if let Some(s) = status
+			{
+				write!(reader.get_mut(), "{}\r\n\r\n", s).unwrap();
+			}
+			let _ = wait.recv();
+		});
+
+		(url, done)
+	}
+
+	#[test]
+	fn post_reads_status_line()
+	{
+		let (url, _done) = serve(Some("HTTP/1.1 204 No Content"));
+		assert!(post(&url, "{}", Duration::from_secs(5)).is_ok());
+
+		let (url, _done) = serve(Some("HTTP/1.1 500 Internal Server Error"));
+		assert!(post(&url, "{}", Duration::from_secs(5)).is_err());
+	}
+
+	#[test]
+	fn post_times_out()
+	{
+		// The server never answers, and never closes the connection.
+		let (url, _done) = serve(None);
+		assert!(post(&url, "{}", Duration::from_millis(200)).is_err());
+	}
+	#[test]
+	fn split_authority_with_ports()
+	{
+		assert_eq!(split_authority("example.com"), Some(("example.com", None)));
+		assert_eq!(split_authority("example.com:8080"), Some(("example.com", Some(8080))));
+		assert_eq!(split_authority("[::1]"), Some(("[::1]", None)));
+		assert_eq!(split_authority("[::1]:8080"), Some(("[::1]", Some(8080))));
+		assert_eq!(split_authority("[::1"), None);
+		assert_eq!(split_authority("[::1]8080"), None);
+		assert_eq!(split_authority("example.com:http"), None);
+	}
+}
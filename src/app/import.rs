@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use sanctum_solver::map::{Encoding, Map, Tile};
+use serde_json::{json, Value};
+use structopt::StructOpt;
+
+use super::{check_strict, compression, error::Result, parse_delimiter, to_value, write_value};
+
+/// # Summary
+///
+/// Split a `line` of a delimited file into its fields, removing any quotes around them.
+fn split(line: &str, delimiter: char) -> Vec<String>
+{
+	let mut fields = vec![String::new()];
+	let mut chars = line.chars().peekable();
+	let mut quoted = false;
+
+	while let Some(c) = chars.next()
+	{
+		match c
+		{
+			'"' if quoted && chars.peek() == Some(&'"') =>
+			{
+				chars.next();
+				fields.last_mut().expect("Expected a field").push('"');
+			},
+			'"' => quoted = !quoted,
+			c if c == delimiter && !quoted => fields.push(String::new()),
+			c => fields.last_mut().expect("Expected a field").push(c),
+		}
+	}
+
+	fields
+}
+
+/// # Summary
+///
+/// The [`Tile`] named by a `cell` of a delimited file, which may be a character or number from
+/// the defaults (see [`char::from`] and [`Tile::number`]), or a name.
+///
+/// # Remarks
+///
+/// Empty cells are [`Tile::Impass`], since spreadsheets often leave walls blank. Cells which are
+/// not any [`Tile`] are kept as-is, so that reading the [`Map`] can warn about them.
+fn to_tile(cell: &str) -> Value
+{
+	let cell = cell.trim();
+	let mut chars = cell.chars();
+
+	let tile = match (cell.parse::<usize>(), chars.next(), chars.next())
+	{
+		(_, None, _) => Some(Tile::Impass),
+		(Ok(number), ..) => Tile::from_number(number).ok(),
+		(_, Some(code), None) => Tile::try_from(code).ok(),
+		_ => None,
+	};
+
+	tile.map(|t| json!(t)).unwrap_or_else(|| cell.into())
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Import
+{
+	#[structopt(
+		default_value = ",",
+		help = "The character between tiles, or `tab`",
+		long,
+		parse(try_from_str = parse_delimiter),
+		short
+	)]
+	delimiter: char,
+
+	#[structopt(
+		default_value = "compact",
+		help = "How to write the grid",
+		long,
+		possible_values = &["compact", "numeric", "sparse", "verbose"],
+		short
+	)]
+	encoding: Encoding,
+
+	#[structopt(help = "The name of the map. Defaults to the name of the file", long, short)]
+	name: Option<String>,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "A file with one row of the grid per line, and a character, number, or \
+	                    name for each tile")]
+	grid: PathBuf,
+}
+
+impl Import
+{
+	/// # Summary
+	///
+	/// Read the grid, and write it out as a map.
+	pub fn run(self) -> Result<()>
+	{
+		let bytes = compression::read(&self.grid)?;
+		let text = String::from_utf8_lossy(&bytes);
+		let rows: Vec<Vec<_>> = text
+			.trim_end()
+			.lines()
+			.map(|line| split(line, self.delimiter).iter().map(|cell| to_tile(cell)).collect())
+			.collect();
+
+		let name = self.name.clone().unwrap_or_else(|| {
+			self.grid.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+		});
+
+		let mut map: Map = serde_json::from_value(json!({"name": name, "grid": rows}))?;
+		map = check_strict(&self.grid, map)?;
+		self.encoding.apply_to(&mut map);
+
+		// Going through a `Value` sorts the fields of every object by name.
+		write_value(self.output.as_deref(), &to_value(&map)?)
+	}
+}
@@ -0,0 +1,100 @@
+use std::{fs, path::PathBuf};
+
+use sanctum_solver::map::{
+	screenshot::{Palette, Screenshot},
+	Encoding,
+	Map,
+};
+use serde_json::json;
+use structopt::StructOpt;
+
+use super::{
+	check_strict,
+	error::{Error, Result},
+	to_value,
+	write_value,
+};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct ImportScreenshot
+{
+	#[structopt(
+		default_value = "compact",
+		help = "How to write the grid",
+		long,
+		possible_values = &["compact", "numeric", "sparse", "verbose"],
+		short
+	)]
+	encoding: Encoding,
+
+	#[structopt(default_value = "64", help = "The largest tile size to look for, in pixels", long)]
+	max_tile_size: usize,
+
+	#[structopt(default_value = "8", help = "The smallest tile size to look for, in pixels", long)]
+	min_tile_size: usize,
+
+	#[structopt(help = "The name of the map. Defaults to the name of the file", long, short)]
+	name: Option<String>,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(
+		help = "A JSON file of the `[red, green, blue]` color of each tile, e.g. `{\"Core\": [60, \
+		        150, 230]}`",
+		long,
+		short
+	)]
+	palette: Option<PathBuf>,
+
+	#[structopt(help = "A PNG or JPEG of the map, seen from above")]
+	screenshot: PathBuf,
+}
+
+impl ImportScreenshot
+{
+	/// # Summary
+	///
+	/// Find the tiles of the screenshot, and write them out as a map.
+	pub fn run(self) -> Result<()>
+	{
+		let palette = match &self.palette
+		{
+			Some(path) =>
+			{
+				let text = fs::read_to_string(path)
+					.map_err(|err| Error::File { err, path: path.clone() })?;
+				serde_json::from_str(&text).map_err(|err| Error::syntax(path, &text, err))?
+			},
+			None => Palette::default(),
+		};
+
+		let image = image::open(&self.screenshot)?.into_rgb8();
+		let (width, height) = (image.width() as usize, image.height() as usize);
+		let screenshot = Screenshot::new(width, height, image.pixels().map(|p| p.0).collect())
+			.expect("Expected one pixel per coordinate");
+
+		let lattice = screenshot
+			.lattice((self.min_tile_size, self.max_tile_size))
+			.ok_or_else(|| Error::NoGrid { path: self.screenshot.clone() })?;
+		let grid = screenshot.tiles(&lattice, &palette);
+
+		let name = self.name.clone().unwrap_or_else(|| {
+			self.screenshot
+				.file_stem()
+				.map(|s| s.to_string_lossy().into_owned())
+				.unwrap_or_default()
+		});
+
+		let mut map: Map = serde_json::from_value(json!({"name": name, "grid": grid}))?;
+		map = check_strict(&self.screenshot, map)?;
+		self.encoding.apply_to(&mut map);
+
+		// Going through a `Value` sorts the fields of every object by name.
+		write_value(self.output.as_deref(), &to_value(&map)?)
+	}
+}
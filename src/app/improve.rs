@@ -0,0 +1,158 @@
+use std::{cmp::Reverse, path::PathBuf};
+
+use sanctum_solver::map::{
+	tileset::TilesetBuilder,
+	Build,
+	Coordinate,
+	Map,
+	Objective,
+	RegionResult,
+	SolverSession,
+};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use super::{config::Config, error::Result, read_map, write_value};
+
+/// # Summary
+///
+/// A map whose build was [improved](Improve), and what was done to it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Improvement
+{
+	/// # Summary
+	///
+	/// The blocks which were placed, in reading order.
+	added: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The map with the improved build placed.
+	map: Map,
+
+	/// # Summary
+	///
+	/// The blocks which were taken away, in reading order.
+	removed: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The [`Objective::score`] of the build before and after it was improved.
+	score: (usize, usize),
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path before the build was improved. The lengths
+	/// after are in the `map`.
+	shortest_path_length: Vec<Option<usize>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Improve
+{
+	#[structopt(help = "The maximum number of blocks the build may have", long, short)]
+	blocks: Option<usize>,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "The most, as a percentage, that any region's path may be shorter than the \
+		        longest. Blocks which only lengthen a path beyond that are removed",
+		long
+	)]
+	fairness: Option<usize>,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(
+		help = "Prefer builds which lose less path length when a single block is removed, even if \
+		        they are a little shorter",
+		long
+	)]
+	robust: bool,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Improve
+{
+	/// # Summary
+	///
+	/// Keep placing blocks on the map's build, and then remove those which do not lengthen any
+	/// path.
+	///
+	/// # Remarks
+	///
+	/// The build is only ever replaced by one with a better (or equal, with fewer blocks)
+	/// [`Objective::score`], so the output is never worse than the input.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let original = Build::take_from(&mut map.grid);
+
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+		let objective = Objective {
+			fairness: self.fairness,
+			formula: None,
+			hazard_damage: map.hazard_damage.unwrap_or(1),
+			robust: self.robust,
+		};
+
+		let mut session =
+			SolverSession::resume(&tileset, original.clone(), diagonals, self.blocks)?;
+		while session.step().is_some()
+		{}
+
+		let mut build = session.into_build();
+		build.minimize(&tileset, &objective, diagonals);
+
+		let rank = |b: &Build| (objective.score(&tileset, b, diagonals), Reverse(b.blocks.len()));
+		let before = rank(&original);
+		if rank(&build) < before
+		{
+			build = original.clone();
+		}
+
+		let sorted = |blocks: &mut Vec<Coordinate>| blocks.sort_unstable_by_key(|c| (c.1, c.0));
+		let mut added: Vec<_> = build.blocks.difference(&original.blocks).copied().collect();
+		let mut removed: Vec<_> = original.blocks.difference(&build.blocks).copied().collect();
+		sorted(&mut added);
+		sorted(&mut removed);
+
+		let shortest_path_length = original.shortest_path_lengths(&tileset, diagonals);
+		let score = (before.0, rank(&build).0);
+		map.regions = RegionResult::of(&tileset, &build, diagonals);
+		map.shortest_path_length = Some(map.regions.iter().map(|r| r.final_length).collect());
+		map.grid = tileset.grid;
+		map.metadata = tileset.metadata;
+		build.apply_to(&mut map.grid)?;
+
+		write_value(self.output.as_deref(), &Improvement {
+			added,
+			map,
+			removed,
+			score,
+			shortest_path_length,
+		})
+	}
+}
@@ -0,0 +1,133 @@
+use std::{path::PathBuf, result::Result as StdResult};
+
+use sanctum_solver::map::{
+	tileset::TilesetBuilder,
+	Build,
+	Map,
+	RegionResult,
+	Wave,
+	WavePlan,
+	WaveSchedule,
+};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use super::{config::Config, error::Result, read_map, write_value};
+
+/// # Summary
+///
+/// A [`WavePlan`] for a map, along with the map once every wave's blocks are placed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Schedule
+{
+	/// # Summary
+	///
+	/// The map with the finished build placed.
+	map: Map,
+
+	waves: Vec<Wave>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Plan
+{
+	#[structopt(
+		help = "When a region starts spawning, as `region=wave` counted from 0 (e.g. `1=3`). Regions \
+		        which are not listed spawn from the first wave",
+		long,
+		parse(try_from_str = parse_activation),
+		use_delimiter = true
+	)]
+	activate: Vec<(usize, usize)>,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		help = "How many blocks are gained before each wave, separated by commas (e.g. `10,4,4`)",
+		long,
+		required = true,
+		use_delimiter = true
+	)]
+	income: Vec<usize>,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(
+		default_value = "100",
+		help = "The percentage of a block's cost which is given back when it is sold",
+		long
+	)]
+	refund: usize,
+
+	#[structopt(
+		help = "The most blocks which may be sold before each wave to make room for better ones. \
+		        If not specified, blocks are only sold once they no longer lengthen any path",
+		long
+	)]
+	sells: Option<usize>,
+
+	#[structopt(help = "A JSON file containing the map layout. Any `Block`s are ignored")]
+	map_json: PathBuf,
+}
+
+impl Plan
+{
+	/// # Summary
+	///
+	/// Plan which blocks to place before each wave, so that the build is as long as it can be at
+	/// every wave. Blocks may be sold between waves to reshape the build as new regions start
+	/// spawning.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		Build::take_from(&mut map.grid);
+
+		let tileset = TilesetBuilder::from_map(&map, diagonals).build()?;
+
+		let schedule = WaveSchedule {
+			active_from: self.activate.into_iter().collect(),
+			refund: self.refund,
+			sells: self.sells,
+			..WaveSchedule::new(self.income)
+		};
+
+		let plan = WavePlan::of(&tileset, diagonals, &schedule)?;
+		map.regions = RegionResult::of(&tileset, &plan.build, diagonals);
+		map.shortest_path_length = Some(map.regions.iter().map(|r| r.final_length).collect());
+		map.grid = tileset.grid;
+		map.metadata = tileset.metadata;
+		plan.build.apply_to(&mut map.grid)?;
+
+		write_value(self.output.as_deref(), &Schedule { map, waves: plan.waves })
+	}
+}
+
+/// # Summary
+///
+/// Read an `--activate` option, which is a region and the wave it starts spawning in.
+fn parse_activation(activation: &str) -> StdResult<(usize, usize), String>
+{
+	activation
+		.split_once('=')
+		.and_then(|(region, wave)| Some((region.trim().parse().ok()?, wave.trim().parse().ok()?)))
+		.ok_or_else(|| format!("{:?} is not of the form `region=wave`", activation))
+}
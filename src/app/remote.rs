@@ -0,0 +1,124 @@
+use std::{
+	env,
+	fs,
+	path::{Path, PathBuf},
+	process,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{
+	cache::hash,
+	error::{Error, Result},
+};
+
+/// # Summary
+///
+/// The directory which downloaded maps are kept in: `sanctum-solver/maps` in `$XDG_CACHE_HOME`,
+/// or `~/.cache`.
+fn directory() -> Option<PathBuf>
+{
+	env::var_os("XDG_CACHE_HOME")
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+		.map(|dir| dir.join("sanctum-solver").join("maps"))
+}
+
+/// # Summary
+///
+/// Get the contents of some `url`.
+#[cfg(feature = "remote")]
+fn download(url: &str) -> Result<Vec<u8>>
+{
+	let failed = |err: reqwest::Error| Error::Download { reason: err.to_string(), url: url.into() };
+	let response =
+		reqwest::blocking::get(url).and_then(|r| r.error_for_status()).map_err(failed)?;
+	Ok(response.bytes().map_err(failed)?.to_vec())
+}
+
+/// # Summary
+///
+/// Get the contents of some `url`.
+#[cfg(not(feature = "remote"))]
+fn download(url: &str) -> Result<Vec<u8>>
+{
+	Err(Error::Download {
+		reason: "this copy of sanctum-solver was built without the `remote` feature".into(),
+		url: url.into(),
+	})
+}
+
+/// # Summary
+///
+/// Download the map at some `url` into the [`directory`], unless it was downloaded before and
+/// there is no need to `refresh` it.
+///
+/// # Returns
+///
+/// Where the map was saved.
+///
+/// # Remarks
+///
+/// The file keeps the name from the `url`, so that compressed maps are still recognized. It is
+/// downloaded to a temporary file first, and then renamed, so that a download which fails (or
+/// another process reading the map at the same time) never sees half of it.
+pub fn fetch(url: &str, refresh: bool) -> Result<PathBuf>
+{
+	/// # Summary
+	///
+	/// How many downloads this process has started, so that each has its own temporary file.
+	static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+
+	let name = file_name(url);
+	let directory = directory().ok_or_else(|| Error::Download {
+		reason: "there is nowhere to save it; set $XDG_CACHE_HOME or $HOME".into(),
+		url: url.into(),
+	})?;
+	let path = directory.join(format!("{}-{}", hash(url.as_bytes()), name));
+
+	if refresh || !path.exists()
+	{
+		let bytes = download(url)?;
+		fs::create_dir_all(&directory)
+			.map_err(|err| Error::File { err, path: directory.clone() })?;
+
+		let download = DOWNLOADS.fetch_add(1, Ordering::Relaxed);
+		let temp = directory.join(format!(".{}.{}.{}.tmp", name, process::id(), download));
+		fs::write(&temp, bytes).map_err(|err| Error::File { err, path: temp.clone() })?;
+		fs::rename(&temp, &path).map_err(|err| Error::File { err, path: path.clone() })?;
+	}
+
+	Ok(path)
+}
+
+/// # Summary
+///
+/// The name of the file at some `url`, without any `?query` or `#fragment`.
+fn file_name(url: &str) -> &str
+{
+	let path = url.split(['?', '#']).next().unwrap_or(url);
+	path.rsplit('/').next().filter(|name| !name.is_empty()).unwrap_or("map.json")
+}
+
+/// # Summary
+///
+/// Whether some `path` given on the command line is actually an `http(s)://` URL.
+pub fn is_url(path: &Path) -> bool
+{
+	path.to_str().is_some_and(|p| p.starts_with("http://") || p.starts_with("https://"))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::file_name;
+
+	#[test]
+	fn file_names()
+	{
+		assert_eq!(file_name("https://example.com/maps/park.json.gz"), "park.json.gz");
+		assert_eq!(file_name("https://example.com/maps/park.json?token=a/b#top"), "park.json");
+		assert_eq!(file_name("https://example.com/maps/"), "map.json");
+		assert_eq!(file_name("https://example.com/maps/?park.json"), "map.json");
+	}
+}
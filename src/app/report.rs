@@ -0,0 +1,333 @@
+use std::{fmt::Write, fs, path::Path};
+
+use sanctum_solver::map::{
+	screenshot::{Palette, Rgb},
+	tileset::TilesetBuilder,
+	Build,
+	Coordinate,
+	DistanceField,
+	Map,
+	Metric,
+	Progress,
+	ShortestPath,
+	Tile,
+};
+use serde_json::json;
+
+use super::error::{Error, Result};
+
+/// # Summary
+///
+/// The color of a [`Tile::Block`], which is not in the default [`Palette`].
+const BLOCK: Rgb = [140, 70, 20];
+
+/// # Summary
+///
+/// How many pixels wide and tall each tile is drawn.
+const CELL: usize = 20;
+
+/// # Summary
+///
+/// The colors of each region's path, in turn.
+const PATHS: [&str; 6] = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4"];
+
+/// # Summary
+///
+/// A standalone HTML page describing a solve, so that it can be shared with people who do not use
+/// the command line.
+#[derive(Clone, Debug, Default)]
+pub struct Report
+{
+	/// # Summary
+	///
+	/// The [`Progress`] each time the number of blocks changed.
+	history: Vec<Progress>,
+}
+
+impl Report
+{
+	/// # Summary
+	///
+	/// Remember some `progress`, if it placed or removed a block since the last one.
+	pub fn record(&mut self, progress: &Progress)
+	{
+		match self.history.last_mut()
+		{
+			Some(last) if last.blocks == progress.blocks => last.clone_from(progress),
+			_ => self.history.push(progress.clone()),
+		}
+	}
+
+	/// # Summary
+	///
+	/// Write the report of a solved `map`, which has some `build` placed, to `path`.
+	///
+	/// # Parameters
+	///
+	/// * `diagonals` and `metric`, which the `map` was solved with.
+	pub fn write(
+		&self,
+		path: &Path,
+		map: &Map,
+		build: &Build,
+		diagonals: bool,
+		metric: Metric,
+	) -> Result<()>
+	{
+		let mut unbuilt = map.clone();
+		Build::take_from(&mut unbuilt.grid);
+		let tileset = TilesetBuilder::from_map(&unbuilt, diagonals).metric(metric).build()?;
+
+		let field = DistanceField::to_coordinates(
+			&tileset.grid,
+			Some(&build.blocks),
+			tileset.core_coordinates().iter().copied(),
+			diagonals,
+		);
+		let paths =
+			ShortestPath::from_entrances_to_any_core(&tileset, Some(&build.blocks), diagonals);
+
+		let mut html = String::new();
+		let _ = writeln!(html, "<!DOCTYPE html>");
+		let _ = writeln!(
+			html,
+			"<html><head><meta charset=\"utf-8\"><title>{}</title>",
+			escape(&map.name)
+		);
+		let _ = writeln!(
+			html,
+			"<style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}td,\
+			 th{{border:1px solid #ccc;padding:.2em .6em;text-align:right}}</style>"
+		);
+		let _ = writeln!(html, "</head><body>\n<h1>{}</h1>", escape(&map.name));
+
+		let _ = writeln!(html, "<h2>Build</h2>\n{}", build_svg(&map.grid, &paths));
+		let _ = writeln!(html, "<h2>Paths</h2>\n{}", path_table(map, &paths));
+		let _ = writeln!(html, "<h2>Distance to core</h2>\n{}", heatmap_svg(&map.grid, &field));
+
+		if !map.warnings.is_empty()
+		{
+			let _ = writeln!(html, "<h2>Warnings</h2>\n<ul>");
+			map.warnings.iter().for_each(|w| {
+				let _ = writeln!(html, "<li>{}</li>", escape(&w.to_string()));
+			});
+			let _ = writeln!(html, "</ul>");
+		}
+
+		let _ = writeln!(html, "<h2>Telemetry</h2>\n{}", history_svg(&self.history));
+
+		// Keep the raw data too, so that it can be pulled back out of the page.
+		let telemetry = json!({"history": self.history, "stats": map.stats});
+		let _ = writeln!(
+			html,
+			"<script id=\"telemetry\" type=\"application/json\">{}</script>",
+			telemetry.to_string().replace("</", "<\\/")
+		);
+		let _ = writeln!(html, "</body></html>");
+
+		fs::write(path, html).map_err(|err| Error::File { err, path: path.into() })
+	}
+}
+
+/// # Summary
+///
+/// Draw each tile of the `grid`, with the `paths` on top.
+fn build_svg(grid: &[Vec<Tile>], paths: &[Option<ShortestPath>]) -> String
+{
+	let palette = Palette::default();
+	let mut svg = svg_start(grid);
+
+	cells(grid).for_each(|(coord, tile)| {
+		let [r, g, b] = match tile
+		{
+			Tile::Block | Tile::TowerBlock => BLOCK,
+			t => palette.0.get(&t).copied().unwrap_or_default(),
+		};
+		let _ = writeln!(
+			svg,
+			"{}",
+			rect(coord, &format!("rgb({},{},{})", r, g, b), &format!("{:?}", tile))
+		);
+	});
+
+	paths.iter().enumerate().for_each(|(region, path)| {
+		if let Some(p) = path
+		{
+			let points: Vec<_> = p
+				.iter()
+				.map(|coord| format!("{},{}", coord.0 * CELL + CELL / 2, coord.1 * CELL + CELL / 2))
+				.collect();
+			let _ = writeln!(
+				svg,
+				"<polyline fill=\"none\" points=\"{}\" stroke=\"{}\" \
+				 stroke-width=\"3\"><title>region {}</title></polyline>",
+				points.join(" "),
+				PATHS[region % PATHS.len()],
+				region
+			);
+		}
+	});
+
+	svg + "</svg>"
+}
+
+/// # Summary
+///
+/// Every tile of a `grid`, with its [`Coordinate`].
+fn cells(grid: &[Vec<Tile>]) -> impl Iterator<Item = (Coordinate, Tile)> + '_
+{
+	grid.iter()
+		.enumerate()
+		.flat_map(|(y, row)| row.iter().enumerate().map(move |(x, tile)| (Coordinate(x, y), *tile)))
+}
+
+/// # Summary
+///
+/// Replace the characters of `text` which have a meaning in HTML.
+fn escape(text: &str) -> String
+{
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// # Summary
+///
+/// Draw how far each tile of the `grid` is from a core, from red (near) to green (far).
+fn heatmap_svg(grid: &[Vec<Tile>], field: &DistanceField) -> String
+{
+	let max = field.max().unwrap_or(0).max(1);
+	let mut svg = svg_start(grid);
+
+	cells(grid).for_each(|(coord, tile)| {
+		let rect = match field.get(&coord)
+		{
+			Some(d) => rect(coord, &format!("hsl({},80%,50%)", d * 120 / max), &d.to_string()),
+			None => rect(coord, "#333", &format!("{:?}", tile)),
+		};
+		let _ = writeln!(svg, "{}", rect);
+	});
+
+	svg + "</svg>"
+}
+
+/// # Summary
+///
+/// Chart the total length of every path against the number of blocks placed, over the `history`
+/// of a solve.
+fn history_svg(history: &[Progress]) -> String
+{
+	const HEIGHT: usize = 200;
+	const WIDTH: usize = 600;
+
+	let totals: Vec<_> = history
+		.iter()
+		.map(|p| (p.blocks, p.shortest_path_length.iter().flatten().sum::<usize>()))
+		.collect();
+	let max_blocks = totals.iter().map(|(b, _)| *b).max().unwrap_or(0).max(1);
+	let max_length = totals.iter().map(|(_, l)| *l).max().unwrap_or(0).max(1);
+
+	let points: Vec<_> = totals
+		.iter()
+		.map(|(blocks, length)| {
+			format!("{},{}", blocks * WIDTH / max_blocks, HEIGHT - length * HEIGHT / max_length)
+		})
+		.collect();
+
+	format!(
+		"<svg height=\"{h}\" viewBox=\"-40 -10 {w} {h}\" width=\"{w}\" \
+		 xmlns=\"http://www.w3.org/2000/svg\">\n\
+		 <line stroke=\"#999\" x1=\"0\" x2=\"0\" y1=\"0\" y2=\"{height}\"/>\n\
+		 <line stroke=\"#999\" x1=\"0\" x2=\"{width}\" y1=\"{height}\" y2=\"{height}\"/>\n\
+		 <text font-size=\"12\" text-anchor=\"end\" x=\"-4\" y=\"10\">{max_length}</text>\n\
+		 <text font-size=\"12\" text-anchor=\"end\" x=\"{width}\" y=\"{label}\">{max_blocks} \
+		 blocks</text>\n\
+		 <polyline fill=\"none\" points=\"{points}\" stroke=\"#4363d8\" stroke-width=\"2\"/>\n</svg>",
+		h = HEIGHT + 30,
+		height = HEIGHT,
+		label = HEIGHT + 15,
+		max_blocks = max_blocks,
+		max_length = max_length,
+		points = points.join(" "),
+		w = WIDTH + 50,
+		width = WIDTH,
+	)
+}
+
+/// # Summary
+///
+/// A table of where each region's path starts and ends, and how long it is.
+fn path_table(map: &Map, paths: &[Option<ShortestPath>]) -> String
+{
+	let classes = map.stats.as_ref().map(|s| &s.shortest_path_length_by_class);
+
+	let mut table =
+		String::from("<table>\n<tr><th>Region</th><th>Length</th><th>Entrance</th><th>Core</th>");
+	classes.into_iter().flat_map(|c| c.keys()).for_each(|class| {
+		let _ = write!(table, "<th>{}</th>", class);
+	});
+	table.push_str("</tr>\n");
+
+	let show = |length: Option<usize>| length.map_or_else(|| "-".into(), |l| l.to_string());
+	paths.iter().enumerate().for_each(|(region, path)| {
+		let (length, entrance, core) = match path
+		{
+			Some(p) =>
+			{
+				let (start, end) = (p.coordinates()[0], p.core());
+				(
+					Some(p.len()),
+					format!("({}, {})", start.0, start.1),
+					format!("({}, {})", end.0, end.1),
+				)
+			},
+			None => (None, "-".into(), "-".into()),
+		};
+
+		let _ = write!(
+			table,
+			"<tr><td style=\"color:{}\">{}</td><td>{}</td><td>{}</td><td>{}</td>",
+			PATHS[region % PATHS.len()],
+			region,
+			show(length),
+			entrance,
+			core
+		);
+		classes.into_iter().flat_map(|c| c.values()).for_each(|lengths| {
+			let _ = write!(table, "<td>{}</td>", show(lengths.get(region).copied().flatten()));
+		});
+		table.push_str("</tr>\n");
+	});
+
+	table + "</table>"
+}
+
+/// # Summary
+///
+/// A square for the tile at `coord`, filled with some `color`, which shows a `title` on hover.
+fn rect(coord: Coordinate, color: &str, title: &str) -> String
+{
+	format!(
+		"<rect fill=\"{}\" height=\"{}\" width=\"{}\" x=\"{}\" y=\"{}\"><title>({}, {}) \
+		 {}</title></rect>",
+		color,
+		CELL,
+		CELL,
+		coord.0 * CELL,
+		coord.1 * CELL,
+		coord.0,
+		coord.1,
+		escape(title)
+	)
+}
+
+/// # Summary
+///
+/// The opening tag of an SVG big enough for the `grid`.
+fn svg_start(grid: &[Vec<Tile>]) -> String
+{
+	let (width, height) = (grid.iter().map(Vec::len).max().unwrap_or(0) * CELL, grid.len() * CELL);
+	format!(
+		"<svg height=\"{h}\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+		h = height,
+		w = width
+	)
+}
@@ -0,0 +1,190 @@
+use std::{collections::BTreeMap, fs, ops::RangeInclusive};
+
+use sanctum_solver::map::{Build, Map, Strategy};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+
+use super::{
+	error::{Error, Result},
+	solve::Options,
+};
+
+/// # Summary
+///
+/// A map which is solved by [`Selftest`], and the lengths its paths are known to reach.
+struct Case
+{
+	/// # Summary
+	///
+	/// The range each spawn region's shortest path should be within, with and without diagonal
+	/// movement.
+	bounds: [&'static [RangeInclusive<usize>]; 2],
+
+	json: &'static str,
+}
+
+/// # Summary
+///
+/// What solving one of the [`CASES`] with some [`Strategy`] gave when it was last
+/// [blessed](Selftest::bless).
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Golden
+{
+	build: Build,
+	shortest_path_length: Vec<Option<usize>>,
+}
+
+/// # Summary
+///
+/// The maps which are built into the binary.
+///
+/// # Remarks
+///
+/// The bounds are loose, so that a change to the solver which makes paths slightly shorter (or
+/// longer) is still accepted. The [`GOLDEN`] results are what catch any change at all.
+const CASES: [Case; 3] = [
+	Case {
+		bounds: [&[22..=35, 34..=55], &[14..=25, 14..=25]],
+		json: include_str!("selftest/crossroads.json"),
+	},
+	Case { bounds: [&[30..=50], &[15..=35]], json: include_str!("selftest/lagoon.json") },
+	Case { bounds: [&[60..=90], &[45..=75]], json: include_str!("../../park.json") },
+];
+
+/// # Summary
+///
+/// The [`Golden`] result of each of the [`CASES`], keyed by map name, [`Strategy`], and
+/// movement.
+const GOLDEN: &str = include_str!("selftest/golden.json");
+
+/// # Summary
+///
+/// Where the [`GOLDEN`] results are written by [`Selftest::bless`].
+const GOLDEN_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/app/selftest/golden.json");
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Selftest
+{
+	#[structopt(
+		help = "Record every result as the expected one, rather than checking it. For use from a \
+		        checkout of the source, after a change which is meant to alter results. Rebuild \
+		        the program afterwards so that it checks against them",
+		long
+	)]
+	bless: bool,
+}
+
+impl Selftest
+{
+	/// # Summary
+	///
+	/// Solve each of the [`CASES`] with every [`Strategy`], with and without diagonal movement.
+	/// Check that every path is as long as it should be, and that the build and path lengths are
+	/// the same as the [`GOLDEN`] ones.
+	///
+	/// # Remarks
+	///
+	/// If `--bless` was passed, the results are written to the [`GOLDEN_PATH`] instead of being
+	/// compared.
+	///
+	/// # Errors
+	///
+	/// * [`Error::Selftest`], if any path is out of bounds, or any result differs from the golden
+	///   one.
+	pub fn run(self) -> Result<()>
+	{
+		// Parse no arguments, so that every option has the same default as `solve`.
+		let mut options = Options::from_iter(["selftest"]);
+		let mut golden: BTreeMap<String, Golden> = serde_json::from_str(GOLDEN)?;
+		let mut failures = 0;
+
+		for case in CASES.iter()
+		{
+			for (diagonals, bounds) in [false, true].into_iter().zip(case.bounds)
+			{
+				for strategy in Strategy::PORTFOLIO
+				{
+					let map = Map::from_json(case.json)?;
+					let key = format!(
+						"{} {}{}",
+						map.name,
+						strategy,
+						if diagonals { " (diagonals)" } else { "" }
+					);
+
+					options.diagonals = diagonals;
+					options.strategy = Some(strategy);
+					let (map, build) = options.solve(map, None, |_| ())?;
+					let result = Golden {
+						build,
+						shortest_path_length: map.shortest_path_length.unwrap_or_default(),
+					};
+
+					let mut problems = Vec::new();
+					let within_bounds =
+						result.shortest_path_length.len() == bounds.len() &&
+							result.shortest_path_length.iter().zip(bounds).all(
+								|(length, range)| length.is_some_and(|l| range.contains(&l)),
+							);
+					if strategy == Strategy::default() && !within_bounds
+					{
+						problems.push(format!("expected {:?}", bounds));
+					}
+
+					match golden.get(&key)
+					{
+						_ if self.bless => (),
+						Some(expected) if *expected == result => (),
+						Some(expected) => problems.push(format!(
+							"expected [{}] with the golden build",
+							show(&expected.shortest_path_length)
+						)),
+						None => problems.push("no golden result".into()),
+					}
+
+					if !problems.is_empty()
+					{
+						failures += 1;
+					}
+
+					println!(
+						"{} {}: [{}]{}",
+						if problems.is_empty() { "ok  " } else { "FAIL" },
+						key,
+						show(&result.shortest_path_length),
+						problems.iter().map(|p| format!(", {}", p)).collect::<String>(),
+					);
+
+					if self.bless
+					{
+						golden.insert(key, result);
+					}
+				}
+			}
+		}
+
+		if self.bless
+		{
+			fs::write(GOLDEN_PATH, serde_json::to_string_pretty(&golden)? + "\n")?;
+			println!("blessed {} result(s) in {}", golden.len(), GOLDEN_PATH);
+		}
+
+		match failures
+		{
+			0 => Ok(()),
+			_ => Err(Error::Selftest { failures }),
+		}
+	}
+}
+
+/// # Summary
+///
+/// Show some path `lengths`, writing `none` for those without a path.
+fn show(lengths: &[Option<usize>]) -> String
+{
+	lengths
+		.iter()
+		.map(|length| length.map_or_else(|| "none".into(), |l| l.to_string()))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
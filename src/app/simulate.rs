@@ -0,0 +1,135 @@
+use std::{path::PathBuf, thread, time::Duration};
+
+use sanctum_solver::map::{
+	tileset::TilesetBuilder,
+	Build,
+	EnemyClass,
+	Frame,
+	Map,
+	Simulation,
+	Tile,
+};
+use structopt::StructOpt;
+
+use super::{config::Config, error::Result, read_map, write_value};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Simulate
+{
+	#[structopt(
+		help = "Play the wave back in the terminal instead of writing frames",
+		long,
+		short
+	)]
+	animate: bool,
+
+	#[structopt(
+		default_value = "ground",
+		help = "What kind of enemy to send",
+		long,
+		possible_values = &["flying", "ground"]
+	)]
+	class: EnemyClass,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	diagonals: bool,
+
+	#[structopt(
+		default_value = "1",
+		help = "How many enemies leave each spawn region",
+		long,
+		short
+	)]
+	enemies: usize,
+
+	#[structopt(
+		default_value = "1",
+		help = "How many ticks pass between enemies leaving",
+		long,
+		short
+	)]
+	interval: usize,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	no_diagonals: bool,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(default_value = "250", help = "How long each tick lasts when animating", long)]
+	tick_ms: u64,
+
+	#[structopt(help = "A JSON file containing the map layout, with the build's `Block`s placed")]
+	map_json: PathBuf,
+}
+
+impl Simulate
+{
+	/// # Summary
+	///
+	/// Walk a wave of enemies through the map's build.
+	pub fn run(self) -> Result<()>
+	{
+		let diagonals = Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals);
+		let mut map = read_map(&self.map_json)?;
+		let build = Build::take_from(&mut map.grid);
+		let tileset = TilesetBuilder::from_map(
+			&Map { grid: self.class.view(&map.grid), ..map.clone() },
+			diagonals,
+		)
+		.build()?;
+
+		let simulation = Simulation::new(&tileset, &build, diagonals, self.enemies, self.interval);
+
+		if !self.animate
+		{
+			return write_value(self.output.as_deref(), &simulation.frames().collect::<Vec<_>>());
+		}
+
+		let mut grid = map.grid;
+		build.apply_to(&mut grid)?;
+
+		simulation.frames().for_each(|frame| {
+			// Clear the screen and return to the top left before drawing the next frame.
+			print!("\x1b[2J\x1b[H{}", render(&grid, &frame));
+			thread::sleep(Duration::from_millis(self.tick_ms));
+		});
+
+		Ok(())
+	}
+}
+
+/// # Summary
+///
+/// Draw a `frame` over the `grid`, using the region number of each enemy as its symbol.
+fn render(grid: &[Vec<Tile>], frame: &Frame) -> String
+{
+	let mut rows: Vec<Vec<char>> =
+		grid.iter().map(|row| row.iter().map(|tile| char::from(*tile)).collect()).collect();
+
+	frame.enemies.iter().for_each(|enemy| {
+		let symbol = std::char::from_digit((enemy.region % 10) as u32, 10).unwrap_or('@');
+		enemy.coordinate.set(&mut rows, symbol).expect("Expected enemies to walk on the grid");
+	});
+
+	let mut rendered = format!("tick {}\n", frame.tick);
+	rows.into_iter().for_each(|row| {
+		rendered.extend(row);
+		rendered.push('\n');
+	});
+
+	rendered
+}
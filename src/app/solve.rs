@@ -0,0 +1,625 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	result::Result as StdResult,
+	sync::{atomic::Ordering, mpsc},
+	time::Duration,
+};
+
+use notify::{event::ModifyKind, EventKind, RecursiveMode, Watcher};
+use sanctum_solver::{
+	map::{
+		build,
+		tileset::Tileset,
+		Build,
+		CornerCutting,
+		Formula,
+		Map,
+		Metric,
+		MovementRules,
+		Progress,
+		Strategy,
+		Warning,
+	},
+	solve,
+	SolveOptions,
+	TimeSlice,
+};
+use structopt::StructOpt;
+
+use super::{
+	cache::{Cache, Key},
+	cancellation,
+	config::Config,
+	dashboard::Dashboard,
+	error::{Error, Result},
+	hook::Hooks,
+	read_map,
+	report::Report,
+	to_value,
+	write_value,
+	SOLVING,
+};
+
+/// # Summary
+///
+/// Roughly how much memory a search for a shortest path uses for each tile of the grid: the tile
+/// it was reached from, when it was visited, and its place in the queue. See
+/// [`Options::search_threads`].
+const BYTES_PER_TILE: usize = 64;
+
+/// # Summary
+///
+/// The options which affect how a [`Build`] is found, shared by every command that solves maps.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Options
+{
+	#[structopt(
+		help = "Include a short code for the build, which can be shared, in the stats",
+		long
+	)]
+	pub build_code: bool,
+
+	#[structopt(
+		help = "A database of the best build found for each map. If it has a build for the map, \
+		        that build is used instead of solving",
+		long
+	)]
+	pub cache: Option<PathBuf>,
+
+	#[structopt(
+		help = "Score every tile of the shortest path, and every tile within this many moves of \
+		        it, when choosing where to place each block; instead of placing it on the first \
+		        tile of the path (from the core) which is valid",
+		long
+	)]
+	pub candidate_scope: Option<usize>,
+
+	#[structopt(
+		help = "A JSON file of constraints which every build must follow: tiles to keep \
+		        `reserved` for towers, tiles for paths to `avoid`, and the `budgets` of blocks \
+		        for each region. They are checked against the map before solving",
+		long
+	)]
+	pub constraints: Option<PathBuf>,
+
+	#[structopt(
+		help = "Whether diagonal moves may cut the corner of an impassable tile: `no`, `one-side` \
+		        if the other side is passable (the default), or `yes`",
+		long,
+		possible_values = &["no", "one-side", "yes"]
+	)]
+	pub corner_cutting: Option<CornerCutting>,
+
+	#[structopt(
+		help = "Allow diagonal movement when calculating shortest paths",
+		long,
+		overrides_with = "no-diagonals",
+		short
+	)]
+	pub diagonals: bool,
+
+	#[structopt(
+		help = "Include how many moves each tile is from the nearest core in the output",
+		long
+	)]
+	pub distances: bool,
+
+	#[structopt(
+		help = "Include a copy of the grid with each region's shortest path marked as `Path0`, \
+		        `Path1`, and so on, for drawing the solution",
+		long
+	)]
+	pub display: bool,
+
+	#[structopt(
+		help = "The most, as a percentage, that any region's path may be shorter than the \
+		        longest. Blocks which only lengthen a path beyond that are removed after solving",
+		long
+	)]
+	pub fairness: Option<usize>,
+
+	#[structopt(
+		help = "Keep placing blocks on the build from the `--cache`, rather than using it as-is. \
+		        The blocks are placed one at a time, so only with `--strategy round-robin` (or a \
+		        split of the blocks), and within the `--time-limit` and `budgets`",
+		long,
+		requires = "cache"
+	)]
+	pub improve: bool,
+
+	#[structopt(
+		help = "How to measure the distance from a spawn to its entrances. Defaults to \
+		        `chebyshev` with `--diagonals`, and `manhattan` otherwise",
+		long,
+		possible_values = &["chebyshev", "euclidean", "manhattan"]
+	)]
+	pub metric: Option<Metric>,
+
+	#[structopt(
+		help = "Do not allow diagonal movement, even if the config does",
+		long,
+		overrides_with = "diagonals"
+	)]
+	pub no_diagonals: bool,
+
+	#[structopt(
+		help = "Score builds by a sum of weighted terms rather than by total path length, e.g. \
+		        `2*min_path + overlap - 0.5*turns`. The terms are `blocks`, `hazards`, `length`, \
+		        `max_path`, `min_path`, `overlap`, `score`, and `turns`",
+		long
+	)]
+	pub objective: Option<Formula>,
+
+	#[structopt(
+		help = "After solving, remove every block which does not contribute to path length",
+		long,
+		short
+	)]
+	pub minimize: bool,
+
+	#[structopt(
+		help = "A JSON file of options to solve with, like those in the `stats.options` of a \
+		        solved map. These replace every other option which affects how a build is found, \
+		        except the block limit",
+		long
+	)]
+	pub options: Option<PathBuf>,
+
+	#[structopt(
+		help = "Prefer builds which lose less path length when a single block is removed, even if \
+		        they are a little shorter",
+		long
+	)]
+	pub robust: bool,
+
+	#[structopt(
+		help = "Roughly how much memory, in MiB, the shortest path searches of each solve may \
+		        use. Fewer threads search at once on maps too large for every thread to fit, down \
+		        to one. Nothing else is limited",
+		long
+	)]
+	pub search_memory: Option<usize>,
+
+	#[structopt(
+		help = "Spend the `--blocks` on each region as `analyze budget` recommends, rather than \
+		        wherever they lengthen a path the most",
+		long
+	)]
+	pub split: bool,
+
+	#[structopt(
+		help = "How to place blocks. Defaults to `round-robin`",
+		long,
+		possible_values = &["portfolio", "priority", "round-robin"],
+		short
+	)]
+	pub strategy: Option<Strategy>,
+
+	#[structopt(
+		help = "The most seconds to search for. Once they run out, the best build found so far is \
+		        kept",
+		long
+	)]
+	pub time_limit: Option<u64>,
+
+	#[structopt(
+		help = "How long a strategy of `--strategy portfolio` may search for, as `strategy=time` \
+		        (e.g. `priority=30s` or `round-robin=500ms`). The time may also be a weight, which \
+		        shares the `--time-limit` between strategies (e.g. `priority=7,round-robin=3`)",
+		long,
+		parse(try_from_str = parse_time_slice),
+		use_delimiter = true
+	)]
+	pub time_slice: Vec<(Strategy, TimeSlice)>,
+
+	#[structopt(
+		help = "Write what each step of the solve looked at to this JSON file: how far the search \
+		        for the path spread at each move, the tiles considered for a block, and those \
+		        rejected. Only `--strategy round-robin` (or a split of the blocks) can be traced. \
+		        Slows the solve down",
+		long
+	)]
+	pub trace: Option<PathBuf>,
+}
+
+impl Options
+{
+	/// # Summary
+	///
+	/// Whether to allow diagonal movement, from `--diagonals` or the [`Config`].
+	pub fn diagonals(&self) -> bool
+	{
+		Config::switch(self.diagonals, self.no_diagonals, Config::get().diagonals)
+	}
+
+	/// # Summary
+	///
+	/// The [`Metric`] to measure entrance distances with.
+	pub fn metric(&self) -> Metric
+	{
+		self.metric.unwrap_or_else(|| Metric::for_movement(self.diagonals()))
+	}
+
+	/// # Summary
+	///
+	/// How enemies move, from `--diagonals` and `--corner-cutting` or the [`Config`].
+	pub fn movement(&self) -> MovementRules
+	{
+		MovementRules {
+			corner_cutting: self
+				.corner_cutting
+				.or(Config::get().corner_cutting)
+				.unwrap_or_default(),
+			diagonals: self.diagonals(),
+		}
+	}
+
+	/// # Summary
+	///
+	/// Find a [`Build`] for the `map` using at most `max_blocks`, and apply it.
+	///
+	/// # Remarks
+	///
+	/// The solve stops early if Ctrl-C is pressed (see [`cancellation`]), and its build is not
+	/// cached.
+	///
+	/// With `--trace`, or `--improve` on a cached build, blocks are placed by a
+	/// [`SolveOptions::session`] so that each [`Step`](build::Step) can be written to the trace
+	/// file. This is an error unless the search would place them one at a time anyway.
+	///
+	/// # Returns
+	///
+	/// The solved `map`, and the [`Build`] which was applied to it.
+	pub fn solve(
+		&self,
+		mut map: Map,
+		max_blocks: Option<usize>,
+		mut on_progress: impl FnMut(&Progress) + Send,
+	) -> Result<(Map, Build)>
+	{
+		let options = self.solve_options(max_blocks)?;
+		let tileset = options.tileset(&map)?;
+		let objective = options.objective(&map);
+
+		let cache = self.cache.as_deref().map(Cache::open).transpose()?;
+		let key = Key::new(&tileset.grid, &options, objective.hazard_damage);
+		let cached = match cache.as_ref()
+		{
+			Some(c) => c.get(&key, max_blocks)?,
+			None => None,
+		};
+
+		SOLVING.fetch_add(1, Ordering::Relaxed);
+		let mut steps = Vec::new();
+		let search = || {
+			let session = match cached
+			{
+				Some((cached, _)) if self.improve =>
+				{
+					options.session(&tileset, &objective, Some(cached), cancellation())
+				},
+				Some((cached, _)) => return Ok((cached, Vec::new())),
+				None if self.trace.is_some() =>
+				{
+					options.session(&tileset, &objective, None, cancellation())
+				},
+				None => return options.search(&tileset, &objective, cancellation(), on_progress),
+			};
+
+			session.map(|mut session| {
+				session.set_trace(self.trace.is_some());
+				while let Some(step) = session.step()
+				{
+					on_progress(session.progress());
+					if step.trace.is_some()
+					{
+						steps.push(step);
+					}
+				}
+
+				(session.into_build(), Vec::new())
+			})
+		};
+		let searched = match self.search_threads(&tileset)
+		{
+			Some(threads) => rayon::ThreadPoolBuilder::new()
+				.num_threads(threads)
+				.build()
+				.expect("Expected the thread pool to be built")
+				.install(search),
+			None => search(),
+		};
+		SOLVING.fetch_sub(1, Ordering::Relaxed);
+
+		let (build, strategies) = searched?;
+		if let Some(path) = self.trace.as_ref()
+		{
+			fs::write(path, to_value(&steps)?.to_string())
+				.map_err(|err| Error::File { err, path: path.clone() })?;
+		}
+		let cancelled = cancellation().is_cancelled();
+		if cancelled
+		{
+			map.warnings.push(Warning::Cancelled);
+		}
+
+		let solution = options.finish(map, tileset, &objective, build, strategies)?;
+		if let Some(c) = cache.as_ref().filter(|_| !cancelled)
+		{
+			c.put(&key, &solution.build, solution.score)?;
+		}
+
+		Ok((solution.map, solution.build))
+	}
+
+	/// # Summary
+	///
+	/// The [`SolveOptions`] which these options describe, placing at most `max_blocks`.
+	///
+	/// # Remarks
+	///
+	/// If there is an `--options` file, it is used instead of the other options. Its block limit is
+	/// only kept when no `max_blocks` are given.
+	pub fn solve_options(&self, max_blocks: Option<usize>) -> Result<SolveOptions>
+	{
+		if let Some(path) = self.options.as_ref()
+		{
+			let text =
+				fs::read_to_string(path).map_err(|err| Error::File { err, path: path.clone() })?;
+			let options: SolveOptions =
+				serde_json::from_str(&text).map_err(|err| Error::syntax(path, &text, err))?;
+
+			return Ok(SolveOptions { max_blocks: max_blocks.or(options.max_blocks), ..options });
+		}
+
+		let constraints = self
+			.constraints
+			.as_ref()
+			.map(|path| {
+				let text = fs::read_to_string(path)
+					.map_err(|err| Error::File { err, path: path.clone() })?;
+				serde_json::from_str(&text).map_err(|err| Error::syntax(path, &text, err))
+			})
+			.transpose()?;
+
+		Ok(SolveOptions {
+			build_code: self.build_code,
+			candidate_scope: self.candidate_scope,
+			constraints,
+			distances: self.distances,
+			display: self.display,
+			fairness: self.fairness,
+			formula: self.objective.clone(),
+			max_blocks,
+			metric: Some(self.metric()),
+			minimize: self.minimize,
+			movement: self.movement(),
+			robust: self.robust,
+			split: self.split,
+			strategy: self.strategy(),
+			time_limit: self.time_limit.map(|seconds| seconds * 1000),
+			time_slices: self.time_slice.iter().copied().collect(),
+		})
+	}
+
+	/// # Summary
+	///
+	/// How many threads can search a `tileset` at once while staying within the
+	/// `--search-memory`, if it was given.
+	///
+	/// # Remarks
+	///
+	/// Each thread searches for shortest paths, which takes [`BYTES_PER_TILE`] for every tile of
+	/// the grid. At least one thread is always used, even if it does not fit.
+	fn search_threads(&self, tileset: &Tileset) -> Option<usize>
+	{
+		self.search_memory.map(|mebibytes| {
+			let tiles = tileset.grid.iter().map(Vec::len).sum::<usize>().max(1);
+			let per_thread = tiles.saturating_mul(BYTES_PER_TILE);
+			(mebibytes.saturating_mul(1024 * 1024) / per_thread)
+				.clamp(1, rayon::current_num_threads())
+		})
+	}
+
+	/// # Summary
+	///
+	/// How to place blocks, from `--strategy` or the [`Config`].
+	pub fn strategy(&self) -> Strategy
+	{
+		self.strategy.or(Config::get().strategy).unwrap_or_default()
+	}
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, StructOpt)]
+pub struct Solve
+{
+	#[structopt(help = "The maximum number of blocks to place", long, short)]
+	blocks: Option<usize>,
+
+	#[structopt(help = "Show a live status view of the solve on `stderr`", long)]
+	dashboard: bool,
+
+	#[structopt(flatten)]
+	hooks: Hooks,
+
+	#[structopt(flatten)]
+	options: Options,
+
+	#[structopt(
+		help = "Where to save the output. If not specified, goes to `stdout`",
+		long,
+		short
+	)]
+	output: Option<PathBuf>,
+
+	#[structopt(help = "The most blocks each player may place when splitting the build", long)]
+	player_blocks: Option<usize>,
+
+	#[structopt(help = "Split the build between this many players", long)]
+	players: Option<usize>,
+
+	#[structopt(
+		help = "Also save a standalone HTML page with pictures of the build, for sharing",
+		long
+	)]
+	report: Option<PathBuf>,
+
+	#[structopt(help = "Keep running, and solve the map again each time the file changes", long)]
+	watch: bool,
+
+	#[structopt(help = "A JSON file containing the map layout")]
+	map_json: PathBuf,
+}
+
+impl Solve
+{
+	/// # Summary
+	///
+	/// Find a [`Build`] for the map and write it out. With `--watch`, do so every time the map
+	/// changes, until stopped.
+	pub fn run(self) -> Result<()>
+	{
+		if !self.watch
+		{
+			return self.solve();
+		}
+
+		let watch_error = |err| Error::Watch { err, path: self.map_json.clone() };
+
+		// Editors often save a file by replacing it, which a watch on the file itself would lose
+		// track of, so the directory is watched instead.
+		let directory = match self.map_json.parent()
+		{
+			Some(p) if !p.as_os_str().is_empty() => p,
+			_ => Path::new("."),
+		};
+		let directory = fs::canonicalize(directory)
+			.map_err(|err| Error::File { err, path: directory.into() })?;
+		let map_json = directory.join(self.map_json.file_name().unwrap_or_default());
+
+		let (sender, receiver) = mpsc::channel();
+		let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+		watcher.watch(&directory, RecursiveMode::NonRecursive).map_err(watch_error)?;
+
+		loop
+		{
+			// A map which is being edited is expected to be broken now and then.
+			if let Err(err) = self.solve()
+			{
+				eprintln!("{}", err);
+			}
+
+			// Ctrl-C was pressed during the solve, which was written out as it was.
+			if cancellation().is_cancelled()
+			{
+				return Ok(());
+			}
+
+			loop
+			{
+				let event = receiver
+					.recv()
+					.expect("Expected the watcher to live as long as the loop")
+					.map_err(watch_error)?;
+
+				if event.paths.contains(&map_json) &&
+					matches!(
+						event.kind,
+						EventKind::Create(_) |
+							EventKind::Modify(
+								ModifyKind::Any | ModifyKind::Data(_) | ModifyKind::Name(_)
+							)
+					)
+				{
+					break;
+				}
+			}
+
+			// Saving can take a few writes, so wait until they stop.
+			while receiver.recv_timeout(Duration::from_millis(100)).is_ok()
+			{}
+
+			eprintln!("{} changed, solving again", self.map_json.display());
+		}
+	}
+
+	/// # Summary
+	///
+	/// Find a [`Build`] for the map and write it out once.
+	fn solve(&self) -> Result<()>
+	{
+		let map = read_map(&self.map_json)?;
+
+		let mut dashboard = self.dashboard.then(|| Dashboard::new(self.blocks));
+		let mut report = self.report.as_ref().map(|_| Report::default());
+		let (mut map, build) = self.options.solve(map, self.blocks, |progress: &Progress| {
+			if let Some(d) = dashboard.as_mut()
+			{
+				d.draw(progress);
+			}
+
+			if let Some(r) = report.as_mut()
+			{
+				r.record(progress);
+			}
+		})?;
+
+		if let Some(mut d) = dashboard
+		{
+			d.finish();
+		}
+
+		if let Some(players) = self.players
+		{
+			map.players = build.split(players, self.player_blocks)?;
+		}
+
+		if let Some((r, path)) = report.zip(self.report.as_deref())
+		{
+			r.write(path, &map, &build, self.options.diagonals(), self.options.metric())?;
+		}
+
+		write_value(self.output.as_deref(), &map)?;
+		self.hooks.fire(self.output.as_deref())
+	}
+}
+
+/// # Summary
+///
+/// Read a `--time-slice` option, which is a strategy and how long it may search for.
+fn parse_time_slice(slice: &str) -> StdResult<(Strategy, TimeSlice), String>
+{
+	let (strategy, time) = slice
+		.split_once('=')
+		.ok_or_else(|| format!("{:?} is not of the form `strategy=time`", slice))?;
+
+	Ok((
+		strategy.trim().parse().map_err(|err: build::Error| err.to_string())?,
+		time.trim().parse().map_err(|err: solve::Error| err.to_string())?,
+	))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use sanctum_solver::map::{tileset::Tileset, Tile::*};
+	use structopt::StructOpt;
+
+	use super::Options;
+
+	#[test]
+	fn search_threads()
+	{
+		let tileset = Tileset::new(vec![vec![Spawn, Empty, Core]; 1024]);
+		let threads = |mebibytes: &str| {
+			Options::from_iter(["solve", "--search-memory", mebibytes]).search_threads(&tileset)
+		};
+
+		assert_eq!(Options::from_iter(["solve"]).search_threads(&tileset), None);
+
+		// Too little memory for even one thread still uses one, and too much can't overflow.
+		assert_eq!(threads("0"), Some(1));
+		assert_eq!(threads(&usize::MAX.to_string()), Some(rayon::current_num_threads()));
+	}
+}
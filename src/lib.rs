@@ -1,4 +1,15 @@
 mod container;
 pub mod map;
+mod parallel;
+pub mod solve;
 
 pub use container::Container;
+pub use solve::{
+	solve,
+	solve_cancellable,
+	solve_with_progress,
+	Solution,
+	SolveOptions,
+	SolveOptionsBuilder,
+	TimeSlice,
+};
@@ -1,12 +1,9 @@
 mod app;
-mod container;
-mod map;
 
 use app::App;
-use container::Container;
 use structopt::StructOpt;
 
 fn main()
 {
-	App::from_args().run().unwrap();
+	App::from_args().main();
 }
@@ -1,21 +1,211 @@
 mod adjacent;
-mod build;
-mod coordinate;
+mod budget_split;
+pub mod build;
+mod choke_points;
+mod comments;
+pub mod constraints;
+pub mod coordinate;
+mod core_entrance;
+mod direction;
+mod display_tile;
+mod distance_field;
+pub mod encoding;
+pub mod enemy_class;
+pub mod metadata;
+pub mod metric;
+pub mod movement_rules;
+mod neighborhood;
+pub mod objective;
+mod region_graph;
+mod region_result;
+mod repr;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
+mod sensitivity;
 mod shortest_path;
-mod tile;
+#[cfg(feature = "simulation")]
+mod simulation;
+mod stats;
+mod threat_map;
+pub mod tile;
+#[cfg(feature = "simulation")]
+mod tile_stats;
 pub mod tileset;
+mod warning;
+mod wave_plan;
+
+use std::collections::BTreeMap;
 
 pub use adjacent::Adjacent;
-pub use build::Build;
-pub use coordinate::Coordinate;
+pub use budget_split::BudgetSplit;
+pub use build::{Build, CancellationToken, Progress, SolverSession, Strategy};
+pub use choke_points::ChokePoints;
+pub use constraints::Constraints;
+pub use coordinate::{Coordinate, ObjectCoordinates};
+pub use core_entrance::CoreEntrance;
+pub use direction::Direction;
+pub use display_tile::DisplayTile;
+pub use distance_field::DistanceField;
+pub use encoding::Encoding;
+pub use enemy_class::EnemyClass;
+pub use metadata::Metadata;
+pub use metric::Metric;
+pub use movement_rules::{CornerCutting, MovementRules};
+pub use neighborhood::Neighborhood;
+pub use objective::{Formula, Objective};
+pub use region_graph::RegionGraph;
+pub use region_result::RegionResult;
+pub use sensitivity::{BlockImpact, Sensitivity};
 use serde::{Deserialize, Serialize};
 pub use shortest_path::ShortestPath;
+#[cfg(feature = "simulation")]
+pub use simulation::{Enemy, Frame, Simulation};
+pub use stats::{Stats, StrategyStats};
+pub use threat_map::{ThreatMap, Tower};
 pub use tile::Tile;
+#[cfg(feature = "simulation")]
+pub use tile_stats::TileStats;
+use tileset::{RegionRule, Targeting};
+pub use warning::Warning;
+pub use wave_plan::{Wave, WavePlan, WaveSchedule};
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "repr::Repr", into = "repr::Repr")]
 pub struct Map
 {
 	pub name: String,
 	pub grid: Vec<Vec<Tile>>,
+
+	/// # Summary
+	///
+	/// The character used for each [`Tile`] when the `grid` is written as rows of strings, rather
+	/// than arrays of [`Tile`]s. If [`None`], the `grid` is written as arrays.
+	pub legend: Option<BTreeMap<char, Tile>>,
+
+	/// # Summary
+	///
+	/// How many moves it takes to get from each tile to the nearest [`Tile::Core`] through the
+	/// build, if it was measured.
+	pub distance_to_core: Option<DistanceField>,
+
+	/// # Summary
+	///
+	/// The `grid` with the shortest path of each region marked on it, if it was asked for, so that
+	/// it can be drawn without finding the paths again.
+	pub display: Option<Vec<Vec<DisplayTile>>>,
+
+	/// # Summary
+	///
+	/// How much damage each [`Tile::Hazard`] deals. Defaults to `1`.
+	pub hazard_damage: Option<usize>,
+
+	/// # Summary
+	///
+	/// Extra data attached to some tiles of the `grid`.
+	pub metadata: Metadata,
+
+	/// # Summary
+	///
+	/// How touching spawns (and cores) are grouped into regions.
+	pub region_rule: RegionRule,
+
+	/// # Summary
+	///
+	/// Which cores the enemies of each spawn region walk to.
+	pub targeting: Targeting,
+
+	/// # Summary
+	///
+	/// A tile of the core which some spawn regions (by [`Region::id`](tileset::Region)) always
+	/// walk to, whatever the `targeting`.
+	pub core_assignments: BTreeMap<usize, Coordinate>,
+
+	/// # Summary
+	///
+	/// The entrances of some spawn regions (by [`Region::id`](tileset::Region)), for when the
+	/// game only opens some of the tiles beside a spawn. Any region which is not listed uses every
+	/// entrance found from the `grid`.
+	pub declared_entrances: BTreeMap<usize, Vec<Coordinate>>,
+
+	/// # Summary
+	///
+	/// How each spawn region fared, if the map was solved.
+	pub regions: Vec<RegionResult>,
+
+	/// # Summary
+	///
+	/// The `final_length` of each of the `regions`. Kept so that readers of older output still
+	/// work; prefer the `regions`.
 	pub shortest_path_length: Option<Vec<Option<usize>>>,
+
+	/// # Summary
+	///
+	/// Whether the `grid` is written as a list of the [`Coordinate`]s of each kind of [`Tile`]
+	/// (leaving out [`Tile::Impass`]), rather than row by row. This is much smaller for large maps
+	/// which are mostly [`Tile::Impass`].
+	pub sparse: bool,
+
+	pub stats: Option<Stats>,
+
+	/// # Summary
+	///
+	/// The blocks which each player should place, when the build is split between players.
+	pub players: Vec<Vec<Coordinate>>,
+
+	/// # Summary
+	///
+	/// The number used for each [`Tile`] when the `grid` is written as rows of numbers, rather
+	/// than arrays of [`Tile`]s. Only used if there is no `legend`.
+	pub tile_codes: Option<BTreeMap<usize, Tile>>,
+
+	/// # Summary
+	///
+	/// Anything which was repaired while reading this [`Map`], or which may have made its solve
+	/// worse than it could have been.
+	pub warnings: Vec<Warning>,
+}
+
+impl Map
+{
+	/// # Summary
+	///
+	/// The version of the map format which is written. Files written in older versions are
+	/// upgraded when they are read, and files written in newer versions cannot be read.
+	pub const VERSION: u32 = 1;
+
+	/// # Summary
+	///
+	/// Read a [`Map`] from `json`, which may have `//` and `/* */` comments and trailing commas.
+	pub fn from_json(json: &str) -> serde_json::Result<Self>
+	{
+		serde_json::from_str(&comments::strip(json))
+	}
+
+	/// # Summary
+	///
+	/// Make every row of the `grid` as long as the longest, by adding [`Tile::Impass`]es to the
+	/// end of shorter rows.
+	pub fn pad(&mut self)
+	{
+		let width = self.grid.iter().map(Vec::len).max().unwrap_or(0);
+		self.grid.iter_mut().for_each(|row| row.resize(width, Tile::Impass));
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::{Map, Tile::*};
+
+	#[test]
+	fn pad()
+	{
+		let mut map: Map =
+			serde_json::from_str(r#"{"name": "test", "grid": ["S.", "..C", ""]}"#).unwrap();
+		map.pad();
+
+		assert_eq!(map.grid, vec![vec![Spawn, Empty, Impass], vec![Empty, Empty, Core], vec![
+			Impass, Impass, Impass
+		]]);
+	}
 }
@@ -1,4 +1,6 @@
-use super::{tileset::COORDINATE_ON_TILESET, Coordinate, Tile};
+use std::array;
+
+use super::{tileset::COORDINATE_ON_TILESET, Coordinate, Direction, MovementRules, Tile};
 use crate::Container;
 
 /// # Summary
@@ -22,38 +24,68 @@ impl<T> Adjacent<T>
 {
 	/// # Summary
 	///
-	/// Run some `f`unction on each [`Some`] value.
-	pub fn for_each(self, mut f: impl FnMut(T))
+	/// Get the value in some `direction`.
+	pub fn get(&self, direction: Direction) -> Option<&T>
 	{
-		/// # Summary
-		///
-		/// Call `f` on `$arg`
-		macro_rules! call_if_some {
-			($arg:expr) => {
-				if let Some(some_arg) = $arg
-				{
-					f(some_arg);
-				}
-			};
+		match direction
+		{
+			Direction::Up => self.up.as_ref(),
+			Direction::Right => self.right.as_ref(),
+			Direction::Down => self.down.as_ref(),
+			Direction::Left => self.left.as_ref(),
+			Direction::UpRight => self.up_right.as_ref(),
+			Direction::DownRight => self.down_right.as_ref(),
+			Direction::DownLeft => self.down_left.as_ref(),
+			Direction::UpLeft => self.up_left.as_ref(),
 		}
+	}
+}
+
+impl<T> IntoIterator for Adjacent<T>
+{
+	type IntoIter = IntoIter<T>;
+	type Item = (Direction, T);
+
+	/// # Summary
+	///
+	/// Iterate over each [`Some`] value and the [`Direction`] it is in, orthogonal directions
+	/// first.
+	fn into_iter(self) -> Self::IntoIter
+	{
+		IntoIter(
+			[
+				(Direction::Up, self.up),
+				(Direction::Right, self.right),
+				(Direction::Down, self.down),
+				(Direction::Left, self.left),
+				(Direction::UpRight, self.up_right),
+				(Direction::DownRight, self.down_right),
+				(Direction::DownLeft, self.down_left),
+				(Direction::UpLeft, self.up_left),
+			]
+			.into_iter(),
+		)
+	}
+}
 
-		call_if_some!(self.up);
-		call_if_some!(self.right);
-		call_if_some!(self.down);
-		call_if_some!(self.left);
+/// # Summary
+///
+/// An [`Iterator`] over the values of an [`Adjacent`], and the [`Direction`] of each.
+#[derive(Clone, Debug)]
+pub struct IntoIter<T>(array::IntoIter<(Direction, Option<T>), 8>);
 
-		call_if_some!(self.up_right);
-		call_if_some!(self.down_right);
-		call_if_some!(self.down_left);
-		call_if_some!(self.up_left);
+impl<T> Iterator for IntoIter<T>
+{
+	type Item = (Direction, T);
+
+	fn next(&mut self) -> Option<Self::Item>
+	{
+		self.0.by_ref().find_map(|(direction, value)| value.map(|v| (direction, v)))
 	}
 }
 
 impl Adjacent<Coordinate>
 {
-	/// # Summary
-	///
-	/// Return [`Self::from_grid_coordinate`] but with blocked diagonals reflecteed from the build.
 	/// # Summary
 	///
 	/// Get the adjacent [`Coordinate`]s to a `coordinate` on an `array`.
@@ -63,80 +95,59 @@ impl Adjacent<Coordinate>
 		diagonals: bool,
 	) -> Self
 	{
-		/// # Summary
-		///
-		/// If `$cond` is `true`, then return `Some($value)`. Otherwise, return `None`.
-		macro_rules! if_then_or_none {
-			($($cond: expr)+, $value: expr) => {
-				if $($cond)&&* {
-					Some($value)
-				} else {
-					None
-				}
-			};
-		}
-
-		let can_move_up = coord.1 > 0;
-		let can_move_right = coord.0 < grid[coord.1].as_ref().len() - 1;
-		let can_move_down = coord.1 < grid.len() - 1;
-		let can_move_left = coord.0 > 0;
+		let diagonal = |dx, dy| if diagonals { coord.offset(grid, dx, dy) } else { None };
 
 		Self {
-			up: if_then_or_none!(can_move_up, Coordinate(coord.0, coord.1 - 1)),
-			right: if_then_or_none!(can_move_right, Coordinate(coord.0 + 1, coord.1)),
-			down: if_then_or_none!(can_move_down, Coordinate(coord.0, coord.1 + 1)),
-			left: if_then_or_none!(can_move_left, Coordinate(coord.0 - 1, coord.1)),
-
-			up_right: if_then_or_none!(
-				can_move_up can_move_right diagonals,
-				Coordinate(coord.0 + 1, coord.1 - 1)
-			),
-			down_right: if_then_or_none!(
-				can_move_down can_move_right diagonals,
-				Coordinate(coord.0 + 1, coord.1 + 1)
-			),
-			down_left: if_then_or_none!(
-				can_move_down can_move_left diagonals,
-				Coordinate(coord.0 - 1, coord.1 + 1)
-			),
-			up_left: if_then_or_none!(
-				can_move_up can_move_left diagonals,
-				Coordinate(coord.0 - 1, coord.1 - 1)
-			),
+			up: coord.offset(grid, 0, -1),
+			right: coord.offset(grid, 1, 0),
+			down: coord.offset(grid, 0, 1),
+			left: coord.offset(grid, -1, 0),
+
+			up_right: diagonal(1, -1),
+			down_right: diagonal(1, 1),
+			down_left: diagonal(-1, 1),
+			up_left: diagonal(-1, -1),
 		}
 	}
 
+	/// # Summary
+	///
+	/// Return [`Self::from_grid_coordinate`], but without the diagonals which the `movement` rules
+	/// do not allow given the impassable tiles (and the `build`) beside them.
 	pub fn from_grid_coordinate_with_build(
 		grid: &[impl AsRef<[Tile]>],
 		build: Option<&impl Container<Coordinate>>,
 		coord: &Coordinate,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 	) -> Self
 	{
-		let mut adjacent = Self::from_grid_coordinate(grid, coord, diagonals);
+		let movement = movement.into();
+		let mut adjacent = Self::from_grid_coordinate(grid, coord, movement.diagonals);
 
 		/// # Summary
 		///
-		/// If `$cond` is `true`, then return `Some($value)`. Otherwise, return `None`.
+		/// Set `$field` to `None` if the `movement` rules do not allow moving there, given whether
+		/// the orthogonal moves `$first` and `$second` beside it are passable.
 		///
 		/// # Remarks
 		///
-		/// We don't set it to `Impass` or `Block`, because `None`s are ignored by `for_each`.
+		/// We don't set it to `Impass` or `Block`, because `None`s are skipped when iterating.
 		/// Therefore we get a performance improvement.
 		macro_rules! if_then_none {
-			($($cond: expr)+, $field: ident) => {
-				if $(!$cond)&&* {
+			($first:expr, $second:expr, $field:ident) => {
+				if !movement.allows_diagonal($first, $second)
+				{
 					adjacent.$field = None;
 				}
 			};
 		}
 
-		if diagonals
+		if movement.diagonals
 		{
 			let can_move_to = |direction: Option<Coordinate>| -> bool {
 				direction
 					.map(|d| {
-						d.get_from_with_build(&grid, build)
+						d.get_from_with_build(grid, build)
 							.expect(COORDINATE_ON_TILESET)
 							.is_passable()
 					})
@@ -148,10 +159,10 @@ impl Adjacent<Coordinate>
 			let can_move_down = can_move_to(adjacent.down);
 			let can_move_left = can_move_to(adjacent.left);
 
-			if_then_none!(can_move_up can_move_right, up_right);
-			if_then_none!(can_move_down can_move_right, down_right);
-			if_then_none!(can_move_down can_move_left, down_left);
-			if_then_none!(can_move_up can_move_left, up_left);
+			if_then_none!(can_move_up, can_move_right, up_right);
+			if_then_none!(can_move_down, can_move_right, down_right);
+			if_then_none!(can_move_down, can_move_left, down_left);
+			if_then_none!(can_move_up, can_move_left, up_left);
 		}
 
 		adjacent
@@ -163,8 +174,8 @@ mod tests
 {
 	use std::time::Instant;
 
-	use super::{Adjacent, Coordinate};
-	use crate::map::{Build, Tile, Tile::*};
+	use super::{Adjacent, Coordinate, Direction};
+	use crate::map::{Build, CornerCutting, MovementRules, Tile, Tile::*};
 
 	#[rustfmt::skip]
 	const ARRAY: [[Tile; 5]; 5] = [
@@ -278,5 +289,43 @@ mod tests
 			down_left: Some(Coordinate(1, 3)),
 			up_left: None,
 		},);
+
+		// Without cutting corners, every diagonal beside a block or impasse is gone, but squeezing
+		// between the two blocks reaches the up-right.
+		let movement = |corner_cutting| MovementRules { corner_cutting, diagonals: true };
+		let no = Adjacent::from_grid_coordinate_with_build(
+			&ARRAY,
+			Some(&build.blocks),
+			&Coordinate(2, 2),
+			movement(CornerCutting::No),
+		);
+		assert_eq!(no.down_right, None);
+		assert_eq!(no.down_left, None);
+
+		let yes = Adjacent::from_grid_coordinate_with_build(
+			&ARRAY,
+			Some(&build.blocks),
+			&Coordinate(2, 2),
+			movement(CornerCutting::Yes),
+		);
+		assert_eq!(yes.up_right, Some(Coordinate(3, 1)));
+	}
+
+	#[test]
+	fn into_iter()
+	{
+		let adjacent = Adjacent::from_grid_coordinate(&ARRAY, &Coordinate(0, 0), true);
+
+		let start = Instant::now();
+		let neighbors: Vec<_> = adjacent.into_iter().collect();
+		println!("Adjacent::into_iter {}us", Instant::now().duration_since(start).as_micros());
+
+		// Only the neighbors which exist are visited, and each knows where it is.
+		assert_eq!(neighbors, vec![
+			(Direction::Right, Coordinate(1, 0)),
+			(Direction::Down, Coordinate(0, 1)),
+			(Direction::DownRight, Coordinate(1, 1)),
+		]);
+		assert!(neighbors.iter().all(|(direction, coord)| adjacent.get(*direction) == Some(coord)));
 	}
 }
@@ -0,0 +1,181 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	build::{Error, Result},
+	tileset::Tileset,
+	MovementRules,
+	ShortestPath,
+	SolverSession,
+};
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IntoParallelIterator, ParallelIterator};
+
+/// # Summary
+///
+/// How to spend a budget of blocks across the regions of a [`Tileset`], so that each block goes
+/// where it lengthens a path the most.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BudgetSplit
+{
+	/// # Summary
+	///
+	/// How many blocks to spend on each region.
+	pub blocks: Vec<usize>,
+
+	/// # Summary
+	///
+	/// How much longer each region's path is expected to get from its blocks.
+	pub gain: Vec<usize>,
+
+	/// # Summary
+	///
+	/// How many blocks of the budget no region could use.
+	pub spare: usize,
+}
+
+impl BudgetSplit
+{
+	/// # Summary
+	///
+	/// The length of a `region`'s path with at most each number of blocks (up to the `budget`),
+	/// when every block is spent on that region alone.
+	fn lengths_by_blocks(
+		tileset: &Tileset,
+		movement: MovementRules,
+		budget: usize,
+		region: usize,
+	) -> Result<Vec<usize>>
+	{
+		let initial = ShortestPath::from_region_to_target(
+			tileset,
+			Option::<&HashSet<_>>::None,
+			region,
+			movement,
+		)
+		.ok_or(Error::NoPathToCore { region })?;
+
+		let mut split = vec![0; tileset.entrances_by_region.len()];
+		split[region] = budget;
+
+		let mut session = SolverSession::new(tileset, movement, Some(budget))?;
+		session.set_split(split);
+
+		let mut lengths = vec![initial.len()];
+		while let Some(step) = session.step()
+		{
+			let blocks = session.build().blocks.len();
+			if lengths.len() <= blocks
+			{
+				lengths.resize(blocks + 1, 0);
+			}
+
+			if let Some(length) = step.shortest_path_length[region]
+			{
+				lengths[blocks] = lengths[blocks].max(length);
+			}
+		}
+
+		// A path is at least as long with more blocks to spend, since they need not all be used.
+		for blocks in 1..lengths.len()
+		{
+			lengths[blocks] = lengths[blocks].max(lengths[blocks - 1]);
+		}
+
+		Ok(lengths)
+	}
+
+	/// # Summary
+	///
+	/// Recommend how to split a `budget` of blocks across the regions of a `tileset`.
+	///
+	/// # Remarks
+	///
+	/// Each region is solved on its own first, to find how its path grows with each block. Then,
+	/// blocks are handed out in turn to whichever region gains the most path per block from its
+	/// next few blocks; looking ahead means a wall which only helps once it is finished is still
+	/// worth building.
+	///
+	/// # Errors
+	///
+	/// * [`Error::NoPathToCore`], if some region cannot reach a core to begin with.
+	pub fn of(tileset: &Tileset, movement: impl Into<MovementRules>, budget: usize)
+		-> Result<Self>
+	{
+		let movement = movement.into();
+		let lengths: Vec<_> = (0..tileset.entrances_by_region.len())
+			.into_par_iter()
+			.map(|region| Self::lengths_by_blocks(tileset, movement, budget, region))
+			.collect::<Result<_>>()?;
+
+		// Whether `a` gains more per block than `b`, preferring fewer blocks when they are equal.
+		let compare = |(a_gain, a_blocks): (usize, usize), (b_gain, b_blocks): (usize, usize)| {
+			(a_gain * b_blocks).cmp(&(b_gain * a_blocks)).then(b_blocks.cmp(&a_blocks))
+		};
+
+		let mut blocks = vec![0; lengths.len()];
+		let mut spare = budget;
+		while let Some((region, more)) = lengths
+			.iter()
+			.enumerate()
+			.filter_map(|(region, lengths)| {
+				let current = lengths[blocks[region]];
+				(1..=spare)
+					.map_while(|more| {
+						lengths.get(blocks[region] + more).map(|l| (l - current, more))
+					})
+					.filter(|(gain, _)| *gain > 0)
+					.max_by(|a, b| compare(*a, *b))
+					.map(|best| (region, best))
+			})
+			.max_by(|(a_region, a), (b_region, b)| match compare(*a, *b)
+			{
+				Ordering::Equal => b_region.cmp(a_region),
+				ordering => ordering,
+			})
+			.map(|(region, (_, more))| (region, more))
+		{
+			blocks[region] += more;
+			spare -= more;
+		}
+
+		let gain =
+			blocks.iter().zip(&lengths).map(|(b, lengths)| lengths[*b] - lengths[0]).collect();
+		Ok(Self { blocks, gain, spare })
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{BudgetSplit, Tileset};
+	use crate::map::{tileset::tests::PARK_TWO_SPAWN, SolverSession};
+
+	#[test]
+	fn of()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let start = Instant::now();
+		let split = BudgetSplit::of(&test_tileset, false, 10).unwrap();
+		println!("BudgetSplit::of {}us", Instant::now().duration_since(start).as_micros());
+
+		// Every block of the budget is accounted for, and only regions with blocks gain anything.
+		assert_eq!(split.blocks.iter().sum::<usize>() + split.spare, 10);
+		split.blocks.iter().zip(&split.gain).for_each(|(blocks, gain)| {
+			assert!(*blocks > 0 || *gain == 0);
+		});
+
+		// A session which follows the split never spends more than it, even with a larger limit.
+		let mut session = SolverSession::new(&test_tileset, false, Some(20)).unwrap();
+		session.set_split(split.blocks.clone());
+		while session.step().is_some()
+		{}
+
+		assert!(session.build().blocks.len() <= split.blocks.iter().sum());
+	}
+}
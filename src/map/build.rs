@@ -1,28 +1,88 @@
+mod cancellation_token;
+mod corridors;
+mod error;
+mod progress;
+mod session;
+mod step_trace;
+mod strategy;
 mod temp_build;
 
-use std::collections::{BTreeMap, HashSet, LinkedList};
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, BTreeSet, HashSet},
+	result::Result as StdResult,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
 
-use rayon::iter::IntoParallelRefIterator;
-use serde::{Deserialize, Serialize};
-use temp_build::TempBuild;
+pub use cancellation_token::CancellationToken;
+use corridors::Corridors;
+pub use error::{Error, Result};
+pub use progress::Progress;
+use serde::{Deserialize, Serialize, Serializer};
+pub use session::{SolverSession, Step};
+pub use step_trace::StepTrace;
+pub use strategy::Strategy;
+use temp_build::{TempBuild, TempRemoval};
 
 use super::{
-	tileset::{Tileset, COORDINATE_ON_TILESET},
+	tileset::Tileset,
 	Adjacent,
 	Coordinate,
+	DistanceField,
+	MovementRules,
+	Objective,
 	ShortestPath,
+	StrategyStats,
 	Tile,
 };
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use crate::Container;
 
 const VALID_BUILD: &str = "Expected build to produce shortest paths";
 
+/// # Summary
+///
+/// Write a `number` in base 36, using the digits `0-9` and then `a-z`.
+fn to_base_36(mut number: usize) -> String
+{
+	let mut digits = Vec::new();
+	loop
+	{
+		digits.push(
+			std::char::from_digit((number % 36) as u32, 36).expect("Expected a base 36 digit"),
+		);
+		number /= 36;
+		if number == 0
+		{
+			break;
+		}
+	}
+
+	digits.into_iter().rev().collect()
+}
+
+/// # Summary
+///
+/// Serialize some `blocks` in reading order, so that the same [`Build`] is always written the
+/// same way.
+fn serialize_sorted<S>(blocks: &HashSet<Coordinate>, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let mut sorted: Vec<_> = blocks.iter().collect();
+	sorted.sort_unstable_by_key(|coord| (coord.1, coord.0));
+	sorted.serialize(serializer)
+}
+
 /// # Summary
 ///
 /// A set of blocks for a [`Tileset`].
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Build
 {
+	#[serde(serialize_with = "serialize_sorted")]
 	pub blocks: HashSet<Coordinate>,
 }
 
@@ -31,11 +91,28 @@ impl Build
 	/// # Summary
 	///
 	/// Apply all of the `blocks` from the [`Build`] to a `tileset`.
-	pub fn apply_to(&self, grid: &mut [impl AsMut<[Tile]>])
+	///
+	/// # Remarks
+	///
+	/// A block on a [`Tile::TowerBase`] becomes a [`Tile::TowerBlock`], so that
+	/// [`Build::take_from`] can put the base back.
+	///
+	/// # Errors
+	///
+	/// * [`Error::OutOfBounds`], if some block is not on the `grid`. Nothing is applied.
+	pub fn apply_to(&self, grid: &mut [impl AsRef<[Tile]> + AsMut<[Tile]>]) -> Result<()>
 	{
-		self.blocks.iter().for_each(|coordinate| {
-			coordinate.set(grid, Tile::Block);
-		})
+		self.blocks.iter().try_for_each(|coord| coord.check_bounds(grid))?;
+		self.blocks.iter().try_for_each(|coord| {
+			let block = match coord.get_from(grid)
+			{
+				Some(Tile::TowerBase) => Tile::TowerBlock,
+				_ => Tile::Block,
+			};
+			coord.set(grid, block)
+		})?;
+
+		Ok(())
 	}
 
 	/// # Summary
@@ -58,16 +135,205 @@ impl Build
 	pub fn find_valid_block_placement(
 		tileset: &Tileset,
 		blocks: &impl Container<Coordinate>,
-		shortest_path: Vec<Coordinate>,
+		shortest_path: &[Coordinate],
 	) -> Option<Coordinate>
 	{
-		shortest_path.into_iter().rev().find(|coord| {
-			// We only want empty tiles
-			coord.get_from(&tileset.grid).expect(COORDINATE_ON_TILESET) == Tile::Empty &&
-				Build::is_valid(&tileset, &TempBuild { blocks, temp_block: *coord })
+		shortest_path.iter().rev().copied().find(|coord| {
+			// We only want empty tiles which can affect a path.
+			tileset.is_buildable(coord) &&
+				Build::is_valid(tileset, &TempBuild { blocks, temp_block: *coord })
 		})
 	}
 
+	/// # Summary
+	///
+	/// [`Build::find_scored_block_placement`] if there is a `candidate_scope`, or else
+	/// [`Build::find_valid_block_placement`].
+	fn find_block_placement(
+		tileset: &Tileset,
+		blocks: &impl Container<Coordinate>,
+		region: usize,
+		shortest_path: &[Coordinate],
+		candidate_scope: Option<usize>,
+		objective: &Objective,
+		movement: MovementRules,
+	) -> Option<Coordinate>
+	{
+		match candidate_scope
+		{
+			Some(scope) => Build::find_scored_block_placement(
+				tileset,
+				blocks,
+				region,
+				shortest_path,
+				scope,
+				objective,
+				movement,
+			),
+			None => Build::find_valid_block_placement(tileset, blocks, shortest_path),
+		}
+	}
+
+	/// # Summary
+	///
+	/// Find the best [valid][valid] [block][block] placement for a spawn `region` among the tiles
+	/// of its `shortest_path`, and the [buildable](Tileset::is_buildable) tiles within
+	/// `candidate_scope` moves of it. Without diagonals, [dead ends](Tileset::dead_ends) are
+	/// skipped.
+	///
+	/// # Remarks
+	///
+	/// Unlike [`Build::find_valid_block_placement`], which takes the first valid tile from the
+	/// core, every candidate is scored by the `objective`: first by the
+	/// [score](Objective::scores) of the `region`'s path with the block placed, then by the scores
+	/// of every region's path added together, so that a path which is led across
+	/// [hazards](Tile::Hazard) is worth more than one of the same length. A tile beside the path
+	/// is only a candidate if its block lengthens some path, e.g. by closing off the way around a
+	/// later block, or by lengthening another region's path; otherwise the build would fill up
+	/// with blocks which do nothing. Ties go to the tiles of the path (nearest the core first),
+	/// then to the closest tiles beside it.
+	///
+	/// [block]: Tile::Block
+	/// [valid]: Build::is_valid
+	pub fn find_scored_block_placement(
+		tileset: &Tileset,
+		blocks: &impl Container<Coordinate>,
+		region: usize,
+		shortest_path: &[Coordinate],
+		candidate_scope: usize,
+		objective: &Objective,
+		movement: impl Into<MovementRules>,
+	) -> Option<Coordinate>
+	{
+		let movement = movement.into();
+		let (candidates, on_path) =
+			Build::scored_candidates(tileset, blocks, shortest_path, candidate_scope, movement);
+
+		let score = |paths: Vec<Option<ShortestPath>>| {
+			let scores = objective.scores_of(tileset, &paths);
+			(scores[region].unwrap_or(0), scores.iter().flatten().sum::<usize>())
+		};
+
+		// A tile beside the path does not change it, so it has to lengthen some path to be worth a
+		// block. Otherwise the build would fill up with blocks which do nothing.
+		let current =
+			score(ShortestPath::from_entrances_to_any_core(tileset, Some(blocks), movement));
+
+		candidates
+			.par_iter()
+			.enumerate()
+			.filter_map(|(index, coord)| {
+				let temp = TempBuild { blocks, temp_block: *coord };
+				if !Build::is_valid(tileset, &temp)
+				{
+					return None;
+				}
+
+				let score =
+					score(ShortestPath::from_entrances_to_any_core(tileset, Some(&temp), movement));
+				(index < on_path || score > current).then_some(((score, Reverse(index)), *coord))
+			})
+			.max_by_key(|(key, _)| *key)
+			.map(|(_, coord)| coord)
+	}
+
+	/// # Summary
+	///
+	/// The tiles which [`Build::find_scored_block_placement`] scores, in the order it prefers
+	/// them, and how many of them are on the `shortest_path`.
+	fn scored_candidates(
+		tileset: &Tileset,
+		blocks: &impl Container<Coordinate>,
+		shortest_path: &[Coordinate],
+		candidate_scope: usize,
+		movement: MovementRules,
+	) -> (Vec<Coordinate>, usize)
+	{
+		let is_candidate =
+			|coord: &Coordinate| tileset.is_buildable(coord) && !blocks.contains(coord);
+
+		let mut candidates: Vec<_> =
+			shortest_path.iter().rev().copied().filter(|coord| is_candidate(coord)).collect();
+		let on_path = candidates.len();
+		if candidate_scope > 0
+		{
+			let distances = DistanceField::to_coordinates(
+				&tileset.grid,
+				Some(blocks),
+				shortest_path.iter().copied(),
+				movement,
+			);
+
+			// Without diagonals, a block in a dead end can't lengthen any path, so it is not
+			// scored.
+			let mut frontier: Vec<_> = tileset
+				.buildable_tiles()
+				.into_iter()
+				.filter(|coord| !blocks.contains(coord))
+				.filter(|coord| movement.diagonals || !tileset.dead_ends().contains(coord))
+				.filter_map(|coord| {
+					distances
+						.get(&coord)
+						.filter(|distance| (1..=candidate_scope).contains(distance))
+						.map(|distance| (distance, coord))
+				})
+				.collect();
+			frontier.sort_unstable_by_key(|(distance, coord)| (*distance, coord.1, coord.0));
+			candidates.extend(frontier.into_iter().map(|(_, coord)| coord));
+		}
+
+		(candidates, on_path)
+	}
+
+	/// # Summary
+	///
+	/// Read a [`Build`] from a `code` made by [`Build::to_code`].
+	///
+	/// # Returns
+	///
+	/// The [`Build`], and the `(width, height)` of the grid it was made for.
+	///
+	/// # Errors
+	///
+	/// * [`Error::InvalidCode`], if the `code` is malformed, its runs go past the end of the grid,
+	///   or the grid has more than [`MAX_SPARSE_TILES`](super::repr::MAX_SPARSE_TILES).
+	pub fn from_code(code: &str) -> Result<(Self, (usize, usize))>
+	{
+		let invalid = || Error::InvalidCode { code: code.into() };
+		let parse = |number: &str| usize::from_str_radix(number, 36).map_err(|_| invalid());
+
+		let (size, runs) = code.split_once('-').unwrap_or((code, ""));
+		let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+		let (width, height) = (parse(width)?, parse(height)?);
+		let area = width
+			.checked_mul(height)
+			.filter(|area| width > 0 && *area <= super::repr::MAX_SPARSE_TILES)
+			.ok_or_else(invalid)?;
+
+		let mut blocks = HashSet::new();
+		let mut index: usize = 0;
+		runs.split('.').filter(|run| !run.is_empty()).enumerate().try_for_each(
+			|(run_index, run)| {
+				// Each run is checked before it is used, so a huge one can't exhaust memory.
+				let end = index
+					.checked_add(parse(run)?)
+					.filter(|end| *end <= area)
+					.ok_or_else(invalid)?;
+
+				// Runs alternate between gaps and blocks, starting with a gap.
+				if run_index % 2 == 1
+				{
+					blocks.extend((index..end).map(|i| Coordinate(i % width, i / width)));
+				}
+
+				index = end;
+				Ok::<_, Error>(())
+			},
+		)?;
+
+		Ok((Self { blocks }, (width, height)))
+	}
+
 	/// # Summary
 	///
 	/// Get the longest build for a specific `tileset` by using round-robin on all of the spawn
@@ -75,155 +341,343 @@ impl Build
 	///
 	/// # Parameters
 	///
-	/// * `diagonals`, whether to use diagonal movement.
+	/// * `movement`, how enemies move, e.g. whether diagonals are allowed.
 	/// * `max_blocks`, the maximum number of blocks to place.
+	/// * `on_progress`, called with the [`Progress`] after each attempt to place a block.
+	///
+	/// # Errors
+	///
+	/// * [`Error::NoPathToCore`], if some region cannot reach a [`Tile::Core`] to begin with.
 	pub fn from_entrances_to_any_core(
 		tileset: &Tileset,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 		max_blocks: Option<usize>,
-	) -> Self
+		on_progress: impl FnMut(&Progress),
+	) -> Result<Self>
 	{
-		let mut build = Build { blocks: HashSet::new() };
-
-		let mut current_entrance = 0;
-		let mut placements = 1;
+		let cancellation = CancellationToken::new();
+		Self::round_robin(
+			tileset,
+			movement.into(),
+			max_blocks,
+			None,
+			&Objective::default(),
+			&cancellation,
+			on_progress,
+		)
+	}
 
-		while max_blocks.map(|max| max > build.blocks.len()).unwrap_or(true)
+	/// # Summary
+	///
+	/// [`Build::from_entrances_to_any_core`], choosing each block with
+	/// [`Build::find_scored_block_placement`] if there is a `candidate_scope`, and stopping early
+	/// if the `cancellation` is cancelled.
+	fn round_robin(
+		tileset: &Tileset,
+		movement: MovementRules,
+		max_blocks: Option<usize>,
+		candidate_scope: Option<usize>,
+		objective: &Objective,
+		cancellation: &CancellationToken,
+		mut on_progress: impl FnMut(&Progress),
+	) -> Result<Self>
+	{
+		let mut session = SolverSession::new(tileset, movement, max_blocks)?;
+		if let Some(scope) = candidate_scope
 		{
-			let entrance = {
-				// If we're still iterating over the number of entrances
-				if current_entrance < tileset.entrances_by_region.len() - 1
-				{
-					current_entrance += 1;
-				// If blocks are still being placed.
-				}
-				else if placements > 0
-				{
-					current_entrance = 0;
-					placements = 0;
-				}
-				else
-				{
-					break;
-				}
-				current_entrance
-			};
+			session.set_candidate_scope(scope);
+		}
 
-			if let Some(coord) = Build::find_valid_block_placement(
-				tileset,
-				&build.blocks,
-				ShortestPath::from_any_grid_coordinate_to_tile(
-					&tileset.grid,
-					Some(&build.blocks),
-					tileset.entrances_by_region[entrance].par_iter(),
-					Tile::Core,
-					diagonals,
-				)
-				.expect(VALID_BUILD)
-				.into(),
-			)
-			{
-				// Test the build with the coordinate inserted.
-				// Insert the coord now that we know it is valid.
-				build.blocks.insert(coord);
-				build.try_remove_adjacent_to(&tileset, coord, diagonals);
+		session.set_objective(objective.clone());
+		session.set_cancellation(cancellation.clone());
 
-				// Mark the block as having been placed.
-				placements += 1;
-			}
+		while session.step().is_some()
+		{
+			on_progress(session.progress());
 		}
 
-		build
+		Ok(session.into_build())
 	}
 
 	/// # Summary
 	///
 	/// Get the longest build for a specific `tileset` by taking priority on the current shortest
 	/// path.
+	///
+	/// # Parameters
+	///
+	/// See [`Build::from_entrances_to_any_core`].
+	///
+	/// # Errors
+	///
+	/// See [`Build::from_entrances_to_any_core`].
 	pub fn from_entrances_to_any_core_with_priority(
 		tileset: &Tileset,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 		max_blocks: Option<usize>,
-	) -> Self
+		on_progress: impl FnMut(&Progress),
+	) -> Result<Self>
+	{
+		let cancellation = CancellationToken::new();
+		Self::priority(
+			tileset,
+			movement.into(),
+			max_blocks,
+			None,
+			&Objective::default(),
+			&cancellation,
+			on_progress,
+		)
+	}
+
+	/// # Summary
+	///
+	/// [`Build::from_entrances_to_any_core_with_priority`], choosing each block with
+	/// [`Build::find_scored_block_placement`] if there is a `candidate_scope`, and stopping early
+	/// if the `cancellation` is cancelled.
+	///
+	/// # Remarks
+	///
+	/// After each block is placed, only the regions whose paths now cross a block are searched
+	/// again, and those searches run in parallel.
+	fn priority(
+		tileset: &Tileset,
+		movement: MovementRules,
+		max_blocks: Option<usize>,
+		candidate_scope: Option<usize>,
+		objective: &Objective,
+		cancellation: &CancellationToken,
+		mut on_progress: impl FnMut(&Progress),
+	) -> Result<Self>
 	{
 		let mut build = Build { blocks: HashSet::new() };
 
-		let mut shortest_paths_by_region: BTreeMap<_, _> =
-			ShortestPath::from_entrances_to_any_core(
-				&tileset,
-				Option::<&HashSet<_>>::None,
-				diagonals,
-			)
-			.into_iter()
-			.enumerate()
-			.map(|(index, shortest_path)| (shortest_path.expect(VALID_BUILD), index))
-			.collect();
+		// Paths are ordered by length alone, so the region breaks ties. Otherwise, a region whose
+		// path is as long as another's would be dropped.
+		let mut shortest_paths_by_region: BTreeSet<_> =
+			Build::initial_shortest_paths(tileset, movement)?
+				.into_iter()
+				.enumerate()
+				.map(|(index, shortest_path)| (shortest_path, index))
+				.collect();
+
+		let mut progress = Progress::new(tileset.entrances_by_region.len());
 
 		while let Some((shortest_path, region_index)) = shortest_paths_by_region.pop_first()
 		{
-			// Make sure we have less than the maximum blocks.
-			if max_blocks.map(|max| build.blocks.len() >= max).unwrap_or(false)
+			// Make sure we have less than the maximum blocks, and have not been asked to stop.
+			if max_blocks.map(|max| build.blocks.len() >= max).unwrap_or(false) ||
+				cancellation.is_cancelled()
 			{
 				break;
 			}
 
-			/// # Summary
-			///
-			/// Create a new shortest path.
-			macro_rules! shortest_path {
-				() => {
-					ShortestPath::from_any_grid_coordinate_to_tile(
-						&tileset.grid,
-						Some(&build.blocks),
-						tileset.entrances_by_region[region_index].par_iter(),
-						Tile::Core,
-						diagonals,
-					)
-					.expect(VALID_BUILD)
-				};
+			progress.set_length(region_index, shortest_path.len());
+			progress.blocks = build.blocks.len();
+			progress.iterations += 1;
+			on_progress(&progress);
+
+			if let Some(coord) = Build::find_block_placement(
+				tileset,
+				&build.blocks,
+				region_index,
+				shortest_path.coordinates(),
+				candidate_scope,
+				objective,
+				movement,
+			)
+			{
+				// It was valid, so insert it.
+				build.blocks.insert(coord);
+				build.try_remove_adjacent_to(tileset, coord, movement);
+
+				// Only the regions whose paths now cross a block need new ones. Finding those is
+				// the main cost of this strategy, so they are found at the same time.
+				let (affected, unaffected): (Vec<_>, Vec<_>) =
+					std::mem::take(&mut shortest_paths_by_region)
+						.into_iter()
+						.chain([(shortest_path, region_index)])
+						.partition(|(path, _)| path.iter().any(|c| build.blocks.contains(c)));
+
+				let recalculated: Vec<_> = affected
+					.par_iter()
+					.map(|(_, region)| {
+						let path = ShortestPath::from_region_to_target(
+							tileset,
+							Some(&build.blocks),
+							*region,
+							movement,
+						)
+						.expect(VALID_BUILD);
+						(path, *region)
+					})
+					.collect();
+
+				shortest_paths_by_region = unaffected.into_iter().chain(recalculated).collect();
 			}
+		}
+
+		Ok(build)
+	}
+
+	/// # Summary
+	///
+	/// Get the longest build for a specific `tileset` using some [`Strategy`].
+	///
+	/// # Parameters
+	///
+	/// * `time_slices`, how long each [`Strategy`] may search for before it is stopped with the
+	///   best build it found so far. Those which are not listed search for as long as they need.
+	///
+	/// Otherwise, see [`Build::from_entrances_to_any_core`].
+	///
+	/// # Returns
+	///
+	/// The [`Build`], and the [`StrategyStats`] of every [`Strategy`] which was run to create it.
+	///
+	/// # Errors
+	///
+	/// See [`Build::from_entrances_to_any_core`].
+	///
+	/// # Remarks
+	///
+	/// A [`Strategy::Portfolio`] runs each of its strategies at the same time, and keeps the
+	/// [`Build`] with the best [`Objective::score`] (using fewer blocks to break ties). Every
+	/// strategy reports to `on_progress`.
+	///
+	/// If there is a `candidate_scope`, each block is chosen with
+	/// [`Build::find_scored_block_placement`], scored by the `objective`.
+	///
+	/// Every strategy stops placing blocks once the `cancellation` is cancelled, so the [`Build`]
+	/// is the best one found so far.
+	#[allow(clippy::too_many_arguments)]
+	pub fn from_strategy(
+		tileset: &Tileset,
+		strategy: Strategy,
+		objective: &Objective,
+		movement: impl Into<MovementRules>,
+		max_blocks: Option<usize>,
+		candidate_scope: Option<usize>,
+		time_slices: &BTreeMap<Strategy, Duration>,
+		cancellation: &CancellationToken,
+		on_progress: impl FnMut(&Progress) + Send,
+	) -> Result<(Self, Vec<StrategyStats>)>
+	{
+		let movement = movement.into();
 
-			let shortest_path_vec = Vec::from(shortest_path);
+		// Check once up front, rather than in every strategy of a portfolio.
+		Build::initial_shortest_paths(tileset, movement)?;
 
-			// The shortest path for this region has had a block placed over it. Recalculate and try
-			// again!
-			if shortest_path_vec.iter().any(|coord| build.blocks.contains(coord))
+		let on_progress = Mutex::new(on_progress);
+		let run = |strategy: Strategy| -> Result<(Self, StrategyStats)> {
+			let report = |progress: &Progress| {
+				(on_progress.lock().expect("Expected progress reporting not to panic"))(progress)
+			};
+
+			let start = Instant::now();
+			let time_slice = time_slices.get(&strategy);
+			let cancellation = match time_slice
 			{
-				shortest_paths_by_region.insert(shortest_path!(), region_index);
-				continue;
-			}
+				Some(slice) => cancellation.with_deadline(start + *slice),
+				None => cancellation.clone(),
+			};
 
-			if let Some(coord) =
-				Build::find_valid_block_placement(tileset, &build.blocks, shortest_path_vec)
+			let build = match strategy
 			{
-				// It was valid, so insert it.
-				build.blocks.insert(coord);
-				build.try_remove_adjacent_to(&tileset, coord, diagonals);
+				Strategy::Priority => Build::priority(
+					tileset,
+					movement,
+					max_blocks,
+					candidate_scope,
+					objective,
+					&cancellation,
+					report,
+				)?,
+				Strategy::RoundRobin => Build::round_robin(
+					tileset,
+					movement,
+					max_blocks,
+					candidate_scope,
+					objective,
+					&cancellation,
+					report,
+				)?,
+				Strategy::Portfolio => unreachable!("Expected portfolio not to contain itself"),
+			};
 
-				// Recalculate the shortest path as well.
-				shortest_paths_by_region.insert(shortest_path!(), region_index);
-			}
+			let elapsed = Instant::now().duration_since(start);
+			let stats = StrategyStats {
+				strategy,
+				best: false,
+				blocks: build.blocks.len(),
+				milliseconds: elapsed.as_millis() as u64,
+				out_of_time: time_slice.is_some_and(|slice| elapsed >= *slice),
+				shortest_path_length: build.shortest_path_lengths(tileset, movement),
+			};
+
+			Ok((build, stats))
+		};
+
+		if strategy != Strategy::Portfolio
+		{
+			let (build, stats) = run(strategy)?;
+			return Ok((build, vec![StrategyStats { best: true, ..stats }]));
 		}
 
-		build
+		let results: Vec<_> =
+			Strategy::PORTFOLIO.par_iter().map(|s| run(*s)).collect::<Result<_>>()?;
+
+		let best = results
+			.iter()
+			.enumerate()
+			.max_by_key(|(_, (build, _))| {
+				(objective.score(tileset, build, movement), Reverse(build.blocks.len()))
+			})
+			.map(|(index, _)| index)
+			.expect("Expected the portfolio to contain at least one strategy");
+
+		let (builds, mut stats): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+		stats[best].best = true;
+		Ok((builds.into_iter().nth(best).expect("Expected best build to exist"), stats))
 	}
 
 	/// # Summary
 	///
-	/// Return whether or not the current [`Build`] prevents any entrance from reaching a core.
+	/// The [`ShortestPath`] from each region of a `tileset` to any core, before any blocks are
+	/// placed.
+	///
+	/// # Errors
+	///
+	/// * [`Error::NoPathToCore`], for the first region which has no such path.
+	fn initial_shortest_paths(
+		tileset: &Tileset,
+		movement: MovementRules,
+	) -> Result<Vec<ShortestPath>>
+	{
+		ShortestPath::from_entrances_to_any_core(tileset, Option::<&HashSet<_>>::None, movement)
+			.into_iter()
+			.enumerate()
+			.map(|(region, path)| path.ok_or(Error::NoPathToCore { region }))
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Return whether or not the current [`Build`] prevents any entrance from reaching a core
+	/// which it [targets](Tileset::targets).
 	fn is_valid(tileset: &Tileset, blocks: &impl Container<Coordinate>) -> bool
 	{
 		// A valid build only contains coordinates which are for `Empty` tiles
-		tileset.entrances_by_region.iter().all(|region| {
+		tileset.entrances_by_region.iter().enumerate().all(|(region, entrances)| {
 			// Additionally, there should be at least one entrance in every region which has a path
 			// to a core.
-			region.keys().any(|entrance| {
-				ShortestPath::from_grid_coordinate_to_tile(
-					&tileset.grid,
+			entrances.keys().any(|entrance| {
+				ShortestPath::from_grid_coordinate_to_target(
+					tileset,
 					Some(blocks),
+					region,
 					*entrance,
 					None,
-					Tile::Core,
 					false,
 				)
 				.is_some()
@@ -231,13 +685,346 @@ impl Build
 		})
 	}
 
+	/// # Summary
+	///
+	/// Remove blocks from this [`Build`] until none can be removed without changing the
+	/// [`Objective::scores`] of some region.
+	///
+	/// # Remarks
+	///
+	/// Unlike [`Build::try_remove_adjacent_to`], every block is considered (not just those near the
+	/// last placement), and paths only have to keep their score rather than their exact route.
+	///
+	/// Blocks are tried one at a time, in order, until none can be removed on its own. Then each
+	/// block which was removed is put back in turn, to see whether two or more others can go in its
+	/// place. This finds a smaller build where one block does the work of several, but it is not
+	/// an exhaustive search, so a smaller build with the same scores may still exist.
+	///
+	/// When the `objective` is [`robust`](Objective::robust) or has a
+	/// [`formula`](Objective::formula), a block is removed whenever doing so lowers neither the
+	/// [`Objective::score`] nor the total of the [`Objective::scores`], even if some region's path
+	/// gets shorter. Otherwise, a block which the rest of the build depends on could be removed
+	/// just because the build would then have nothing left to lose.
+	///
+	/// # Returns
+	///
+	/// The number of blocks which were removed.
+	pub fn minimize(
+		&mut self,
+		tileset: &Tileset,
+		objective: &Objective,
+		movement: impl Into<MovementRules>,
+	) -> usize
+	{
+		let movement = movement.into();
+		let expected_scores = objective.scores(tileset, self, movement);
+		let mut robust_score = objective.score(tileset, self, movement);
+		let original_len = self.blocks.len();
+
+		let mut removed = self.remove_redundant(
+			tileset,
+			objective,
+			movement,
+			&expected_scores,
+			&mut robust_score,
+			None,
+		);
+
+		let mut index = 0;
+		while let Some(restored) = removed.get(index).copied()
+		{
+			let (blocks, score) = (self.blocks.clone(), robust_score);
+			self.blocks.insert(restored);
+
+			let replaced = self.remove_redundant(
+				tileset,
+				objective,
+				movement,
+				&expected_scores,
+				&mut robust_score,
+				Some(restored),
+			);
+
+			// Putting one block back for one other is no better, so only a trade for two or more
+			// is kept. The build is then smaller, so this cannot go on forever.
+			if replaced.len() > 1
+			{
+				removed.remove(index);
+				removed.extend(replaced);
+				removed.extend(self.remove_redundant(
+					tileset,
+					objective,
+					movement,
+					&expected_scores,
+					&mut robust_score,
+					None,
+				));
+				index = 0;
+			}
+			else
+			{
+				(self.blocks, robust_score) = (blocks, score);
+				index += 1;
+			}
+		}
+
+		original_len - self.blocks.len()
+	}
+
+	/// # Summary
+	///
+	/// Remove every block of this [`Build`] (except the one `kept`) which can go without changing
+	/// the `expected_scores`, as described by [`Build::minimize`]. The `robust_score` is raised as
+	/// blocks are removed.
+	///
+	/// # Returns
+	///
+	/// The blocks which were removed, in the order they were.
+	fn remove_redundant(
+		&mut self,
+		tileset: &Tileset,
+		objective: &Objective,
+		movement: MovementRules,
+		expected_scores: &[Option<usize>],
+		robust_score: &mut usize,
+		kept: Option<Coordinate>,
+	) -> Vec<Coordinate>
+	{
+		let expected_total: usize = expected_scores.iter().flatten().sum();
+		let mut removed = Vec::new();
+
+		loop
+		{
+			let mut candidates: Vec<_> =
+				self.blocks.iter().copied().filter(|coord| Some(*coord) != kept).collect();
+			candidates.sort_unstable();
+
+			let len = removed.len();
+			removed.extend(candidates.into_iter().filter(|coord| {
+				self.blocks.remove(coord);
+				if objective.robust || objective.formula.is_some()
+				{
+					let total: usize =
+						objective.scores(tileset, self, movement).into_iter().flatten().sum();
+					let score = objective.score(tileset, self, movement);
+					if total >= expected_total && score >= *robust_score
+					{
+						*robust_score = score;
+						return true;
+					}
+				}
+				else if objective.scores(tileset, self, movement) == expected_scores
+				{
+					return true;
+				}
+
+				self.blocks.insert(*coord);
+				false
+			}));
+
+			// Removing a block may have made another redundant, so keep going until nothing
+			// changes.
+			if removed.len() == len
+			{
+				return removed;
+			}
+		}
+	}
+
+	/// # Summary
+	///
+	/// The length of the [`ShortestPath`] from each region to any core through this [`Build`].
+	pub fn shortest_path_lengths(
+		&self,
+		tileset: &Tileset,
+		movement: impl Into<MovementRules>,
+	) -> Vec<Option<usize>>
+	{
+		ShortestPath::from_entrances_to_any_core(tileset, Some(&self.blocks), movement)
+			.into_iter()
+			.map(|path| path.map(|p| p.len()))
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Divide the blocks of this [`Build`] between some number of `players`, keeping each player's
+	/// blocks close together.
+	///
+	/// # Parameters
+	///
+	/// * `players`, how many players are placing blocks.
+	/// * `budget`, the most blocks any one player may place. If [`None`], the blocks are shared
+	///   evenly.
+	///
+	/// # Returns
+	///
+	/// The blocks each player should place, in reading order.
+	pub fn split(&self, players: usize, budget: Option<usize>) -> Result<Vec<Vec<Coordinate>>>
+	{
+		/// # Summary
+		///
+		/// How many times to move each player's center to the middle of their blocks.
+		const ROUNDS: usize = 5;
+
+		let mut blocks: Vec<_> = self.blocks.iter().copied().collect();
+		blocks.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+		let players = players.max(1);
+		let budget = budget.unwrap_or_else(|| blocks.len().div_ceil(players));
+		if budget * players < blocks.len()
+		{
+			return Err(Error::OverBudget { blocks: blocks.len(), players, budget });
+		}
+
+		// Start each player as far away from the others as possible.
+		let mut centers = Vec::with_capacity(players);
+		if let Some(first) = blocks.first()
+		{
+			centers.push(*first);
+		}
+
+		while centers.len() < players.min(blocks.len())
+		{
+			centers.push(
+				*blocks
+					.iter()
+					.max_by_key(|coord| {
+						centers.iter().map(|center| center.distance_from(coord)).min().unwrap_or(0)
+					})
+					.expect("Expected there to be more blocks than centers"),
+			);
+		}
+
+		let mut assignments = vec![Vec::new(); players];
+		for _ in 0..ROUNDS
+		{
+			// Hand out the closest block to player pairings first, as long as the player has room.
+			let mut pairs: Vec<_> = blocks
+				.iter()
+				.enumerate()
+				.flat_map(|(block, coord)| {
+					centers
+						.iter()
+						.enumerate()
+						.map(move |(player, center)| (center.distance_from(coord), block, player))
+				})
+				.collect();
+			pairs.sort_unstable();
+
+			let mut assigned = vec![false; blocks.len()];
+			assignments.iter_mut().for_each(Vec::clear);
+			pairs.into_iter().for_each(|(_, block, player)| {
+				if !assigned[block] && assignments[player].len() < budget
+				{
+					assigned[block] = true;
+					assignments[player].push(blocks[block]);
+				}
+			});
+
+			centers.iter_mut().zip(assignments.iter()).filter(|(_, a)| !a.is_empty()).for_each(
+				|(center, assignment)| {
+					let (x, y) =
+						assignment.iter().fold((0, 0), |(x, y), coord| (x + coord.0, y + coord.1));
+					*center = Coordinate(x / assignment.len(), y / assignment.len());
+				},
+			);
+		}
+
+		assignments.iter_mut().for_each(|a| a.sort_unstable_by_key(|coord| (coord.1, coord.0)));
+		Ok(assignments)
+	}
+
+	/// # Summary
+	///
+	/// Remove every [`Tile::Block`] and [`Tile::TowerBlock`] from the `grid`, replacing each with
+	/// the [`Tile::Empty`] or [`Tile::TowerBase`] it was placed on, and collect them into a
+	/// [`Build`].
+	///
+	/// # Remarks
+	///
+	/// This is the inverse of [`Build::apply_to`].
+	pub fn take_from(grid: &mut [impl AsMut<[Tile]>]) -> Self
+	{
+		let mut blocks = HashSet::new();
+
+		grid.iter_mut().enumerate().for_each(|(y, row)| {
+			row.as_mut().iter_mut().enumerate().for_each(|(x, tile)| {
+				let under = match tile
+				{
+					Tile::Block => Tile::Empty,
+					Tile::TowerBlock => Tile::TowerBase,
+					_ => return,
+				};
+				*tile = under;
+				blocks.insert(Coordinate(x, y));
+			})
+		});
+
+		Build { blocks }
+	}
+
+	/// # Summary
+	///
+	/// Write this [`Build`] as a short code which can be shared in chat or a URL. It can be read
+	/// back with [`Build::from_code`].
+	///
+	/// # Remarks
+	///
+	/// The code is the `width` and `height` of the grid, followed by the lengths of each run of
+	/// tiles without and with blocks (in reading order), all in base 36. For example,
+	/// `gxe-3.2` is a 16 by 14 grid with blocks at `(3, 0)` and `(4, 0)`.
+	pub fn to_code(&self, width: usize, height: usize) -> String
+	{
+		let mut sorted: Vec<_> =
+			self.blocks.iter().map(|coord| coord.1 * width + coord.0).collect();
+		sorted.sort_unstable();
+
+		let mut runs = Vec::new();
+		let mut end = 0;
+		sorted.into_iter().for_each(|index| {
+			if index == end && !runs.is_empty()
+			{
+				*runs.last_mut().expect("Expected a run of blocks") += 1;
+			}
+			else
+			{
+				runs.push(index - end);
+				runs.push(1);
+			}
+			end = index + 1;
+		});
+
+		let mut code = format!("{}x{}", to_base_36(width), to_base_36(height));
+		if !runs.is_empty()
+		{
+			code.push('-');
+			code.push_str(&runs.into_iter().map(to_base_36).collect::<Vec<_>>().join("."));
+		}
+
+		code
+	}
+
 	/// # Summary
 	///
 	/// Try to remove all coordinates [`Adjacent`] to `coord` on the `tileset`, and see if removing
 	/// them from this [`Build`] would alter the [`ShortestPath::from_entrances_to_any_core`].
 	///
-	/// Returns `true` if an item was returned.
-	fn try_remove_adjacent_to(&mut self, tileset: &Tileset, coord: Coordinate, diagonals: bool)
+	/// # Remarks
+	///
+	/// Blocks are tried in batches: every untried block next to the last batch's removals is
+	/// checked on its own, in parallel. Blocks which could each be removed alone may still be
+	/// needed together (e.g. two walls of the same corridor), so the batch is removed at once and,
+	/// if that alters a path, rolled back and removed one at a time instead.
+	///
+	/// A block which could not open a corridor as short as any region's path (see
+	/// [`Corridors::could_shorten`]) is removed without searching for the paths again.
+	fn try_remove_adjacent_to(
+		&mut self,
+		tileset: &Tileset,
+		coord: Coordinate,
+		movement: MovementRules,
+	)
 	{
 		// Lazy load the expected shortest paths. We may not need to calculate it!
 		let mut expected_shortest_paths = None;
@@ -245,48 +1032,63 @@ impl Build
 		// Which coordinates we have already tried removing.
 		let mut visited = HashSet::<Coordinate>::new();
 
-		// Queue of `Adjacent`s we want to try.
-		let mut adjacent_queue = LinkedList::new();
-		adjacent_queue.push_back(Adjacent::from_grid_coordinate(&tileset.grid, &coord, diagonals));
+		// The coordinates whose adjacent blocks we want to try.
+		let mut frontier = vec![coord];
 
-		while let Some(adjacent) = adjacent_queue.pop_front()
+		while !frontier.is_empty()
 		{
-			adjacent.for_each(|adjacent_coord| {
-				if self.blocks.contains(&adjacent_coord) && !visited.contains(&adjacent_coord)
-				{
-					// Mark this coordinate as visited.
-					visited.insert(adjacent_coord);
+			let candidates: Vec<_> = frontier
+				.into_iter()
+				.flat_map(|c| Adjacent::from_grid_coordinate(&tileset.grid, &c, movement.diagonals))
+				.map(|(_, adjacent_coord)| adjacent_coord)
+				.filter(|adjacent_coord| {
+					self.blocks.contains(adjacent_coord) && visited.insert(*adjacent_coord)
+				})
+				.collect();
 
-					// We'll need this value to be `Some`thing now.
-					if expected_shortest_paths.is_none()
-					{
-						expected_shortest_paths = Some(ShortestPath::from_entrances_to_any_core(
-							&tileset,
-							Some(&self.blocks),
-							diagonals,
-						));
-					}
+			if candidates.is_empty()
+			{
+				break;
+			}
 
-					// If a coordinate was removed,
-					if self.try_remove_coord(
-						tileset,
-						expected_shortest_paths
-							.as_ref()
-							.expect("Expected `shortest_path` to be `Some` by now"),
-						coord,
-						diagonals,
-					)
-					{
-						// Look at adjacent coordinates to see if any of those can be removed
-						// either.
-						adjacent_queue.push_back(Adjacent::from_grid_coordinate(
-							&tileset.grid,
-							&adjacent_coord,
-							diagonals,
-						));
+			// We'll need this value to be `Some`thing now.
+			let expected = expected_shortest_paths.get_or_insert_with(|| {
+				ShortestPath::from_entrances_to_any_core(tileset, Some(&self.blocks), movement)
+			});
+
+			let corridors = Corridors::new(tileset, &self.blocks, movement);
+			let removable: Vec<_> = candidates
+				.par_iter()
+				.copied()
+				.filter(|candidate| {
+					!corridors.could_shorten(tileset, expected, *candidate, movement) || {
+						let removal =
+							TempRemoval { blocks: &self.blocks, removed_block: *candidate };
+						ShortestPath::from_entrances_to_any_core(tileset, Some(&removal), movement) ==
+							*expected
 					}
-				}
+				})
+				.collect();
+
+			removable.iter().for_each(|c| {
+				self.blocks.remove(c);
 			});
+
+			// Look at adjacent coordinates to see if any of those can be removed either.
+			frontier = if removable.len() > 1 &&
+				ShortestPath::from_entrances_to_any_core(tileset, Some(&self.blocks), movement) !=
+					*expected
+			{
+				self.blocks.extend(removable.iter().copied());
+				removable
+					.into_iter()
+					.filter(|c| self.try_remove_coord(tileset, expected, *c, movement))
+					.collect()
+			}
+			else
+			{
+				removable
+			};
 		}
 	}
 
@@ -295,20 +1097,38 @@ impl Build
 	/// See if removing `coord` them from this [`Build`]  would alter the
 	/// [`ShortestPath::from_entrances_to_any_core`], and if it wouldn't remove it.
 	///
+	/// # Remarks
+	///
+	/// The paths are only searched for again if the [`Corridors`] say the removal could open a
+	/// shorter one.
+	///
 	/// Returns `true` if an item was removed.
 	fn try_remove_coord(
 		&mut self,
 		tileset: &Tileset,
 		expected_shortest_paths: &[Option<ShortestPath>],
 		coord: Coordinate,
-		diagonals: bool,
+		movement: MovementRules,
 	) -> bool
 	{
-		// If the coordinate was removed (and therefore part of the build in the first place)
-		if self.blocks.remove(&coord)
+		// Only a coordinate which is part of the build can be removed.
+		if !self.blocks.contains(&coord)
+		{
+			return false;
+		}
+
+		let could_shorten = Corridors::new(tileset, &self.blocks, movement).could_shorten(
+			tileset,
+			expected_shortest_paths,
+			coord,
+			movement,
+		);
+
+		self.blocks.remove(&coord);
+		if could_shorten
 		{
 			let actual_shortest_path =
-				ShortestPath::from_entrances_to_any_core(&tileset, Some(&self.blocks), diagonals);
+				ShortestPath::from_entrances_to_any_core(tileset, Some(&self.blocks), movement);
 
 			// If it changed ANYTHING about the shortest paths
 			if actual_shortest_path != expected_shortest_paths
@@ -316,13 +1136,10 @@ impl Build
 				self.blocks.insert(coord);
 				return false;
 			}
-
-			// Wasn't needed, return true.
-			return true;
 		}
 
-		// Nothing happened, return false.
-		false
+		// Wasn't needed, return true.
+		true
 	}
 }
 
@@ -331,16 +1148,289 @@ mod tests
 {
 	use std::time::Instant;
 
-	use super::{Build, Coordinate, HashSet, Tileset};
-	use crate::map::tileset::tests::PARK_TWO_SPAWN;
+	use super::{
+		BTreeMap,
+		Build,
+		CancellationToken,
+		Coordinate,
+		Duration,
+		Error,
+		HashSet,
+		MovementRules,
+		Objective,
+		ShortestPath,
+		Strategy,
+		Tileset,
+	};
+	use crate::map::{
+		tileset::{tests::PARK_TWO_SPAWN, Targeting},
+		Map,
+		Tile::*,
+	};
 
 	#[test]
-	fn is_valid()
+	fn apply_to()
+	{
+		let mut grid = vec![vec![Spawn, Empty, Empty], vec![Empty, Empty, Core]];
+		let build = Build { blocks: [Coordinate(1, 0), Coordinate(3, 1)].into_iter().collect() };
+
+		let start = Instant::now();
+		let result = build.apply_to(&mut grid);
+		println!("Build::apply_to {}us", Instant::now().duration_since(start).as_micros());
+
+		// Nothing is placed when any block is off of the grid.
+		assert!(matches!(
+			result,
+			Err(Error::OutOfBounds { coord: Coordinate(3, 1), dims: (3, 2) })
+		));
+		assert_eq!(grid[0][1], Empty);
+
+		let build = Build { blocks: [Coordinate(1, 0)].into_iter().collect() };
+		build.apply_to(&mut grid).unwrap();
+		assert_eq!(grid[0][1], Block);
+	}
+
+	#[test]
+	fn from_code()
 	{
-		let test_tileset = Tileset::new(
-			PARK_TWO_SPAWN.iter().map(|inner| inner.iter().copied().collect()).collect(),
+		let build = Build {
+			blocks: [
+				Coordinate(3, 0),
+				Coordinate(4, 0),
+				Coordinate(15, 0),
+				Coordinate(0, 1),
+				Coordinate(7, 13),
+			]
+			.into_iter()
+			.collect(),
+		};
+
+		let start = Instant::now();
+		let code = build.to_code(16, 14);
+		println!("Build::to_code {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(code, "gxe-3.2.a.2.5i.1");
+		assert_eq!(Build::from_code(&code).unwrap(), (build, (16, 14)));
+		assert_eq!(Build { blocks: HashSet::new() }.to_code(16, 14), "gxe");
+		assert_eq!(Build::from_code("gxe").unwrap().0.blocks.len(), 0);
+		assert!(Build::from_code("gxe-zz.1").is_err());
+		assert!(Build::from_code("nonsense").is_err());
+
+		// Oversized runs and grids are refused rather than decoded.
+		assert!(Build::from_code("1x1-0.zzzzzzzzzzzz").is_err());
+		assert!(Build::from_code("1x1-zzzzzzzzzzzzz.zzzzzzzzzzzzz").is_err());
+		assert!(Build::from_code("0x1").is_err());
+		assert!(Build::from_code("zzzzzzxzzzzzz-0.1").is_err());
+	}
+
+	#[test]
+	fn from_entrances_to_any_core_with_priority()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1      2      3      4       5      6      7      8
+			vec![Spawn, Empty, Empty, Empty, Impass, Empty, Empty, Empty, Spawn], // 0
+			vec![Empty, Empty, Empty, Empty, Impass, Empty, Empty, Empty, Empty], // 1
+			vec![Empty, Empty, Empty, Empty, Impass, Empty, Empty, Empty, Empty], // 2
+			vec![Empty, Empty, Empty, Empty, Impass, Empty, Empty, Empty, Empty], // 3
+			vec![Empty, Empty, Empty, Empty, Core,   Empty, Empty, Empty, Empty], // 4
+		]);
+		let before = Build { blocks: HashSet::new() }.shortest_path_lengths(&test_tileset, false);
+
+		let start = Instant::now();
+		let build =
+			Build::from_entrances_to_any_core_with_priority(&test_tileset, false, None, |_| ())
+				.unwrap();
+		println!(
+			"Build::from_entrances_to_any_core_with_priority {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		// Both regions start out with paths of the same length, and blocks are placed for both.
+		assert_eq!(before[0], before[1]);
+		assert!(build.blocks.iter().any(|coord| coord.0 < 4));
+		assert!(build.blocks.iter().any(|coord| coord.0 > 4));
+	}
+
+	#[test]
+	fn find_scored_block_placement()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let blocks = HashSet::new();
+		let path =
+			ShortestPath::from_region_to_target(&test_tileset, Some(&blocks), 0, false).unwrap();
+		let length_with = |coord: Coordinate| {
+			let blocks: HashSet<_> = [coord].into_iter().collect();
+			ShortestPath::from_region_to_target(&test_tileset, Some(&blocks), 0, false)
+				.unwrap()
+				.len()
+		};
+
+		let start = Instant::now();
+		let on_path = Build::find_scored_block_placement(
+			&test_tileset,
+			&blocks,
+			0,
+			path.coordinates(),
+			0,
+			&Objective::default(),
+			false,
+		)
+		.unwrap();
+		println!(
+			"Build::find_scored_block_placement {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		// Without any scope, only the path is looked at, and the best tile of it is chosen.
+		assert!(path.coordinates().contains(&on_path));
+		let first_valid =
+			Build::find_valid_block_placement(&test_tileset, &blocks, path.coordinates()).unwrap();
+		assert!(length_with(on_path) >= length_with(first_valid));
+
+		// Looking further can only find a placement which scores as well or better.
+		let nearby = Build::find_scored_block_placement(
+			&test_tileset,
+			&blocks,
+			0,
+			path.coordinates(),
+			2,
+			&Objective::default(),
+			false,
+		)
+		.unwrap();
+		assert!(length_with(nearby) >= length_with(on_path));
+
+		// A block at (2, 0) leads the path across the hazard, and one at (6, 0) leads it the long
+		// way round.
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1       2       3       4       5      6       7      8
+			vec![Spawn,  Empty,  Empty,  Empty,  Empty,  Empty, Empty,  Empty, Core],   // 0
+			vec![Impass, Empty,  Hazard, Empty,  Impass, Empty, Impass, Empty, Impass], // 1
+			vec![Impass, Impass, Impass, Impass, Impass, Empty, Empty,  Empty, Impass], // 2
+		]);
+		let path =
+			ShortestPath::from_region_to_target(&test_tileset, Some(&blocks), 0, false).unwrap();
+		let place = |hazard_damage: usize| {
+			Build::find_scored_block_placement(
+				&test_tileset,
+				&blocks,
+				0,
+				path.coordinates(),
+				0,
+				&Objective { hazard_damage, ..Default::default() },
+				false,
+			)
+			.unwrap()
+		};
+
+		assert_eq!(place(0), Coordinate(6, 0));
+		assert_eq!(place(10), Coordinate(2, 0));
+	}
+
+	#[test]
+	fn from_strategy()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let start = Instant::now();
+		let (build, stats) = Build::from_strategy(
+			&test_tileset,
+			Strategy::Portfolio,
+			&Objective::default(),
+			false,
+			Some(10),
+			None,
+			&BTreeMap::new(),
+			&CancellationToken::new(),
+			|_| (),
+		)
+		.unwrap();
+		println!(
+			"Build::from_strategy(Portfolio) {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		// A region which is walled off from the core cannot be solved.
+		let walled_off = Tileset::new(vec![vec![Spawn, Empty, Impass, Core]]);
+		assert!(matches!(
+			Build::from_strategy(
+				&walled_off,
+				Strategy::Portfolio,
+				&Objective::default(),
+				false,
+				None,
+				None,
+				&BTreeMap::new(),
+				&CancellationToken::new(),
+				|_| ()
+			),
+			Err(Error::NoPathToCore { region: 0 })
+		));
+
+		// A solve which is cancelled before it starts places nothing, but still reports each
+		// strategy.
+		let cancelled = CancellationToken::new();
+		cancelled.clone().cancel();
+		let (empty, empty_stats) = Build::from_strategy(
+			&test_tileset,
+			Strategy::Portfolio,
+			&Objective::default(),
+			false,
+			Some(10),
+			None,
+			&BTreeMap::new(),
+			&cancelled,
+			|_| (),
+		)
+		.unwrap();
+		assert!(empty.blocks.is_empty());
+		assert_eq!(empty_stats.len(), Strategy::PORTFOLIO.len());
+
+		// A strategy which has no time at all places nothing, and the other one's build is kept.
+		let (timed, timed_stats) = Build::from_strategy(
+			&test_tileset,
+			Strategy::Portfolio,
+			&Objective::default(),
+			false,
+			Some(10),
+			None,
+			&[(Strategy::Priority, Duration::ZERO)].into_iter().collect(),
+			&CancellationToken::new(),
+			|_| (),
+		)
+		.unwrap();
+		assert!(!timed.blocks.is_empty());
+		assert_eq!(
+			timed_stats.iter().map(|s| (s.strategy, s.best, s.out_of_time)).collect::<Vec<_>>(),
+			vec![(Strategy::Priority, false, true), (Strategy::RoundRobin, true, false)]
+		);
+
+		// Every strategy in the portfolio should be reported.
+		assert_eq!(
+			stats.iter().map(|s| s.strategy).collect::<Vec<_>>(),
+			Strategy::PORTFOLIO.to_vec()
 		);
 
+		assert_eq!(stats.iter().filter(|s| s.best).count(), 1);
+
+		// The best build should be the one which is kept.
+		let total = |lengths: &[Option<usize>]| lengths.iter().flatten().sum::<usize>();
+		assert_eq!(
+			total(&build.shortest_path_lengths(&test_tileset, false)),
+			stats.iter().map(|s| total(&s.shortest_path_length)).max().unwrap()
+		);
+	}
+
+	#[test]
+	fn is_valid()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
 		let start = Instant::now();
 
 		// Empty build should be valid for a valid Tileset.
@@ -369,5 +1459,224 @@ mod tests
 		));
 
 		println!("Build::is_valid {}us", Instant::now().duration_since(start).as_micros() / 3);
+
+		// Cutting a spawn off from the core it targets is invalid, even if another core is open.
+		let mut two_cores = Tileset::new(vec![vec![Core, Empty, Spawn, Empty, Empty, Core]]);
+		let blocked = [Coordinate(1, 0)].iter().copied().collect::<HashSet<_>>();
+		assert!(Build::is_valid(&two_cores, &blocked));
+
+		two_cores.set_targeting(Targeting::NearestCore);
+		assert!(!Build::is_valid(&two_cores, &blocked));
+	}
+
+	#[test]
+	fn minimize()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let mut build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(15), |_| ()).unwrap();
+		let expected_lengths = build.shortest_path_lengths(&test_tileset, false);
+
+		// A block in the corner of the map cannot lengthen anything.
+		build.blocks.insert(Coordinate(15, 0));
+		let len = build.blocks.len();
+
+		let start = Instant::now();
+		let removed = build.minimize(&test_tileset, &Objective::default(), false);
+		println!("Build::minimize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert!(removed >= 1);
+		assert_eq!(build.blocks.len(), len - removed);
+		assert!(!build.blocks.contains(&Coordinate(15, 0)));
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), expected_lengths);
+	}
+
+	#[test]
+	fn minimize_greedy()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1       2       3       4
+			vec![Spawn, Empty,  Empty,  Empty,  Core],  // 0
+			vec![Empty, Impass, Impass, Impass, Empty], // 1
+			vec![Empty, Empty,  Empty,  Empty,  Empty], // 2
+		]);
+
+		// Either block closes the short way on its own, so whichever is tried first is removed,
+		// and the other has to stay.
+		let mut build =
+			Build { blocks: [Coordinate(1, 0), Coordinate(2, 0)].into_iter().collect() };
+		assert_eq!(build.minimize(&test_tileset, &Objective::default(), false), 1);
+		assert_eq!(build.blocks, [Coordinate(2, 0)].into_iter().collect());
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), vec![Some(9)]);
+	}
+
+	#[test]
+	fn minimize_robust()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1      2      3
+			vec![Spawn,  Empty, Empty, Core],  // 0
+			vec![Impass, Empty, Empty, Empty], // 1
+		]);
+		let objective = Objective { robust: true, ..Default::default() };
+
+		// Without its only block, the build scores no worse when robust, but its path is shorter.
+		let mut build = Build { blocks: [Coordinate(2, 0)].into_iter().collect() };
+		assert_eq!(build.minimize(&test_tileset, &objective, false), 0);
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), vec![Some(6)]);
+	}
+
+	#[test]
+	fn minimize_trade()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1       2       3       4       5       6
+			vec![Spawn,  Empty,  Empty,  Empty,  Empty,  Empty,  Empty], // 0
+			vec![Impass, Impass, Empty,  Empty,  Impass, Impass, Empty], // 1
+			vec![Impass, Empty,  Empty,  Empty,  Impass, Impass, Empty], // 2
+			vec![Impass, Empty,  Impass, Impass, Impass, Impass, Empty], // 3
+			vec![Core,   Empty,  Empty,  Empty,  Empty,  Empty,  Empty], // 4
+		]);
+
+		// Either the block at the bottom of the short way or the two at its top close it. The one
+		// is tried first, so it goes, and then neither of the two can go on its own; but both can
+		// once it is put back.
+		let mut build = Build {
+			blocks: [Coordinate(1, 3), Coordinate(2, 1), Coordinate(3, 1)].into_iter().collect(),
+		};
+		let lengths = build.shortest_path_lengths(&test_tileset, false);
+		assert_eq!(build.minimize(&test_tileset, &Objective::default(), false), 2);
+		assert_eq!(build.blocks, [Coordinate(1, 3)].into_iter().collect());
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), lengths);
+	}
+
+	#[test]
+	fn minimize_with_formula()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1      2      3
+			vec![Spawn,  Empty, Empty, Core],  // 0
+			vec![Impass, Empty, Empty, Empty], // 1
+			vec![Empty,  Empty, Empty, Empty], // 2
+		]);
+		let objective = Objective {
+			formula: Some("min_path - 3*blocks".parse().unwrap()),
+			..Default::default()
+		};
+		let mut build =
+			Build { blocks: [Coordinate(0, 2), Coordinate(2, 0)].into_iter().collect() };
+
+		// Each removal raises the formula, but only the block in the dead end keeps the path.
+		assert_eq!(build.minimize(&test_tileset, &objective, false), 1);
+		assert_eq!(build.blocks, [Coordinate(2, 0)].into_iter().collect());
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), vec![Some(6)]);
+	}
+
+	#[test]
+	fn scored_candidates()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0     1       2      3      4
+			vec![Spawn,  Empty,  Empty, Empty,  Core],   // 0
+			vec![Impass, Impass, Empty, Impass, Impass], // 1
+			vec![Impass, Empty,  Empty, Impass, Impass], // 2
+		]);
+		let blocks = HashSet::new();
+		let path = ShortestPath::from_entrances_to_any_core(&test_tileset, Some(&blocks), false)
+			.remove(0)
+			.unwrap();
+		let diagonals = MovementRules { diagonals: true, ..Default::default() };
+
+		// The dead end below the path is only worth scoring when a block there could stop a
+		// diagonal move.
+		let (candidates, on_path) =
+			Build::scored_candidates(&test_tileset, &blocks, path.coordinates(), 2, false.into());
+		assert_eq!(candidates, vec![Coordinate(3, 0), Coordinate(2, 0), Coordinate(1, 0)]);
+		assert_eq!(on_path, 3);
+
+		let (candidates, _) =
+			Build::scored_candidates(&test_tileset, &blocks, path.coordinates(), 2, diagonals);
+		assert_eq!(candidates[3..], [Coordinate(2, 1), Coordinate(1, 2), Coordinate(2, 2)]);
+	}
+
+	#[test]
+	fn split()
+	{
+		let build = Build {
+			blocks: [
+				Coordinate(0, 0),
+				Coordinate(1, 0),
+				Coordinate(0, 1),
+				Coordinate(9, 9),
+				Coordinate(8, 9),
+				Coordinate(9, 8),
+			]
+			.iter()
+			.copied()
+			.collect(),
+		};
+
+		let start = Instant::now();
+		let split = build.split(2, None).unwrap();
+		println!("Build::split {}us", Instant::now().duration_since(start).as_micros());
+
+		// Each player should get one corner.
+		assert_eq!(split, vec![vec![Coordinate(0, 0), Coordinate(1, 0), Coordinate(0, 1)], vec![
+			Coordinate(9, 8),
+			Coordinate(8, 9),
+			Coordinate(9, 9)
+		],]);
+
+		// Not enough room for everyone.
+		assert!(build.split(2, Some(2)).is_err());
+	}
+
+	#[test]
+	fn take_from()
+	{
+		let mut map: Map = serde_json::from_str(
+			r#"{"name": "test", "grid": [["Spawn", "Empty", "TowerBase", "Empty", "Core"]]}"#,
+		)
+		.unwrap();
+		let build = Build { blocks: [Coordinate(1, 0), Coordinate(2, 0)].into_iter().collect() };
+		build.apply_to(&mut map.grid).unwrap();
+		assert_eq!(map.grid[0][1..3], [Block, TowerBlock]);
+
+		// Writing and reading the solved map gives back both the build and the tower base.
+		let mut read: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+		assert_eq!(Build::take_from(&mut read.grid), build);
+		assert_eq!(read.grid, vec![vec![Spawn, Empty, TowerBase, Empty, Core]]);
+	}
+
+	#[test]
+	fn try_remove_adjacent_to()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let mut build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(15), |_| ()).unwrap();
+		let expected_lengths = build.shortest_path_lengths(&test_tileset, false);
+
+		// A clump of blocks in the corner of the map cannot lengthen anything.
+		let corner = [Coordinate(14, 0), Coordinate(15, 0), Coordinate(15, 1)];
+		build.blocks.extend(corner);
+
+		let start = Instant::now();
+		build.try_remove_adjacent_to(&test_tileset, Coordinate(15, 0), false.into());
+		println!(
+			"Build::try_remove_adjacent_to {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		assert!(corner.iter().all(|coord| !build.blocks.contains(coord)));
+		assert_eq!(build.shortest_path_lengths(&test_tileset, false), expected_lengths);
 	}
 }
@@ -0,0 +1,96 @@
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Instant,
+};
+
+/// # Summary
+///
+/// A flag which asks a [`Build`](super::Build) being created to stop early, e.g. from another
+/// thread.
+///
+/// # Remarks
+///
+/// Clones share the same flag, so one clone can be handed to the solver while another is kept to
+/// [`cancel`](CancellationToken::cancel) it. The solver checks the flag between placements, and
+/// returns the build as it was when it stopped.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken
+{
+	cancelled: Arc<AtomicBool>,
+
+	/// # Summary
+	///
+	/// See [`CancellationToken::with_deadline`].
+	deadline: Option<Instant>,
+}
+
+impl CancellationToken
+{
+	/// # Summary
+	///
+	/// Ask every solve which was given this token (or a clone of it) to stop.
+	pub fn cancel(&self)
+	{
+		self.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	/// # Summary
+	///
+	/// Whether [`CancellationToken::cancel`] has been called on this token or a clone of it, or
+	/// its [deadline](CancellationToken::with_deadline) has passed.
+	pub fn is_cancelled(&self) -> bool
+	{
+		self.cancelled.load(Ordering::Relaxed) ||
+			self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+	}
+
+	/// # Summary
+	///
+	/// A token which has not been cancelled.
+	pub fn new() -> Self
+	{
+		Self::default()
+	}
+
+	/// # Summary
+	///
+	/// A clone of this token which is also cancelled once the `deadline` passes, e.g. to give a
+	/// solve a time limit. Cancelling either one still cancels the other.
+	pub fn with_deadline(&self, deadline: Instant) -> Self
+	{
+		Self {
+			cancelled: Arc::clone(&self.cancelled),
+			deadline: Some(self.deadline.map_or(deadline, |d| d.min(deadline))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::{Duration, Instant};
+
+	use super::CancellationToken;
+
+	#[test]
+	fn with_deadline()
+	{
+		let token = CancellationToken::new();
+		let later = token.with_deadline(Instant::now() + Duration::from_secs(60));
+		let passed = token.with_deadline(Instant::now());
+
+		// A deadline only cancels the clone it was given to.
+		assert!(passed.is_cancelled());
+		assert!(!token.is_cancelled() && !later.is_cancelled());
+
+		// The earlier of two deadlines is kept.
+		assert!(passed.with_deadline(Instant::now() + Duration::from_secs(60)).is_cancelled());
+
+		// Cancelling any clone cancels the rest.
+		later.cancel();
+		assert!(token.is_cancelled());
+	}
+}
@@ -0,0 +1,160 @@
+use super::{Adjacent, Coordinate, MovementRules, ShortestPath, Tileset};
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IntoParallelIterator, ParallelIterator};
+use crate::{map::DistanceField, Container};
+
+/// # Summary
+///
+/// How far each tile is from the entrances of each region, and from the cores it targets, through
+/// some blocks. Used to tell whether removing a block could open a shorter corridor without
+/// searching for every region's [`ShortestPath`] again.
+///
+/// # Remarks
+///
+/// Removing a block only shortens distances, so these must be measured again after any block is
+/// removed.
+pub(super) struct Corridors
+{
+	/// # Summary
+	///
+	/// For each region, the distance of each tile from its nearest open entrance, the distance of
+	/// each tile from its nearest target, and the shortest distance of any entrance from its
+	/// spawn.
+	by_region: Vec<(DistanceField, DistanceField, usize)>,
+}
+
+impl Corridors
+{
+	/// # Summary
+	///
+	/// Whether removing the block on `coord` could make some region's path no longer than the
+	/// `expected_shortest_paths`, or give a path to a region which had none.
+	///
+	/// # Remarks
+	///
+	/// Any path which the removal opens must step from some tile next to `coord` to another,
+	/// either through `coord` or by cutting its corner, so it is at least as long as the distance
+	/// to the first tile plus the distance from the second. When that is longer than the current
+	/// path for every region, the paths cannot change.
+	pub(super) fn could_shorten(
+		&self,
+		tileset: &Tileset,
+		expected_shortest_paths: &[Option<ShortestPath>],
+		coord: Coordinate,
+		movement: MovementRules,
+	) -> bool
+	{
+		let neighbors: Vec<_> =
+			Adjacent::from_grid_coordinate(&tileset.grid, &coord, movement.diagonals)
+				.into_iter()
+				.map(|(_, adjacent_coord)| adjacent_coord)
+				.collect();
+
+		self.by_region.iter().zip(expected_shortest_paths).enumerate().any(
+			|(region, ((from_entrances, to_targets, start_distance), expected))| {
+				// An opened entrance can start a path anywhere.
+				if tileset.entrances_by_region[region].contains_key(&coord)
+				{
+					return true;
+				}
+
+				let shortest = neighbors
+					.iter()
+					.filter_map(|first| from_entrances.get(first).map(|d| (first, d)))
+					.flat_map(|(first, from_distance)| {
+						neighbors.iter().filter(move |second| *second != first).filter_map(
+							move |second| {
+								to_targets
+									.get(second)
+									.map(|to_distance| from_distance + to_distance)
+							},
+						)
+					})
+					.min();
+
+				// Both tiles are on the path as well as the steps between them.
+				shortest.is_some_and(|distance| {
+					expected.as_ref().is_none_or(|path| start_distance + distance + 2 <= path.len())
+				})
+			},
+		)
+	}
+
+	/// # Summary
+	///
+	/// Measure the [`Corridors`] of every region of a `tileset` through some `blocks`.
+	pub(super) fn new(
+		tileset: &Tileset,
+		blocks: &impl Container<Coordinate>,
+		movement: MovementRules,
+	) -> Self
+	{
+		let by_region = (0..tileset.entrances_by_region.len())
+			.into_par_iter()
+			.map(|region| {
+				let entrances = &tileset.entrances_by_region[region];
+				let open: Vec<_> =
+					entrances.keys().copied().filter(|coord| !blocks.contains(coord)).collect();
+
+				let targets: Vec<_> = match tileset.targets(region)
+				{
+					Some(targets) => targets.iter().copied().collect(),
+					None => tileset.core_coordinates().to_vec(),
+				};
+
+				(
+					DistanceField::to_coordinates(&tileset.grid, Some(blocks), open, movement),
+					DistanceField::to_coordinates(&tileset.grid, Some(blocks), targets, movement),
+					entrances.values().min().copied().unwrap_or(0),
+				)
+			})
+			.collect();
+
+		Self { by_region }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Coordinate, Corridors, ShortestPath, Tileset};
+	use crate::map::{tileset::tests::PARK_TWO_SPAWN, Build};
+
+	#[test]
+	fn could_shorten()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let mut build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(15), |_| ()).unwrap();
+		build.blocks.insert(Coordinate(15, 0));
+		let expected =
+			ShortestPath::from_entrances_to_any_core(&test_tileset, Some(&build.blocks), false);
+
+		let start = Instant::now();
+		let corridors = Corridors::new(&test_tileset, &build.blocks, false.into());
+		println!("Corridors::new {}us", Instant::now().duration_since(start).as_micros());
+
+		// A block in the corner of the map cannot open anything.
+		assert!(!corridors.could_shorten(
+			&test_tileset,
+			&expected,
+			Coordinate(15, 0),
+			false.into()
+		));
+
+		// Every block whose removal does shorten a path must be caught.
+		build.blocks.iter().for_each(|coord| {
+			let mut without = build.blocks.clone();
+			without.remove(coord);
+			if ShortestPath::from_entrances_to_any_core(&test_tileset, Some(&without), false) !=
+				expected
+			{
+				assert!(corridors.could_shorten(&test_tileset, &expected, *coord, false.into()));
+			}
+		});
+	}
+}
@@ -0,0 +1,57 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+use crate::map::{coordinate, Coordinate};
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("{:?} is not a valid build code", code))]
+	InvalidCode
+	{
+		code: String
+	},
+
+	#[snafu(display("Spawn region {} has no path to a core", region))]
+	NoPathToCore
+	{
+		region: usize
+	},
+
+	#[snafu(display("Block {:?} is not on a grid {} wide and {} tall", coord, dims.0, dims.1))]
+	OutOfBounds
+	{
+		coord: Coordinate, dims: (usize, usize)
+	},
+
+	#[snafu(display(
+		"{} blocks cannot be split between {} players with {} blocks each",
+		blocks,
+		players,
+		budget
+	))]
+	OverBudget
+	{
+		blocks: usize, players: usize, budget: usize
+	},
+
+	#[snafu(display("There is no strategy named {:?}", name))]
+	UnknownStrategy
+	{
+		name: String
+	},
+}
+
+impl From<coordinate::Error> for Error
+{
+	fn from(err: coordinate::Error) -> Self
+	{
+		match err
+		{
+			coordinate::Error::OutOfBounds { coord, dims } => Error::OutOfBounds { coord, dims },
+		}
+	}
+}
+
+pub type Result<T> = StdResult<T, Error>;
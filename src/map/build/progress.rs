@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// # Summary
+///
+/// A snapshot of how far along a [`Build`](super::Build) is while it is being created.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Progress
+{
+	/// # Summary
+	///
+	/// The longest that each region's shortest path has been so far, or [`None`] if it has not
+	/// been calculated yet.
+	///
+	/// # Remarks
+	///
+	/// A block can shorten some other region's path, so this may be longer than the
+	/// [`shortest_path_length`](Progress::shortest_path_length).
+	#[serde(default)]
+	pub best_path_length: Vec<Option<usize>>,
+
+	/// # Summary
+	///
+	/// How many blocks are currently placed.
+	pub blocks: usize,
+
+	/// # Summary
+	///
+	/// How many times the strategy has tried to place a block.
+	pub iterations: usize,
+
+	/// # Summary
+	///
+	/// The most recently calculated length of each region's shortest path, or [`None`] if it has
+	/// not been calculated yet.
+	pub shortest_path_length: Vec<Option<usize>>,
+}
+
+impl Progress
+{
+	/// # Summary
+	///
+	/// A [`Progress`] for a tileset with some number of `regions`, before anything is calculated.
+	pub(super) fn new(regions: usize) -> Self
+	{
+		Self {
+			best_path_length: vec![None; regions],
+			shortest_path_length: vec![None; regions],
+			..Default::default()
+		}
+	}
+
+	/// # Summary
+	///
+	/// Record the `length` of a `region`'s shortest path, keeping the longest one as its best.
+	pub(super) fn set_length(&mut self, region: usize, length: usize)
+	{
+		self.shortest_path_length[region] = Some(length);
+		let best = &mut self.best_path_length[region];
+		*best = Some(best.map_or(length, |b| b.max(length)));
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::Progress;
+
+	#[test]
+	fn set_length()
+	{
+		let mut progress = Progress::new(2);
+		progress.set_length(0, 10);
+		progress.set_length(0, 7);
+		progress.set_length(1, 4);
+
+		assert_eq!(progress.shortest_path_length, vec![Some(7), Some(4)]);
+		assert_eq!(progress.best_path_length, vec![Some(10), Some(4)]);
+	}
+}
@@ -0,0 +1,562 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Build, CancellationToken, Error, Progress, Result, StepTrace, TempBuild, VALID_BUILD};
+use crate::map::{
+	tileset::Tileset,
+	Coordinate,
+	DistanceField,
+	MovementRules,
+	Objective,
+	ShortestPath,
+};
+
+/// # Summary
+///
+/// What changed during a single [`SolverSession::step`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Step
+{
+	/// # Summary
+	///
+	/// The blocks which were placed, in reading order.
+	pub added: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The region whose shortest path was lengthened.
+	pub region: usize,
+
+	/// # Summary
+	///
+	/// The blocks which were taken away because they no longer lengthened any path, in reading
+	/// order.
+	pub removed: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The most recently calculated length of each region's shortest path, after this step.
+	pub shortest_path_length: Vec<Option<usize>>,
+
+	/// # Summary
+	///
+	/// What was looked at to make this step, if the session is
+	/// [traced](SolverSession::set_trace).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub trace: Option<StepTrace>,
+}
+
+/// # Summary
+///
+/// A [`Strategy::RoundRobin`](super::Strategy::RoundRobin) solve which is run one placement at a
+/// time, so that each change can be shown as it happens.
+///
+/// # Remarks
+///
+/// Call [`SolverSession::step`] until it returns [`None`], and then take the finished [`Build`]
+/// with [`SolverSession::into_build`].
+#[derive(Clone, Debug)]
+pub struct SolverSession<'tileset>
+{
+	build: Build,
+
+	/// # Summary
+	///
+	/// See [`SolverSession::set_cancellation`].
+	cancellation: Option<CancellationToken>,
+
+	/// # Summary
+	///
+	/// See [`SolverSession::set_candidate_scope`].
+	candidate_scope: Option<usize>,
+
+	/// # Summary
+	///
+	/// For each region, the shortest path along which no block could be placed, as long as it is
+	/// still the region's shortest path. Another region's blocks may change the path, after which
+	/// the region is tried again.
+	converged: Vec<Option<ShortestPath>>,
+
+	max_blocks: Option<usize>,
+	movement: MovementRules,
+
+	/// # Summary
+	///
+	/// See [`SolverSession::set_objective`].
+	objective: Objective,
+
+	/// # Summary
+	///
+	/// The region which each block of the `build` was placed for.
+	owners: HashMap<Coordinate, usize>,
+
+	/// # Summary
+	///
+	/// The region whose turn it is next.
+	next_region: usize,
+
+	progress: Progress,
+	split: Option<Vec<usize>>,
+	tileset: &'tileset Tileset,
+
+	/// # Summary
+	///
+	/// See [`SolverSession::set_trace`].
+	trace: bool,
+}
+
+impl<'tileset> SolverSession<'tileset>
+{
+	/// # Summary
+	///
+	/// The [`Build`] as it is so far.
+	pub fn build(&self) -> &Build
+	{
+		&self.build
+	}
+
+	/// # Summary
+	///
+	/// Finish the session early, keeping the [`Build`] as it is so far.
+	pub fn into_build(self) -> Build
+	{
+		self.build
+	}
+
+	/// # Summary
+	///
+	/// Start solving a `tileset`.
+	///
+	/// # Parameters
+	///
+	/// See [`Build::from_entrances_to_any_core`].
+	///
+	/// # Errors
+	///
+	/// See [`Build::from_entrances_to_any_core`].
+	pub fn new(
+		tileset: &'tileset Tileset,
+		movement: impl Into<MovementRules>,
+		max_blocks: Option<usize>,
+	) -> Result<Self>
+	{
+		let movement = movement.into();
+		Build::initial_shortest_paths(tileset, movement)?;
+
+		Ok(Self {
+			build: Build { blocks: HashSet::new() },
+			cancellation: None,
+			candidate_scope: None,
+			converged: vec![None; tileset.entrances_by_region.len()],
+			max_blocks,
+			movement,
+			next_region: 0,
+			objective: Objective::default(),
+			owners: HashMap::new(),
+			progress: Progress::new(tileset.entrances_by_region.len()),
+			split: None,
+			tileset,
+			trace: false,
+		})
+	}
+
+	/// # Summary
+	///
+	/// How far along the session is.
+	pub fn progress(&self) -> &Progress
+	{
+		&self.progress
+	}
+
+	/// # Summary
+	///
+	/// Start solving a `tileset` from an existing `build`, e.g. one which was saved from an
+	/// earlier session.
+	///
+	/// # Parameters
+	///
+	/// See [`Build::from_entrances_to_any_core`].
+	///
+	/// # Errors
+	///
+	/// * [`Error::NoPathToCore`](super::Error::NoPathToCore), if some region cannot reach a
+	///   [`Tile::Core`] through the `build`.
+	pub fn resume(
+		tileset: &'tileset Tileset,
+		build: Build,
+		movement: impl Into<MovementRules>,
+		max_blocks: Option<usize>,
+	) -> Result<Self>
+	{
+		let movement = movement.into();
+		let mut session = Self::new(tileset, movement, max_blocks)?;
+		if let Some(region) =
+			build.shortest_path_lengths(tileset, movement).iter().position(Option::is_none)
+		{
+			return Err(Error::NoPathToCore { region });
+		}
+
+		session.progress.blocks = build.blocks.len();
+		session.build = build;
+		Ok(session)
+	}
+
+	/// # Summary
+	///
+	/// Finish the session as soon as the `cancellation` is cancelled, keeping the [`Build`] as it
+	/// is at that point.
+	pub fn set_cancellation(&mut self, cancellation: CancellationToken)
+	{
+		self.cancellation = Some(cancellation);
+	}
+
+	/// # Summary
+	///
+	/// Choose each block with [`Build::find_scored_block_placement`], looking at the tiles within
+	/// `candidate_scope` moves of the shortest path as well as the path itself.
+	pub fn set_candidate_scope(&mut self, candidate_scope: usize)
+	{
+		self.candidate_scope = Some(candidate_scope);
+	}
+
+	/// # Summary
+	///
+	/// Score the blocks which [`Build::find_scored_block_placement`] chooses between by the
+	/// `objective`, rather than by the [default](Objective::default).
+	pub fn set_objective(&mut self, objective: Objective)
+	{
+		self.objective = objective;
+	}
+
+	/// # Summary
+	///
+	/// Only place blocks for each region while fewer than `split[region]` of the build's blocks
+	/// were placed for it, e.g. to follow a [`BudgetSplit`](crate::map::BudgetSplit).
+	///
+	/// # Remarks
+	///
+	/// Blocks which were already in a [resumed](SolverSession::resume) build do not count
+	/// towards any region.
+	pub fn set_split(&mut self, split: Vec<usize>)
+	{
+		self.split = Some(split);
+	}
+
+	/// # Summary
+	///
+	/// Whether to record a [`StepTrace`] in every [`Step`].
+	///
+	/// # Remarks
+	///
+	/// Tracing checks each candidate again after the step is made, so it slows the session down.
+	pub fn set_trace(&mut self, trace: bool)
+	{
+		self.trace = trace;
+	}
+
+	/// # Summary
+	///
+	/// Try to place one block along the shortest path of the next region.
+	///
+	/// # Remarks
+	///
+	/// Regions take turns, skipping those which have converged: no block could be placed along
+	/// their shortest path, and it has not changed since. The session is finished once every
+	/// region has converged, the `max_blocks` are placed, or it is
+	/// [cancelled](SolverSession::set_cancellation).
+	///
+	/// # Returns
+	///
+	/// * `None`, if the session is finished.
+	/// * `Some(Step)`, detailing what changed. Its `added` blocks are empty if no block could be
+	///   placed for that region.
+	pub fn step(&mut self) -> Option<Step>
+	{
+		if self.max_blocks.is_some_and(|max| max <= self.build.blocks.len()) ||
+			self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+		{
+			return None;
+		}
+
+		let regions = self.tileset.entrances_by_region.len();
+		let (region, shortest_path) = (0..regions)
+			.map(|offset| (self.next_region + offset) % regions)
+			.map(|region| (region, self.shortest_path(region)))
+			.find(|(region, path)| self.converged[*region].as_ref() != Some(path))?;
+		self.next_region = (region + 1) % regions;
+		self.progress.set_length(region, shortest_path.len());
+
+		let within_split = self.split.as_ref().is_none_or(|split| {
+			split.get(region).is_some_and(|blocks| {
+				self.owners.values().filter(|owner| **owner == region).count() < *blocks
+			})
+		});
+
+		let mut step = Step { region, ..Default::default() };
+		let placement = within_split
+			.then(|| {
+				Build::find_block_placement(
+					self.tileset,
+					&self.build.blocks,
+					region,
+					shortest_path.coordinates(),
+					self.candidate_scope,
+					&self.objective,
+					self.movement,
+				)
+			})
+			.flatten();
+
+		if self.trace
+		{
+			step.trace = Some(self.trace(region, &shortest_path, within_split, placement));
+		}
+
+		if let Some(coord) = placement
+		{
+			let before = self.build.blocks.clone();
+
+			// Test the build with the coordinate inserted.
+			// Insert the coord now that we know it is valid.
+			self.build.blocks.insert(coord);
+			self.build.try_remove_adjacent_to(self.tileset, coord, self.movement);
+
+			step.added = self.build.blocks.difference(&before).copied().collect();
+			step.added.sort_unstable_by_key(|coord| (coord.1, coord.0));
+			step.removed = before.difference(&self.build.blocks).copied().collect();
+			step.removed.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+			step.added.iter().for_each(|coord| {
+				self.owners.insert(*coord, region);
+			});
+			step.removed.iter().for_each(|coord| {
+				self.owners.remove(coord);
+			});
+
+			self.progress.set_length(region, self.shortest_path(region).len());
+		}
+		else
+		{
+			self.converged[region] = Some(shortest_path);
+		}
+
+		self.progress.blocks = self.build.blocks.len();
+		self.progress.iterations += 1;
+		step.shortest_path_length.clone_from(&self.progress.shortest_path_length);
+
+		Some(step)
+	}
+
+	/// # Summary
+	///
+	/// The current [`ShortestPath`] from a `region` to any core which it
+	/// [targets](Tileset::targets).
+	fn shortest_path(&self, region: usize) -> ShortestPath
+	{
+		ShortestPath::from_region_to_target(
+			self.tileset,
+			Some(&self.build.blocks),
+			region,
+			self.movement,
+		)
+		.expect(VALID_BUILD)
+	}
+
+	/// # Summary
+	///
+	/// Work out the [`StepTrace`] of a step for a `region` along its `shortest_path`, before the
+	/// `placement` (if any) is made.
+	///
+	/// # Parameters
+	///
+	/// * `searched`, whether a placement was searched for at all, i.e. the region was within its
+	///   [split](SolverSession::set_split).
+	fn trace(
+		&self,
+		region: usize,
+		shortest_path: &ShortestPath,
+		searched: bool,
+		placement: Option<Coordinate>,
+	) -> StepTrace
+	{
+		let blocks = &self.build.blocks;
+		let distances = DistanceField::to_coordinates(
+			&self.tileset.grid,
+			Some(blocks),
+			self.tileset.entrances_by_region[region].keys().copied(),
+			self.movement,
+		);
+
+		let mut frontier = vec![0; shortest_path.coordinates().len()];
+		distances.rows().iter().flatten().flatten().for_each(|distance| {
+			if let Some(tiles) = frontier.get_mut(*distance)
+			{
+				*tiles += 1;
+			}
+		});
+
+		let candidates = match (searched, self.candidate_scope)
+		{
+			(false, _) => Vec::new(),
+			(true, Some(scope)) =>
+			{
+				let path = shortest_path.coordinates();
+				Build::scored_candidates(self.tileset, blocks, path, scope, self.movement).0
+			},
+			// Tiles are tried from the core until one is valid, so none after it were considered.
+			(true, None) =>
+			{
+				let mut candidates: Vec<_> = shortest_path
+					.iter()
+					.rev()
+					.copied()
+					.filter(|coord| self.tileset.is_buildable(coord))
+					.collect();
+				if let Some(index) = placement.and_then(|p| candidates.iter().position(|c| *c == p))
+				{
+					candidates.truncate(index + 1);
+				}
+
+				candidates
+			},
+		};
+
+		let rejected = candidates
+			.iter()
+			.copied()
+			.filter(|coord| {
+				!Build::is_valid(self.tileset, &TempBuild { blocks, temp_block: *coord })
+			})
+			.collect();
+
+		StepTrace { candidates, frontier, rejected }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, SolverSession, Tileset};
+	use crate::map::{
+		tileset::tests::{PARK, PARK_TWO_SPAWN},
+		Tile,
+	};
+
+	#[test]
+	fn converged()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let mut session = SolverSession::new(&test_tileset, false, Some(10)).unwrap();
+
+		// The first region cannot place anything, so it converges right away.
+		session.set_split(vec![0, 10]);
+
+		let start = Instant::now();
+		let first = session.step().unwrap();
+		println!("SolverSession::step {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(first.region, 0);
+		assert!(first.added.is_empty());
+		assert!(session.converged[0].is_some());
+
+		// The other region keeps placing blocks until it is done too. The first is only tried again
+		// when those blocks change its path.
+		while let Some(step) = session.step()
+		{
+			assert!(step.region == 1 || step.added.is_empty());
+		}
+
+		assert!(!session.build().blocks.is_empty());
+		assert!(
+			session.build().blocks.len() == 10 || session.converged.iter().all(Option::is_some)
+		);
+	}
+
+	#[test]
+	fn set_trace()
+	{
+		let test_tileset = Tileset::new(PARK.iter().map(|inner| inner.to_vec()).collect());
+		let mut session = SolverSession::new(&test_tileset, false, Some(10)).unwrap();
+		session.set_trace(true);
+
+		let start = Instant::now();
+		let steps: Vec<_> = std::iter::from_fn(|| session.step()).collect();
+		println!("SolverSession::set_trace {}us", Instant::now().duration_since(start).as_micros());
+
+		steps.iter().for_each(|step| {
+			let trace = step.trace.as_ref().unwrap();
+
+			// The search reaches the entrances first, and spreads out from there.
+			assert!(trace.frontier[0] > 0);
+
+			// Every tile before the one which was placed was rejected.
+			match step.added.is_empty()
+			{
+				true => assert_eq!(trace.rejected, trace.candidates),
+				false =>
+				{
+					let (placed, before) = trace.candidates.split_last().unwrap();
+					assert!(step.added.contains(placed));
+					assert_eq!(trace.rejected, before);
+				},
+			}
+		});
+	}
+
+	#[test]
+	fn step()
+	{
+		[&PARK, &PARK_TWO_SPAWN].iter().for_each(|grid| {
+			let test_tileset = Tileset::new(grid.iter().map(|inner| inner.to_vec()).collect());
+			let mut session = SolverSession::new(&test_tileset, false, Some(10)).unwrap();
+			let mut grid = test_tileset.grid.clone();
+
+			let start = Instant::now();
+			let mut steps = 0;
+			while let Some(step) = session.step()
+			{
+				steps += 1;
+
+				// Replaying each delta should always give the session's build.
+				step.added.iter().for_each(|coord| coord.set(&mut grid, Tile::Block).unwrap());
+				step.removed.iter().for_each(|coord| coord.set(&mut grid, Tile::Empty).unwrap());
+				assert_eq!(Build::take_from(&mut grid.clone()), *session.build());
+				assert_eq!(step.shortest_path_length, session.progress().shortest_path_length);
+			}
+			println!("SolverSession::step {}us", Instant::now().duration_since(start).as_micros());
+
+			assert_eq!(session.progress().iterations, steps);
+			assert!(session.build().blocks.len() <= 10);
+		});
+	}
+
+	#[test]
+	fn resume()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let total = |build: &Build| -> usize {
+			build.shortest_path_lengths(&test_tileset, false).into_iter().flatten().sum()
+		};
+
+		let partial =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(5), |_| ()).unwrap();
+
+		let start = Instant::now();
+		let mut session =
+			SolverSession::resume(&test_tileset, partial.clone(), false, Some(10)).unwrap();
+		println!("SolverSession::resume {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(session.progress().blocks, partial.blocks.len());
+		while session.step().is_some()
+		{}
+
+		// Picking up where the partial build left off can only make the paths longer.
+		assert!(session.build().blocks.len() <= 10);
+		assert!(total(session.build()) >= total(&partial));
+	}
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::map::Coordinate;
+
+/// # Summary
+///
+/// What a [`SolverSession`](super::SolverSession) looked at while making a [`Step`](super::Step),
+/// so that how it chooses blocks can be shown (e.g. when teaching, or debugging a heuristic).
+///
+/// # Remarks
+///
+/// Empty lists are left out when serialized, to keep long traces small.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct StepTrace
+{
+	/// # Summary
+	///
+	/// The tiles which were considered for a block, in the order they were considered.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub candidates: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// How many tiles the search for the region's shortest path reached at each number of moves
+	/// from its entrances, up to the length of the path.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub frontier: Vec<usize>,
+
+	/// # Summary
+	///
+	/// The `candidates` which were turned down because a block there would cut some region off
+	/// from its core.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub rejected: Vec<Coordinate>,
+}
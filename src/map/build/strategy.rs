@@ -0,0 +1,68 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result};
+
+/// # Summary
+///
+/// A way of creating a [`Build`](super::Build).
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy
+{
+	/// # Summary
+	///
+	/// Run every other [`Strategy`] at the same time, and keep the best result.
+	Portfolio,
+
+	/// # Summary
+	///
+	/// See [`Build::from_entrances_to_any_core_with_priority`](super::Build::from_entrances_to_any_core_with_priority).
+	Priority,
+
+	/// # Summary
+	///
+	/// See [`Build::from_entrances_to_any_core`](super::Build::from_entrances_to_any_core).
+	#[default]
+	RoundRobin,
+}
+
+impl Strategy
+{
+	/// # Summary
+	///
+	/// Every [`Strategy`] which the [`Strategy::Portfolio`] runs.
+	pub const PORTFOLIO: [Strategy; 2] = [Strategy::Priority, Strategy::RoundRobin];
+}
+
+impl fmt::Display for Strategy
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			Strategy::Portfolio => "portfolio",
+			Strategy::Priority => "priority",
+			Strategy::RoundRobin => "round-robin",
+		})
+	}
+}
+
+impl FromStr for Strategy
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"portfolio" => Strategy::Portfolio,
+			"priority" => Strategy::Priority,
+			"round-robin" => Strategy::RoundRobin,
+			_ => return Err(Error::UnknownStrategy { name: name.into() }),
+		})
+	}
+}
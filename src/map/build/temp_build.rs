@@ -15,6 +15,20 @@ where
 	pub(super) temp_block: Coordinate,
 }
 
+/// # Summary
+///
+/// A temporary [`Build`][build] holding some `blocks` but without the `removed_block`, for
+/// purposes of checking before removal from the main [`Build`][build].
+///
+/// [build]: super::Build
+pub(super) struct TempRemoval<'blocks, C>
+where
+	C: Container<Coordinate>,
+{
+	pub(super) blocks: &'blocks C,
+	pub(super) removed_block: Coordinate,
+}
+
 impl<C> Container<Coordinate> for TempBuild<'_, C>
 where
 	C: Container<Coordinate>,
@@ -24,3 +38,13 @@ where
 		self.blocks.contains(some) || self.temp_block == *some
 	}
 }
+
+impl<C> Container<Coordinate> for TempRemoval<'_, C>
+where
+	C: Container<Coordinate>,
+{
+	fn contains(&self, some: &Coordinate) -> bool
+	{
+		self.removed_block != *some && self.blocks.contains(some)
+	}
+}
@@ -0,0 +1,356 @@
+use std::collections::{HashSet, LinkedList};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	tileset::{Tileset, COORDINATE_ON_TILESET},
+	Adjacent,
+	Build,
+	Coordinate,
+	Sensitivity,
+	ShortestPath,
+	Tile,
+};
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IntoParallelRefIterator, ParallelIterator};
+
+/// # Summary
+///
+/// The capacity of an edge which can never be cut.
+const UNCUTTABLE: usize = usize::MAX / 2;
+
+/// # Summary
+///
+/// A directed edge of a flow network, with its remaining `capacity` and the index of its reverse
+/// edge in the adjacency list of `to`.
+struct Edge
+{
+	capacity: usize,
+	reverse: usize,
+	to: usize,
+}
+
+/// # Summary
+///
+/// Add an edge from `from` to `to` to a flow `network`, along with its reverse edge.
+fn add_edge(network: &mut [Vec<Edge>], from: usize, to: usize, capacity: usize)
+{
+	let reverse = network[to].len();
+	let forward = network[from].len();
+	network[from].push(Edge { capacity, reverse, to });
+	network[to].push(Edge { capacity: 0, reverse: forward, to: from });
+}
+
+/// # Summary
+///
+/// The places in a [`Build`] which matter most: where enemies must always walk, and which blocks
+/// cannot be lost without shortening a path.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ChokePoints
+{
+	/// # Summary
+	///
+	/// The blocks which shorten some region's path when removed, in reading order. These should
+	/// never be sold.
+	pub critical_blocks: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The fewest [buildable](super::Tile::is_buildable) tiles which would cut each region off
+	/// from every core on the map without any build, in reading order; or [`None`] if the region
+	/// can reach a core without crossing any buildable tile. These are the map's natural choke
+	/// points, where a few blocks have an outsized effect.
+	pub natural: Vec<Option<Vec<Coordinate>>>,
+
+	/// # Summary
+	///
+	/// The tiles which are on every shortest path of every region, in reading order. These are
+	/// the best places for the strongest towers to cover.
+	pub shared: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The tiles which are on every shortest path of each region, in path order.
+	pub tiles_by_region: Vec<Vec<Coordinate>>,
+}
+
+impl ChokePoints
+{
+	/// # Summary
+	///
+	/// Find the [`ChokePoints`] of a `build` on a `tileset`.
+	///
+	/// # Remarks
+	///
+	/// A tile is on every shortest path of a region exactly when blocking it makes that region's
+	/// path longer (or cuts it off).
+	pub fn of(tileset: &Tileset, build: &Build, diagonals: bool) -> Self
+	{
+		let paths =
+			ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), diagonals);
+		let tiles_by_region: Vec<Vec<Coordinate>> = paths
+			.into_iter()
+			.enumerate()
+			.map(|(region, path)| {
+				let path = match path
+				{
+					Some(p) => p,
+					None => return Vec::new(),
+				};
+
+				// The core is always at the end of the path, so it is not interesting.
+				let (_, coordinates) =
+					path.coordinates().split_last().expect("Expected a path to reach a core");
+
+				coordinates
+					.par_iter()
+					.copied()
+					.filter(|coord| {
+						let mut blocked = build.clone();
+						blocked.blocks.insert(*coord);
+
+						ShortestPath::from_entrances_to_any_core(
+							tileset,
+							Some(&blocked.blocks),
+							diagonals,
+						)
+						.swap_remove(region)
+						.is_none_or(|p| p.len() > path.len())
+					})
+					.collect()
+			})
+			.collect();
+
+		let mut shared: Vec<_> = tiles_by_region
+			.split_first()
+			.map(|(first, rest)| {
+				first
+					.iter()
+					.filter(|coord| rest.iter().all(|tiles| tiles.contains(coord)))
+					.copied()
+					.collect()
+			})
+			.unwrap_or_default();
+		shared.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+		let mut critical_blocks: Vec<_> = Sensitivity::of(tileset, build, diagonals, 1.0)
+			.impacts
+			.into_iter()
+			.filter(|impact| impact.loss > 0)
+			.map(|impact| impact.block)
+			.collect();
+		critical_blocks.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+		let natural = (0..tileset.entrances_by_region.len())
+			.map(|region| Self::min_cut(tileset, region, diagonals))
+			.collect();
+
+		Self { critical_blocks, natural, shared, tiles_by_region }
+	}
+
+	/// # Summary
+	///
+	/// Find the fewest [buildable](super::Tile::is_buildable) tiles which separate a `region`'s
+	/// entrances from every core.
+	///
+	/// # Remarks
+	///
+	/// Each passable tile is split into an entry and an exit joined by an edge, which costs `1` to
+	/// cut if the tile is buildable (and cannot be cut otherwise). The maximum flow from the
+	/// entrances to the cores is then the size of the smallest cut, and the cut itself is made of
+	/// the tiles whose entry can still be reached once the flow is saturated, but whose exit
+	/// cannot.
+	///
+	/// With `diagonals`, blocking a tile does not stop diagonal moves past its corner, so the cut
+	/// may be larger than it needs to be.
+	pub(super) fn min_cut(
+		tileset: &Tileset,
+		region: usize,
+		diagonals: bool,
+	) -> Option<Vec<Coordinate>>
+	{
+		let grid = &tileset.grid;
+		let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+		let index = |coord: Coordinate| coord.1 * width + coord.0;
+
+		let tiles = width * grid.len();
+		let source = 2 * tiles;
+		let sink = source + 1;
+		let mut network: Vec<Vec<Edge>> = (0..=sink).map(|_| Vec::new()).collect();
+
+		grid.iter().enumerate().for_each(|(y, row)| {
+			row.iter().enumerate().filter(|(_, tile)| tile.is_passable()).for_each(|(x, tile)| {
+				let coord = Coordinate(x, y);
+				let capacity = if tile.is_buildable() { 1 } else { UNCUTTABLE };
+				add_edge(&mut network, 2 * index(coord), 2 * index(coord) + 1, capacity);
+
+				Adjacent::from_grid_coordinate_with_build(
+					grid,
+					Option::<&HashSet<_>>::None,
+					&coord,
+					diagonals,
+				)
+				.into_iter()
+				.for_each(|(_, adjacent_coord)| {
+					let adjacent_tile = adjacent_coord.get_from(grid).expect(COORDINATE_ON_TILESET);
+					if adjacent_tile == Tile::Core
+					{
+						add_edge(&mut network, 2 * index(coord) + 1, sink, UNCUTTABLE);
+					}
+					else if adjacent_tile.is_passable()
+					{
+						add_edge(
+							&mut network,
+							2 * index(coord) + 1,
+							2 * index(adjacent_coord),
+							UNCUTTABLE,
+						);
+					}
+				});
+			})
+		});
+
+		tileset.entrances_by_region[region].keys().for_each(|entrance| {
+			add_edge(&mut network, source, 2 * index(*entrance), UNCUTTABLE);
+		});
+
+		// Find the nodes which can be reached from the `source` with some capacity to spare, and
+		// the edge each one was reached through.
+		let search = |network: &[Vec<Edge>]| -> Vec<Option<(usize, usize)>> {
+			let mut previous = vec![None; network.len()];
+			let mut node_queue = LinkedList::new();
+
+			previous[source] = Some((source, 0));
+			node_queue.push_back(source);
+
+			while let Some(node) = node_queue.pop_front()
+			{
+				network[node].iter().enumerate().for_each(|(edge_index, edge)| {
+					if edge.capacity > 0 && previous[edge.to].is_none()
+					{
+						previous[edge.to] = Some((node, edge_index));
+						node_queue.push_back(edge.to);
+					}
+				});
+			}
+
+			previous
+		};
+
+		let mut flow = 0;
+		loop
+		{
+			let previous = search(&network);
+			if previous[sink].is_none()
+			{
+				break;
+			}
+
+			// A finite cut can never be larger than the number of tiles, so an augmenting path with
+			// more capacity than that crosses no buildable tile at all.
+			let mut bottleneck = UNCUTTABLE;
+			let mut node = sink;
+			while node != source
+			{
+				let (prev, edge_index) = previous[node].expect("Expected a path to the sink");
+				bottleneck = bottleneck.min(network[prev][edge_index].capacity);
+				node = prev;
+			}
+
+			if bottleneck > tiles
+			{
+				return None;
+			}
+
+			let mut node = sink;
+			while node != source
+			{
+				let (prev, edge_index) = previous[node].expect("Expected a path to the sink");
+				network[prev][edge_index].capacity -= bottleneck;
+				let reverse = network[prev][edge_index].reverse;
+				network[node][reverse].capacity += bottleneck;
+				node = prev;
+			}
+
+			flow += bottleneck;
+		}
+
+		let reachable = search(&network);
+		let mut cut: Vec<_> = grid
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| (0..row.len()).map(move |x| Coordinate(x, y)))
+			.filter(|coord| {
+				reachable[2 * index(*coord)].is_some() && reachable[2 * index(*coord) + 1].is_none()
+			})
+			.collect();
+		cut.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+		debug_assert_eq!(cut.len(), flow);
+		Some(cut)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, ChokePoints, Coordinate, Tileset};
+	use crate::map::{tileset::TilesetBuilder, Tile::*};
+
+	#[test]
+	fn of()
+	{
+		#[rustfmt::skip]
+		let mut grid = vec![
+			// 0      1      2      3      4
+			vec![Spawn,  Empty, Empty, Empty, Empty], // 0
+			vec![Impass, Empty, Empty, Empty, Empty], // 1
+			vec![Impass, Block, Empty, Block, Core],  // 2
+		];
+		let build = Build::take_from(&mut grid);
+		let test_tileset = Tileset::new(grid);
+
+		let start = Instant::now();
+		let choke_points = ChokePoints::of(&test_tileset, &build, false);
+		println!("ChokePoints::of {}us", Instant::now().duration_since(start).as_micros());
+
+		// Enemies always enter at (1, 0), and must finish by stepping down from (4, 1).
+		assert_eq!(choke_points.tiles_by_region, vec![vec![Coordinate(1, 0), Coordinate(4, 1)]]);
+		assert_eq!(choke_points.shared, vec![Coordinate(1, 0), Coordinate(4, 1)]);
+
+		// Neither block is in the way of the shortest path, so neither is critical.
+		assert!(choke_points.critical_blocks.is_empty());
+
+		// Without the build, the spawn's only entrance is the narrowest point.
+		assert_eq!(choke_points.natural, vec![Some(vec![Coordinate(1, 0)])]);
+	}
+
+	#[test]
+	fn min_cut()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1      2       3      4
+			vec![Spawn, Pass,  Empty,  Empty, Empty], // 0
+			vec![Pass,  Pass,  Impass, Empty, Empty], // 1
+			vec![Pass,  Pass,  Empty,  Empty, Core],  // 2
+		];
+		let test_tileset = Tileset::new(grid);
+
+		let start = Instant::now();
+		let cut = ChokePoints::min_cut(&test_tileset, 0, false);
+		println!("ChokePoints::min_cut {}us", Instant::now().duration_since(start).as_micros());
+
+		// The wall leaves two ways past it.
+		assert_eq!(cut, Some(vec![Coordinate(2, 0), Coordinate(2, 2)]));
+
+		// A path which is never buildable cannot be cut.
+		let corridor = TilesetBuilder::new(vec![vec![Spawn, Empty, Pass, Core]])
+			.entrance_overrides([(0, [(Coordinate(2, 0), 2)].into_iter().collect())])
+			.build()
+			.unwrap();
+		assert_eq!(ChokePoints::min_cut(&corridor, 0, false), None);
+	}
+}
@@ -0,0 +1,134 @@
+use std::{iter::Peekable, str::Chars};
+
+/// # Summary
+///
+/// Copy the `chars` of a JSON string (after its opening quote) onto `stripped`, up to and
+/// including its closing quote.
+fn copy_string(chars: &mut Peekable<Chars>, stripped: &mut String)
+{
+	while let Some(c) = chars.next()
+	{
+		stripped.push(c);
+		match c
+		{
+			'"' => return,
+			'\\' => stripped.extend(chars.next()),
+			_ => (),
+		}
+	}
+}
+
+/// # Summary
+///
+/// Turn JSON with `//` and `/* */` comments and trailing commas into plain JSON.
+///
+/// # Remarks
+///
+/// Everything which is taken out is replaced by spaces (keeping newlines), so that errors in the
+/// result point to the same line and column as they would in the original.
+pub fn strip(json: &str) -> String
+{
+	let mut stripped = String::with_capacity(json.len());
+	let mut chars = json.chars().peekable();
+
+	// The index of the last comma in `stripped`, if it may turn out to be trailing.
+	let mut last_comma = None;
+
+	while let Some(c) = chars.next()
+	{
+		match (c, chars.peek())
+		{
+			('"', _) =>
+			{
+				last_comma = None;
+				stripped.push(c);
+				copy_string(&mut chars, &mut stripped);
+			},
+			('/', Some('/')) =>
+			{
+				stripped.push(' ');
+				while chars.next_if(|c| *c != '\n').is_some()
+				{
+					stripped.push(' ');
+				}
+			},
+			('/', Some('*')) =>
+			{
+				chars.next();
+				stripped.push_str("  ");
+
+				let mut previous = None;
+				for c in chars.by_ref()
+				{
+					stripped.push(if c == '\n' { c } else { ' ' });
+					if previous == Some('*') && c == '/'
+					{
+						break;
+					}
+
+					previous = Some(c);
+				}
+			},
+			(',', _) =>
+			{
+				last_comma = Some(stripped.len());
+				stripped.push(c);
+			},
+			(']' | '}', _) =>
+			{
+				if let Some(index) = last_comma.take()
+				{
+					stripped.replace_range(index..=index, " ");
+				}
+
+				stripped.push(c);
+			},
+			_ =>
+			{
+				if !c.is_whitespace()
+				{
+					last_comma = None;
+				}
+
+				stripped.push(c);
+			},
+		}
+	}
+
+	stripped
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	#[test]
+	fn strip()
+	{
+		let json = r#"{
+			// The name is "park".
+			"name": "park // not a comment", /* neither is "/* this" */
+			"grid": [
+				"S.C", // 0
+				"X~,", // 1
+			],
+		}"#;
+
+		let start = Instant::now();
+		let stripped = super::strip(json);
+		println!("comments::strip {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(stripped.lines().count(), json.lines().count());
+		assert_eq!(
+			serde_json::from_str::<serde_json::Value>(&stripped).unwrap(),
+			serde_json::json!({"name": "park // not a comment", "grid": ["S.C", "X~,"]})
+		);
+
+		// Errors point to the same place.
+		let error = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap_err();
+		let (commented, plain) =
+			(error(&super::strip("[\n/* a */ 1 2]")), error("[\n        1 2]"));
+		assert_eq!((commented.line(), commented.column()), (plain.line(), plain.column()));
+	}
+}
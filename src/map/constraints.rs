@@ -0,0 +1,202 @@
+mod error;
+
+use std::collections::HashSet;
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Coordinate, MovementRules, ShortestPath};
+
+/// # Summary
+///
+/// Rules which every [`Build`](super::Build) for a map must follow, whichever
+/// [`Strategy`](super::Strategy) finds it.
+///
+/// # Remarks
+///
+/// Constraints are [checked](Constraints::check) against the map before any block is placed, so a
+/// mistake (e.g. a tile which is not on the map) is reported before solving rather than after.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Constraints
+{
+	/// # Summary
+	///
+	/// Tiles which no path may cross, e.g. where towers will stand. They are treated as
+	/// [impassable](super::Tile::Impass) while solving.
+	pub avoid: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The most blocks to place for each spawn region, in place of a
+	/// [`BudgetSplit`](super::BudgetSplit).
+	pub budgets: Option<Vec<usize>>,
+
+	/// # Summary
+	///
+	/// Tiles which must never have a block placed on them, although paths may still cross them.
+	pub reserved: Vec<Coordinate>,
+}
+
+impl Constraints
+{
+	/// # Summary
+	///
+	/// [Check](Constraints::check) these constraints against a `tileset`, and then apply them to
+	/// it, so that every [`Strategy`](super::Strategy) follows them.
+	///
+	/// # Errors
+	///
+	/// * See [`Constraints::check`].
+	/// * [`Error::Unreachable`], if some region has no path to a core once the tiles to `avoid` are
+	///   impassable.
+	pub fn apply(&self, tileset: &mut Tileset, movement: impl Into<MovementRules>) -> Result<()>
+	{
+		self.check(tileset)?;
+
+		tileset.reserve(self.reserved.iter().copied());
+		tileset.avoid(self.avoid.iter().copied());
+
+		match ShortestPath::from_entrances_to_any_core(
+			tileset,
+			Option::<&HashSet<_>>::None,
+			movement,
+		)
+		.iter()
+		.position(Option::is_none)
+		{
+			Some(region) => Err(Error::Unreachable { region }),
+			None => Ok(()),
+		}
+	}
+
+	/// # Summary
+	///
+	/// Check that these constraints make sense for a `tileset`, without changing it.
+	///
+	/// # Errors
+	///
+	/// * [`Error::Budgets`], if there is not one budget for each spawn region.
+	/// * [`Error::Conflict`], if a tile is both reserved and avoided.
+	/// * [`Error::OutOfBounds`], if some tile is not on the grid.
+	/// * [`Error::NotAvoidable`], if a tile to avoid is not [passable](super::Tile::is_passable).
+	pub fn check(&self, tileset: &Tileset) -> Result<()>
+	{
+		self.avoid
+			.iter()
+			.chain(&self.reserved)
+			.try_for_each(|coord| coord.check_bounds(&tileset.grid))
+			.map_err(|err| Error::OutOfBounds { err })?;
+
+		if let Some(coord) = self.avoid.iter().find(|coord| self.reserved.contains(coord))
+		{
+			return Err(Error::Conflict { coord: *coord });
+		}
+
+		if let Some((coord, tile)) = self
+			.avoid
+			.iter()
+			.filter_map(|coord| coord.get_from(&tileset.grid).map(|tile| (*coord, tile)))
+			.find(|(_, tile)| !tile.is_passable())
+		{
+			return Err(Error::NotAvoidable { coord, tile });
+		}
+
+		let regions = tileset.entrances_by_region.len();
+		match self.budgets.as_ref().filter(|b| b.len() != regions)
+		{
+			Some(budgets) => Err(Error::Budgets { expected: regions, found: budgets.len() }),
+			None => Ok(()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Constraints, Error};
+	use crate::map::{tileset::Tileset, Build, Coordinate, Tile::*};
+
+	#[test]
+	fn apply()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0      1      2      3
+			vec![Spawn, Empty, Empty, Empty], // 0
+			vec![Empty, Empty, Empty, Empty], // 1
+			vec![Empty, Empty, Empty, Core],  // 2
+		];
+		let constraints = Constraints {
+			avoid: vec![Coordinate(1, 1)],
+			reserved: vec![Coordinate(2, 0)],
+			..Default::default()
+		};
+
+		let mut test_tileset = Tileset::new(grid.clone());
+		let start = Instant::now();
+		constraints.apply(&mut test_tileset, false).unwrap();
+		println!("Constraints::apply {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(test_tileset.grid[1][1], Impass);
+		assert_eq!(test_tileset.avoided(), &[(Coordinate(1, 1), Empty)]);
+		assert!(!test_tileset.is_buildable(&Coordinate(2, 0)));
+
+		// No strategy places a block on a reserved or avoided tile.
+		for build in [
+			Build::from_entrances_to_any_core(&test_tileset, false, None, |_| ()).unwrap(),
+			Build::from_entrances_to_any_core_with_priority(&test_tileset, false, None, |_| ())
+				.unwrap(),
+		]
+		{
+			assert!(!build.blocks.contains(&Coordinate(1, 1)));
+			assert!(!build.blocks.contains(&Coordinate(2, 0)));
+		}
+
+		// Avoiding every way to the core is reported up front.
+		let walled_in = Constraints {
+			avoid: vec![Coordinate(1, 0), Coordinate(0, 1), Coordinate(1, 1)],
+			..Default::default()
+		};
+		assert!(matches!(
+			walled_in.apply(&mut Tileset::new(grid), false),
+			Err(Error::Unreachable { region: 0 })
+		));
+	}
+
+	#[test]
+	fn check()
+	{
+		let test_tileset = Tileset::new(vec![vec![Spawn, Empty, Pass, Core]]);
+		let check = |json: serde_json::Value| {
+			serde_json::from_value::<Constraints>(json).unwrap().check(&test_tileset)
+		};
+
+		let start = Instant::now();
+		let result = check(serde_json::json!({ "reserved": [[1, 0]], "budgets": [3] }));
+		println!("Constraints::check {}us", Instant::now().duration_since(start).as_micros());
+
+		assert!(result.is_ok());
+		assert!(matches!(
+			check(serde_json::json!({ "avoid": [[4, 0]] })),
+			Err(Error::OutOfBounds { .. })
+		));
+		assert!(matches!(
+			check(serde_json::json!({ "avoid": [[1, 0]], "reserved": [[1, 0]] })),
+			Err(Error::Conflict { .. })
+		));
+		assert!(matches!(
+			check(serde_json::json!({ "avoid": [[3, 0]] })),
+			Err(Error::NotAvoidable { .. })
+		));
+		assert!(matches!(
+			check(serde_json::json!({ "budgets": [1, 2] })),
+			Err(Error::Budgets { expected: 1, found: 2 })
+		));
+		assert!(
+			serde_json::from_value::<Constraints>(serde_json::json!({ "reserve": [] })).is_err()
+		);
+	}
+}
@@ -0,0 +1,48 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+use crate::map::{coordinate, Coordinate, Tile};
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("There are budgets for {} region(s), but the map has {}", found, expected))]
+	Budgets
+	{
+		expected: usize, found: usize
+	},
+
+	#[snafu(display("{:?} is both reserved and avoided", coord))]
+	Conflict
+	{
+		coord: Coordinate
+	},
+
+	#[snafu(display(
+		"{:?} is a {:?}, which paths do not cross and so cannot be avoided",
+		coord,
+		tile
+	))]
+	NotAvoidable
+	{
+		coord: Coordinate, tile: Tile
+	},
+
+	#[snafu(display("{}", err))]
+	OutOfBounds
+	{
+		err: coordinate::Error
+	},
+
+	#[snafu(display(
+		"Spawn region {} has no path to a core which does not cross an avoided tile",
+		region
+	))]
+	Unreachable
+	{
+		region: usize
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
@@ -1,16 +1,77 @@
-use serde::{Deserialize, Serialize};
+mod error;
+
+use std::{cell::Cell, result::Result as StdResult};
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::Tile;
 use crate::Container;
 
+thread_local! {
+	/// # Summary
+	///
+	/// Whether [`Coordinate`]s are serialized as `{"x": 4, "y": 1}` rather than `[4, 1]`, while an
+	/// [`ObjectCoordinates`] is being serialized on this thread.
+	static AS_OBJECTS: Cell<bool> = const { Cell::new(false) };
+}
+
 /// # Summary
 ///
 /// A __(__`x`__,__ `y`__)__ tuple which refers to coordinates in a two-dimensional array.
-#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+///
+/// # Remarks
+///
+/// Serialized as an `[x, y]` pair, unless it is part of an [`ObjectCoordinates`], and may be
+/// deserialized from either form.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Coordinate(pub usize, pub usize);
 
+/// # Summary
+///
+/// Some value whose every [`Coordinate`] is serialized as an `{"x": 4, "y": 1}` object, rather
+/// than an `[x, y]` pair which is easily mistaken for `[row, column]`.
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectCoordinates<'value, T: ?Sized>(pub &'value T);
+
+/// # Summary
+///
+/// The forms which a [`Coordinate`] may be serialized in.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum Repr
+{
+	Object
+	{
+		x: usize,
+		y: usize,
+	},
+	Pair(usize, usize),
+}
+
 impl Coordinate
 {
+	/// # Summary
+	///
+	/// Check that this [`Coordinate`] is on a `grid`.
+	///
+	/// # Errors
+	///
+	/// * [`Error::OutOfBounds`], if it is not.
+	pub fn check_bounds<T>(&self, grid: &[impl AsRef<[T]>]) -> Result<()>
+	{
+		let height = grid.len();
+		match grid.get(self.1).map(|row| row.as_ref().len())
+		{
+			Some(width) if self.0 < width => Ok(()),
+			Some(width) => Err(Error::OutOfBounds { coord: *self, dims: (width, height) }),
+			None => Err(Error::OutOfBounds {
+				coord: *self,
+				dims: (grid.first().map_or(0, |row| row.as_ref().len()), height),
+			}),
+		}
+	}
+
 	/// # Summary
 	///
 	/// Calculate the distance between this [`Coordinate`] and the `other`.
@@ -66,32 +127,116 @@ impl Coordinate
 		self.get_from(grid)
 	}
 
+	/// # Summary
+	///
+	/// The [`Coordinate`] which is `dx` columns and `dy` rows away from this one, if it is on the
+	/// `grid`.
+	pub fn offset<T>(&self, grid: &[impl AsRef<[T]>], dx: isize, dy: isize) -> Option<Self>
+	{
+		let x = self.0.checked_add_signed(dx)?;
+		let y = self.1.checked_add_signed(dy)?;
+
+		(x < grid.get(y)?.as_ref().len()).then_some(Coordinate(x, y))
+	}
+
 	/// # Summary
 	///
 	/// Set the `T` value stored at the [`Coordinate`] in array.
 	///
-	/// # Panics
+	/// # Errors
 	///
-	/// If `array[self.1][self.0]` is out of bounds.
-	pub fn set<T>(&self, grid: &mut [impl AsMut<[T]>], value: T)
+	/// * [`Error::OutOfBounds`], if `grid[self.1][self.0]` does not exist. Nothing is set. The
+	///   `dims` are the width of row `self.1` (or of the first row, if there is no such row) and
+	///   the number of rows.
+	pub fn set<T>(&self, grid: &mut [impl AsMut<[T]>], value: T) -> Result<()>
+	{
+		let height = grid.len();
+		let width = |row: &mut [T]| row.len();
+		match grid.get_mut(self.1).map(AsMut::as_mut)
+		{
+			Some(row) => match row.get_mut(self.0)
+			{
+				Some(cell) =>
+				{
+					*cell = value;
+					Ok(())
+				},
+				None => Err(Error::OutOfBounds { coord: *self, dims: (width(row), height) }),
+			},
+			None => Err(Error::OutOfBounds {
+				coord: *self,
+				dims: (grid.first_mut().map_or(0, |row| width(row.as_mut())), height),
+			}),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Coordinate
+{
+	fn deserialize<D>(deserializer: D) -> StdResult<Self, D::Error>
+	where
+		D: Deserializer<'de>,
 	{
-		if let Some(inner) = grid.get_mut(self.1)
+		Ok(match Repr::deserialize(deserializer)?
 		{
-			inner.as_mut()[self.0] = value
+			Repr::Object { x, y } | Repr::Pair(x, y) => Coordinate(x, y),
+		})
+	}
+}
+
+impl Serialize for Coordinate
+{
+	fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		if AS_OBJECTS.get()
+		{
+			Repr::Object { x: self.0, y: self.1 }
+		}
+		else
+		{
+			Repr::Pair(self.0, self.1)
 		}
+		.serialize(serializer)
+	}
+}
+
+impl<T> Serialize for ObjectCoordinates<'_, T>
+where
+	T: Serialize + ?Sized,
+{
+	fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let previous = AS_OBJECTS.replace(true);
+		let result = self.0.serialize(serializer);
+		AS_OBJECTS.set(previous);
+		result
 	}
 }
 
 #[cfg(test)]
 mod tests
 {
-	use super::Coordinate;
+	use super::{Coordinate, Error, ObjectCoordinates};
 
 	const ARRAY: [[usize; 5]; 5] =
 		[[1, 2, 3, 4, 5], [6, 7, 8, 9, 10], [11, 12, 13, 14, 15], [16, 17, 18, 19, 20], [
 			21, 22, 23, 24, 25,
 		]];
 
+	#[test]
+	fn test_deserialize()
+	{
+		assert_eq!(serde_json::from_str::<Coordinate>("[4, 1]").unwrap(), Coordinate(4, 1));
+		assert_eq!(
+			serde_json::from_str::<Coordinate>(r#"{"x": 4, "y": 1}"#).unwrap(),
+			Coordinate(4, 1)
+		);
+	}
+
 	#[test]
 	fn test_get_from()
 	{
@@ -99,4 +244,47 @@ mod tests
 		assert_eq!(Coordinate(0, 1).get_from(&ARRAY), Some(6));
 		assert_eq!(Coordinate(100, 1).get_from(&ARRAY), None);
 	}
+
+	#[test]
+	fn test_offset()
+	{
+		assert_eq!(Coordinate(2, 2).offset(&ARRAY, 2, -1), Some(Coordinate(4, 1)));
+		assert_eq!(Coordinate(0, 0).offset(&ARRAY, -1, 0), None);
+		assert_eq!(Coordinate(4, 4).offset(&ARRAY, 0, 1), None);
+		assert_eq!(Coordinate(4, 4).offset(&ARRAY, 1, 0), None);
+	}
+
+	#[test]
+	fn test_serialize()
+	{
+		assert_eq!(serde_json::to_string(&Coordinate(4, 1)).unwrap(), "[4,1]");
+
+		// Only the wrapped value is affected, even when it is nested.
+		let nested = vec![Coordinate(4, 1)];
+		assert_eq!(
+			serde_json::to_string(&ObjectCoordinates(&nested)).unwrap(),
+			r#"[{"x":4,"y":1}]"#
+		);
+		assert_eq!(serde_json::to_string(&nested).unwrap(), "[[4,1]]");
+	}
+
+	#[test]
+	fn test_set()
+	{
+		let mut array = ARRAY;
+		Coordinate(2, 2).set(&mut array, 0).unwrap();
+		assert_eq!(array[2][2], 0);
+
+		// Neither an out-of-range column nor row is set, and both report the size of the grid.
+		assert!(matches!(
+			Coordinate(5, 1).set(&mut array, 0),
+			Err(Error::OutOfBounds { coord: Coordinate(5, 1), dims: (5, 5) })
+		));
+		assert!(matches!(
+			Coordinate(1, 5).set(&mut array, 0),
+			Err(Error::OutOfBounds { coord: Coordinate(1, 5), dims: (5, 5) })
+		));
+		assert!(Coordinate(1, 5).check_bounds(&ARRAY).is_err());
+		assert_eq!(array.iter().flatten().filter(|value| **value == 0).count(), 1);
+	}
 }
@@ -0,0 +1,17 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+use super::Coordinate;
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("{:?} is not on a grid {} wide and {} tall", coord, dims.0, dims.1))]
+	OutOfBounds
+	{
+		coord: Coordinate, dims: (usize, usize)
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
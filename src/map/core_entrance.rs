@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	tileset::{Tileset, COORDINATE_ON_TILESET},
+	Adjacent,
+	Build,
+	Coordinate,
+	DistanceField,
+	MovementRules,
+	ShortestPath,
+	Tile,
+};
+
+/// # Summary
+///
+/// The last tiles which enemies cross before reaching a core: every path to the core must end on
+/// one of them, so they are where last-resort towers do the most good.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CoreEntrance
+{
+	/// # Summary
+	///
+	/// The first tile of the core, in reading order.
+	pub core: Coordinate,
+
+	/// # Summary
+	///
+	/// The tiles of `tiles` which enemies can still reach through the build, in reading order.
+	pub open: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The tiles beside the core which enemies could step onto it from if there were no build, in
+	/// reading order.
+	pub tiles: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The tiles of `open` which some region's shortest path steps onto the core from, in reading
+	/// order.
+	pub used: Vec<Coordinate>,
+}
+
+impl CoreEntrance
+{
+	/// # Summary
+	///
+	/// The [`CoreEntrance`] of each core of a `tileset` with some `build`, in the order of
+	/// [`Tileset::regions`].
+	///
+	/// # Remarks
+	///
+	/// A tile is only open if some spawn region can walk to it, so tiles which the build walls off
+	/// from every spawn are not counted even if they are empty.
+	pub fn of(tileset: &Tileset, build: &Build, movement: impl Into<MovementRules>) -> Vec<Self>
+	{
+		let movement = movement.into();
+		let reachable = DistanceField::to_coordinates(
+			&tileset.grid,
+			Some(&build.blocks),
+			tileset
+				.entrances_by_region
+				.iter()
+				.flat_map(|entrances| entrances.keys())
+				.copied()
+				.filter(|coord| !build.blocks.contains(coord)),
+			movement,
+		);
+
+		let used: HashSet<_> =
+			ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), movement)
+				.into_iter()
+				.flatten()
+				.filter_map(|path| path.coordinates().iter().rev().nth(1).copied())
+				.collect();
+
+		let sorted = |mut coords: Vec<Coordinate>| {
+			coords.sort_unstable_by_key(|coord| (coord.1, coord.0));
+			coords
+		};
+
+		tileset
+			.regions(Tile::Core)
+			.expect("Expected cores to be a region")
+			.into_iter()
+			.map(|region| {
+				let tiles: HashSet<_> = region
+					.coordinates
+					.iter()
+					.flat_map(|coord| {
+						Adjacent::from_grid_coordinate_with_build(
+							&tileset.grid,
+							Option::<&HashSet<_>>::None,
+							coord,
+							movement,
+						)
+					})
+					.map(|(_, adjacent_coord)| adjacent_coord)
+					.filter(|coord| {
+						coord.get_from(&tileset.grid).expect(COORDINATE_ON_TILESET).is_passable()
+					})
+					.collect();
+
+				let open: Vec<_> = tiles
+					.iter()
+					.copied()
+					.filter(|coord| !build.blocks.contains(coord) && reachable.get(coord).is_some())
+					.collect();
+
+				Self {
+					core: *region
+						.coordinates
+						.iter()
+						.min_by_key(|coord| (coord.1, coord.0))
+						.expect("Expected a region to have at least one tile"),
+					used: sorted(
+						open.iter().copied().filter(|coord| used.contains(coord)).collect(),
+					),
+					open: sorted(open),
+					tiles: sorted(tiles.into_iter().collect()),
+				}
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, Coordinate, CoreEntrance, Tileset};
+	use crate::map::tileset::tests::PARK;
+
+	#[test]
+	fn of()
+	{
+		let test_tileset = Tileset::new(PARK.iter().map(|inner| inner.to_vec()).collect());
+		let build = Build::from_entrances_to_any_core(&test_tileset, false, None, |_| ()).unwrap();
+
+		let start = Instant::now();
+		let entrances = CoreEntrance::of(&test_tileset, &build, false);
+		println!("CoreEntrance::of {}us", Instant::now().duration_since(start).as_micros());
+
+		// Park has a single 2x2 core, ringed by tiles which cannot be built on.
+		assert_eq!(entrances.len(), 1);
+		assert_eq!(entrances[0].core, Coordinate(5, 11));
+		assert_eq!(entrances[0].tiles, vec![
+			Coordinate(5, 10),
+			Coordinate(6, 10),
+			Coordinate(4, 11),
+			Coordinate(7, 11),
+			Coordinate(4, 12),
+			Coordinate(7, 12),
+			Coordinate(5, 13),
+			Coordinate(6, 13),
+		]);
+
+		assert!(!entrances[0].used.is_empty());
+		assert!(entrances[0].used.iter().all(|coord| entrances[0].open.contains(coord)));
+		assert!(entrances[0].open.iter().all(|coord| entrances[0].tiles.contains(coord)));
+
+		// Without a build, every tile beside the core is open.
+		let empty = CoreEntrance::of(&test_tileset, &Build { blocks: Default::default() }, false);
+		assert_eq!(empty[0].open, empty[0].tiles);
+	}
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// # Summary
+///
+/// A way to move from one [`Coordinate`](super::Coordinate) to an [`Adjacent`](super::Adjacent)
+/// one.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction
+{
+	Up,
+	Right,
+	Down,
+	Left,
+
+	UpRight,
+	DownRight,
+	DownLeft,
+	UpLeft,
+}
+
+impl Direction
+{
+	/// # Summary
+	///
+	/// Every [`Direction`], orthogonal ones first.
+	pub const ALL: [Direction; 8] = [
+		Direction::Up,
+		Direction::Right,
+		Direction::Down,
+		Direction::Left,
+		Direction::UpRight,
+		Direction::DownRight,
+		Direction::DownLeft,
+		Direction::UpLeft,
+	];
+
+	/// # Summary
+	///
+	/// Whether moving in this [`Direction`] changes both the row and the column.
+	pub fn is_diagonal(&self) -> bool
+	{
+		matches!(
+			self,
+			Direction::UpRight | Direction::DownRight | Direction::DownLeft | Direction::UpLeft
+		)
+	}
+}
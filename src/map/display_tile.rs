@@ -0,0 +1,165 @@
+use std::fmt;
+
+use serde::{
+	de::{self, IntoDeserializer, Visitor},
+	Deserialize,
+	Deserializer,
+	Serialize,
+	Serializer,
+};
+
+use super::{RegionResult, Tile};
+
+/// # Summary
+///
+/// A square of the display layer of a solved [`Map`](super::Map): the [`Tile`] which is there,
+/// or the region whose path crosses it.
+///
+/// # Remarks
+///
+/// Written as the name of the [`Tile`] (e.g. `"Block"`), or as `"Path"` followed by the number of
+/// the region (e.g. `"Path0"`).
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DisplayTile
+{
+	/// # Summary
+	///
+	/// A tile which the shortest path of this region crosses. When several paths cross the same
+	/// tile, it belongs to the region with the lowest number.
+	Path(usize),
+
+	/// # Summary
+	///
+	/// A tile which no path crosses.
+	Tile(Tile),
+}
+
+impl DisplayTile
+{
+	/// # Summary
+	///
+	/// The display layer of a solved `grid`, marking the path of each of the `regions`.
+	///
+	/// # Remarks
+	///
+	/// Only passable tiles are marked, so the spawns and cores at either end of a path keep their
+	/// [`Tile`].
+	pub fn layer(grid: &[Vec<Tile>], regions: &[RegionResult]) -> Vec<Vec<Self>>
+	{
+		let mut layer: Vec<Vec<_>> =
+			grid.iter().map(|row| row.iter().copied().map(Self::Tile).collect()).collect();
+
+		// Go backwards, so that the lowest region is written last.
+		regions.iter().enumerate().rev().for_each(|(region, result)| {
+			result.path.iter().for_each(|coord| {
+				if let Some(tile) = layer.get_mut(coord.1).and_then(|row| row.get_mut(coord.0))
+				{
+					if grid[coord.1][coord.0].is_passable()
+					{
+						*tile = Self::Path(region);
+					}
+				}
+			})
+		});
+
+		layer
+	}
+}
+
+impl<'de> Deserialize<'de> for DisplayTile
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// # Summary
+		///
+		/// Reads a [`DisplayTile`] from its name.
+		struct DisplayTileVisitor;
+
+		impl Visitor<'_> for DisplayTileVisitor
+		{
+			type Value = DisplayTile;
+
+			fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+			{
+				f.write_str("the name of a tile, or \"Path\" followed by a region number")
+			}
+
+			fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				match name.strip_prefix("Path").and_then(|region| region.parse().ok())
+				{
+					Some(region) => Ok(DisplayTile::Path(region)),
+					None => Tile::deserialize(name.into_deserializer()).map(DisplayTile::Tile),
+				}
+			}
+		}
+
+		deserializer.deserialize_str(DisplayTileVisitor)
+	}
+}
+
+impl Serialize for DisplayTile
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		match self
+		{
+			DisplayTile::Path(region) => serializer.collect_str(&format_args!("Path{}", region)),
+			DisplayTile::Tile(tile) => tile.serialize(serializer),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{DisplayTile, Tile};
+	use crate::map::{
+		tileset::{tests::PARK_TWO_SPAWN, Tileset},
+		Build,
+		RegionResult,
+	};
+
+	#[test]
+	fn layer()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(10), |_| ()).unwrap();
+		let regions = RegionResult::of(&test_tileset, &build, false);
+		let mut grid = test_tileset.grid.clone();
+		build.apply_to(&mut grid).unwrap();
+
+		let start = Instant::now();
+		let layer = DisplayTile::layer(&grid, &regions);
+		println!("DisplayTile::layer {}us", Instant::now().duration_since(start).as_micros());
+
+		// Every passable tile of the first region's path is marked, and nothing else changes.
+		regions[0]
+			.path
+			.iter()
+			.filter(|coord| grid[coord.1][coord.0].is_passable())
+			.for_each(|coord| assert_eq!(layer[coord.1][coord.0], DisplayTile::Path(0)));
+		layer.iter().flatten().zip(grid.iter().flatten()).for_each(|(display, tile)| {
+			assert!(matches!(display, DisplayTile::Path(_)) || display == &DisplayTile::Tile(*tile))
+		});
+
+		let json = serde_json::to_string(&layer).unwrap();
+		assert!(json.contains("\"Path1\"") && json.contains("\"Block\""));
+		assert_eq!(serde_json::from_str::<Vec<Vec<DisplayTile>>>(&json).unwrap(), layer);
+		assert!(serde_json::from_str::<DisplayTile>("\"Pathway\"").is_err());
+		assert_eq!(
+			serde_json::from_str::<DisplayTile>("\"Core\"").unwrap(),
+			DisplayTile::Tile(Tile::Core)
+		);
+	}
+}
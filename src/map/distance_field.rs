@@ -0,0 +1,170 @@
+use std::collections::{HashSet, LinkedList};
+
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::COORDINATE_ON_TILESET, Adjacent, Coordinate, MovementRules, Tile};
+use crate::Container;
+
+/// # Summary
+///
+/// How many moves it takes to get from each tile of a grid to the nearest [`Tile`] of some kind,
+/// such as a [`Tile::Core`].
+///
+/// # Remarks
+///
+/// Tiles which cannot reach the target (e.g. because they are impassable, or walled off) have no
+/// distance.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct DistanceField(Vec<Vec<Option<usize>>>);
+
+impl DistanceField
+{
+	/// # Summary
+	///
+	/// The distance of a `coord`, if it can reach the target.
+	pub fn get(&self, coord: &Coordinate) -> Option<usize>
+	{
+		coord.get_from(&self.0).flatten()
+	}
+
+	/// # Summary
+	///
+	/// The greatest distance of any tile.
+	pub fn max(&self) -> Option<usize>
+	{
+		self.0.iter().flatten().flatten().copied().max()
+	}
+
+	/// # Summary
+	///
+	/// Every distance, row by row.
+	pub fn rows(&self) -> &[Vec<Option<usize>>]
+	{
+		&self.0
+	}
+
+	/// # Summary
+	///
+	/// Measure how far each tile of a `grid` (with some `build` placed) is from the nearest of
+	/// some `targets`.
+	///
+	/// # Remarks
+	///
+	/// Movement is the same as [`ShortestPath`](super::ShortestPath).
+	pub fn to_coordinates(
+		grid: &[impl AsRef<[Tile]>],
+		build: Option<&impl Container<Coordinate>>,
+		targets: impl IntoIterator<Item = Coordinate>,
+		movement: impl Into<MovementRules>,
+	) -> Self
+	{
+		let movement = movement.into();
+		let mut distances: Vec<Vec<Option<usize>>> =
+			grid.iter().map(|row| vec![None; row.as_ref().len()]).collect();
+
+		// Walk outward from every target at once, so each tile is reached first from the nearest
+		// one.
+		let mut coordinate_queue: LinkedList<_> =
+			targets.into_iter().map(|coord| (coord, 0)).collect();
+		let mut visited: HashSet<_> = coordinate_queue.iter().map(|(coord, _)| *coord).collect();
+
+		while let Some((coord, distance)) = coordinate_queue.pop_front()
+		{
+			coord.set(&mut distances, Some(distance)).expect(COORDINATE_ON_TILESET);
+
+			Adjacent::from_grid_coordinate_with_build(grid, build, &coord, movement)
+				.into_iter()
+				.filter(|(_, adjacent_coord)| {
+					adjacent_coord
+						.get_from_with_build(grid, build)
+						.expect(COORDINATE_ON_TILESET)
+						.is_passable()
+				})
+				.for_each(|(_, adjacent_coord)| {
+					if visited.insert(adjacent_coord)
+					{
+						coordinate_queue.push_back((adjacent_coord, distance + 1));
+					}
+				});
+		}
+
+		Self(distances)
+	}
+
+	/// # Summary
+	///
+	/// Measure how far each tile of a `grid` (with some `build` placed) is from the nearest
+	/// `end_tile`.
+	///
+	/// # Remarks
+	///
+	/// Movement is the same as [`ShortestPath`](super::ShortestPath), so the distance of an
+	/// entrance is one less than the length of its path (not counting the distance from the
+	/// spawn).
+	pub fn to_tile(
+		grid: &[impl AsRef<[Tile]>],
+		build: Option<&impl Container<Coordinate>>,
+		end_tile: Tile,
+		movement: impl Into<MovementRules>,
+	) -> Self
+	{
+		let targets: Vec<_> = grid
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| {
+				row.as_ref()
+					.iter()
+					.enumerate()
+					.filter(|(_, tile)| **tile == end_tile)
+					.map(move |(x, _)| Coordinate(x, y))
+			})
+			.collect();
+
+		Self::to_coordinates(grid, build, targets, movement)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Coordinate, DistanceField, Tile};
+	use crate::map::{
+		tileset::{tests::PARK_TWO_SPAWN, Tileset},
+		Build,
+		ShortestPath,
+	};
+
+	#[test]
+	fn to_tile()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(10), |_| ()).unwrap();
+
+		let start = Instant::now();
+		let field =
+			DistanceField::to_tile(&test_tileset.grid, Some(&build.blocks), Tile::Core, false);
+		println!("DistanceField::to_tile {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(field.get(&Coordinate(5, 11)), Some(0));
+		assert_eq!(field.get(&Coordinate(0, 0)), None);
+		build.blocks.iter().for_each(|block| assert_eq!(field.get(block), None));
+
+		// Every entrance is as far away as its shortest path says.
+		test_tileset.entrances_by_region.iter().flat_map(|e| e.keys()).for_each(|entrance| {
+			let path = ShortestPath::from_grid_coordinate_to_tile(
+				&test_tileset.grid,
+				Some(&build.blocks),
+				*entrance,
+				None,
+				Tile::Core,
+				false,
+			);
+			assert_eq!(field.get(entrance), path.map(|p| p.len() - 1));
+		});
+	}
+}
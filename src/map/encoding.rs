@@ -0,0 +1,99 @@
+mod error;
+
+use std::{fmt, str::FromStr};
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{Map, Tile};
+
+/// # Summary
+///
+/// How the grid of a [`Map`] is written to a file.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encoding
+{
+	/// # Summary
+	///
+	/// Each row is a string, with one character per [`Tile`] (see [`char::from`]).
+	Compact,
+
+	/// # Summary
+	///
+	/// Each row is an array of numbers, with one number per [`Tile`] (see [`Tile::number`]).
+	Numeric,
+
+	/// # Summary
+	///
+	/// The grid's size, and the [`Coordinate`](super::Coordinate)s of every [`Tile`] which is not
+	/// [`Tile::Impass`].
+	Sparse,
+
+	/// # Summary
+	///
+	/// Each row is an array of [`Tile`] names.
+	#[default]
+	Verbose,
+}
+
+impl Encoding
+{
+	/// # Summary
+	///
+	/// Make a `map` be written with this [`Encoding`], using the default character or number for
+	/// each [`Tile`].
+	pub fn apply_to(&self, map: &mut Map)
+	{
+		map.legend = None;
+		map.sparse = false;
+		map.tile_codes = None;
+
+		match self
+		{
+			Encoding::Compact =>
+			{
+				map.legend = Some(Tile::ALL.iter().map(|tile| (char::from(*tile), *tile)).collect())
+			},
+			Encoding::Numeric =>
+			{
+				map.tile_codes = Some(Tile::ALL.iter().map(|tile| (tile.number(), *tile)).collect())
+			},
+			Encoding::Sparse => map.sparse = true,
+			Encoding::Verbose => (),
+		}
+	}
+}
+
+impl fmt::Display for Encoding
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			Encoding::Compact => "compact",
+			Encoding::Numeric => "numeric",
+			Encoding::Sparse => "sparse",
+			Encoding::Verbose => "verbose",
+		})
+	}
+}
+
+impl FromStr for Encoding
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"compact" => Encoding::Compact,
+			"numeric" => Encoding::Numeric,
+			"sparse" => Encoding::Sparse,
+			"verbose" => Encoding::Verbose,
+			_ => return Err(Error::UnknownEncoding { name: name.into() }),
+		})
+	}
+}
@@ -0,0 +1,127 @@
+mod error;
+
+use std::{fmt, str::FromStr};
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Tile;
+
+/// # Summary
+///
+/// A kind of enemy, which decides what [`Tile`]s can be moved through.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnemyClass
+{
+	/// # Summary
+	///
+	/// An enemy which can fly over [`Tile::Water`].
+	Flying,
+
+	/// # Summary
+	///
+	/// An enemy which walks, and so must go around [`Tile::Water`].
+	#[default]
+	Ground,
+}
+
+impl EnemyClass
+{
+	/// # Summary
+	///
+	/// Every [`EnemyClass`].
+	pub const ALL: [EnemyClass; 2] = [EnemyClass::Flying, EnemyClass::Ground];
+
+	/// # Summary
+	///
+	/// Whether or not this [`EnemyClass`] can move through some `tile`.
+	pub fn can_pass(&self, tile: Tile) -> bool
+	{
+		self.sees(tile).is_passable()
+	}
+
+	/// # Summary
+	///
+	/// What some `tile` is like for this [`EnemyClass`], in terms of the tiles which
+	/// [`ShortestPath`](super::ShortestPath)s understand.
+	pub fn sees(&self, tile: Tile) -> Tile
+	{
+		match (self, tile)
+		{
+			(EnemyClass::Flying, Tile::Water) => Tile::Pass,
+			(_, t) => t,
+		}
+	}
+
+	/// # Summary
+	///
+	/// The `grid` as seen by this [`EnemyClass`]. See [`EnemyClass::sees`].
+	pub fn view(&self, grid: &[impl AsRef<[Tile]>]) -> Vec<Vec<Tile>>
+	{
+		grid.iter().map(|row| row.as_ref().iter().map(|tile| self.sees(*tile)).collect()).collect()
+	}
+}
+
+impl fmt::Display for EnemyClass
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			EnemyClass::Flying => "flying",
+			EnemyClass::Ground => "ground",
+		})
+	}
+}
+
+impl FromStr for EnemyClass
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"flying" => EnemyClass::Flying,
+			"ground" => EnemyClass::Ground,
+			_ => return Err(Error::UnknownClass { name: name.into() }),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{EnemyClass, Tile::*};
+	use crate::map::{tileset::Tileset, Build};
+
+	#[test]
+	fn view()
+	{
+		#[rustfmt::skip]
+		let grid = [
+			// 0    1      2
+			[Spawn, Water, Core],  // 0
+			[Empty, Water, Empty], // 1
+			[Empty, Empty, Empty], // 2
+		];
+
+		let start = Instant::now();
+		let flying = Tileset::new(EnemyClass::Flying.view(&grid));
+		let ground = Tileset::new(EnemyClass::Ground.view(&grid));
+		println!("EnemyClass::view {}us", Instant::now().duration_since(start).as_micros() / 2);
+
+		assert!(EnemyClass::Flying.can_pass(Water));
+		assert!(!EnemyClass::Ground.can_pass(Water));
+
+		// Flying enemies may cut across the water, but enemies on foot must go around it.
+		let build = Build { blocks: HashSet::new() };
+		assert_eq!(build.shortest_path_lengths(&flying, false), vec![Some(5)]);
+		assert_eq!(build.shortest_path_lengths(&ground, false), vec![Some(7)]);
+	}
+}
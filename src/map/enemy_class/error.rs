@@ -0,0 +1,15 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("There is no enemy class named {:?}", name))]
+	UnknownClass
+	{
+		name: String
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Coordinate;
+
+/// # Summary
+///
+/// The key/value pairs attached to a single [`Coordinate`].
+pub type Properties = BTreeMap<String, Value>;
+
+/// # Summary
+///
+/// The [`Properties`] of a single [`Coordinate`], as it is written in a file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Entry
+{
+	coordinate: Coordinate,
+	properties: Properties,
+}
+
+/// # Summary
+///
+/// Arbitrary data attached to the tiles of a grid, such as names, costs, or tower slots.
+///
+/// # Remarks
+///
+/// This is written as a list of `{"coordinate": [x, y], "properties": {...}}` objects, since JSON
+/// objects may only have string keys.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "Vec<Entry>", into = "Vec<Entry>")]
+pub struct Metadata(BTreeMap<Coordinate, Properties>);
+
+impl Metadata
+{
+	/// # Summary
+	///
+	/// The [`Properties`] of a `coord`, if it has any.
+	pub fn get(&self, coord: &Coordinate) -> Option<&Properties>
+	{
+		self.0.get(coord)
+	}
+
+	/// # Summary
+	///
+	/// The value of some `key` for a `coord`, if it has been set.
+	pub fn get_value(&self, coord: &Coordinate, key: &str) -> Option<&Value>
+	{
+		self.get(coord).and_then(|properties| properties.get(key))
+	}
+
+	/// # Summary
+	///
+	/// Set the value of some `key` for a `coord`.
+	///
+	/// # Returns
+	///
+	/// The previous value, if there was one.
+	pub fn insert(
+		&mut self,
+		coord: Coordinate,
+		key: impl Into<String>,
+		value: Value,
+	) -> Option<Value>
+	{
+		self.0.entry(coord).or_default().insert(key.into(), value)
+	}
+
+	/// # Summary
+	///
+	/// Whether no [`Coordinate`] has any [`Properties`].
+	pub fn is_empty(&self) -> bool
+	{
+		self.0.is_empty()
+	}
+
+	/// # Summary
+	///
+	/// Every [`Coordinate`] which has [`Properties`], in order.
+	pub fn iter(&self) -> impl Iterator<Item = (&Coordinate, &Properties)>
+	{
+		self.0.iter()
+	}
+
+	/// # Summary
+	///
+	/// Unset some `key` for a `coord`.
+	///
+	/// # Returns
+	///
+	/// The value which was removed, if there was one.
+	pub fn remove(&mut self, coord: &Coordinate, key: &str) -> Option<Value>
+	{
+		let properties = self.0.get_mut(coord)?;
+		let removed = properties.remove(key);
+
+		if properties.is_empty()
+		{
+			self.0.remove(coord);
+		}
+
+		removed
+	}
+
+	/// # Summary
+	///
+	/// Every [`Coordinate`] which has some `key` set, and its value.
+	pub fn with_key<'m>(&'m self, key: &'m str)
+		-> impl Iterator<Item = (&'m Coordinate, &'m Value)>
+	{
+		self.0.iter().filter_map(move |(coord, properties)| properties.get(key).map(|v| (coord, v)))
+	}
+}
+
+impl From<Vec<Entry>> for Metadata
+{
+	fn from(entries: Vec<Entry>) -> Self
+	{
+		let mut metadata = Self::default();
+		entries.into_iter().for_each(|entry| {
+			metadata.0.entry(entry.coordinate).or_default().extend(entry.properties)
+		});
+
+		metadata
+	}
+}
+
+impl From<Metadata> for Vec<Entry>
+{
+	fn from(metadata: Metadata) -> Self
+	{
+		metadata
+			.0
+			.into_iter()
+			.map(|(coordinate, properties)| Entry { coordinate, properties })
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use serde_json::json;
+
+	use super::{Coordinate, Metadata};
+
+	#[test]
+	fn serde()
+	{
+		let json = json!([
+			{"coordinate": [4, 1], "properties": {"name": "kill zone", "cost": 2}},
+			{"coordinate": [0, 0], "properties": {"tower_slot": true}},
+			{"coordinate": [4, 1], "properties": {"cost": 3}},
+		]);
+
+		let start = Instant::now();
+		let mut metadata: Metadata = serde_json::from_value(json).unwrap();
+		println!("Metadata::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		// Entries for the same coordinate are merged, with later ones taking precedence.
+		assert_eq!(metadata.get_value(&Coordinate(4, 1), "cost"), Some(&json!(3)));
+		assert_eq!(metadata.with_key("tower_slot").count(), 1);
+
+		assert_eq!(metadata.remove(&Coordinate(0, 0), "tower_slot"), Some(json!(true)));
+		assert_eq!(metadata.get(&Coordinate(0, 0)), None);
+		assert_eq!(
+			metadata.insert(Coordinate(4, 1), "name", json!("gate")),
+			Some(json!("kill zone"))
+		);
+
+		assert_eq!(
+			serde_json::to_value(&metadata).unwrap(),
+			json!([{"coordinate": [4, 1], "properties": {"cost": 3, "name": "gate"}}])
+		);
+	}
+}
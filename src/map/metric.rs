@@ -0,0 +1,116 @@
+mod error;
+
+use std::{fmt, str::FromStr};
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Coordinate;
+
+/// # Summary
+///
+/// A way to measure the distance between two [`Coordinate`]s, without taking any barriers into
+/// account.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric
+{
+	/// # Summary
+	///
+	/// The number of moves it takes when diagonal moves are allowed.
+	Chebyshev,
+
+	/// # Summary
+	///
+	/// The straight-line distance, rounded to the nearest tile.
+	Euclidean,
+
+	/// # Summary
+	///
+	/// The number of moves it takes when only orthogonal moves are allowed.
+	#[default]
+	Manhattan,
+}
+
+impl Metric
+{
+	/// # Summary
+	///
+	/// Measure the distance between `a` and `b`.
+	pub fn distance(&self, a: &Coordinate, b: &Coordinate) -> usize
+	{
+		let dx = a.0.abs_diff(b.0);
+		let dy = a.1.abs_diff(b.1);
+
+		match self
+		{
+			Metric::Chebyshev => dx.max(dy),
+			Metric::Euclidean => ((dx * dx + dy * dy) as f64).sqrt().round() as usize,
+			Metric::Manhattan => dx + dy,
+		}
+	}
+
+	/// # Summary
+	///
+	/// The [`Metric`] which matches a movement model: [`Metric::Chebyshev`] if `diagonals` are
+	/// allowed, and [`Metric::Manhattan`] otherwise.
+	pub fn for_movement(diagonals: bool) -> Self
+	{
+		if diagonals
+		{
+			Metric::Chebyshev
+		}
+		else
+		{
+			Metric::Manhattan
+		}
+	}
+}
+
+impl fmt::Display for Metric
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			Metric::Chebyshev => "chebyshev",
+			Metric::Euclidean => "euclidean",
+			Metric::Manhattan => "manhattan",
+		})
+	}
+}
+
+impl FromStr for Metric
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"chebyshev" => Metric::Chebyshev,
+			"euclidean" => Metric::Euclidean,
+			"manhattan" => Metric::Manhattan,
+			_ => return Err(Error::UnknownMetric { name: name.into() }),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::{Coordinate, Metric};
+
+	#[test]
+	fn distance()
+	{
+		let (a, b) = (Coordinate(1, 1), Coordinate(4, 5));
+
+		assert_eq!(Metric::Chebyshev.distance(&a, &b), 4);
+		assert_eq!(Metric::Euclidean.distance(&a, &b), 5);
+		assert_eq!(Metric::Manhattan.distance(&a, &b), 7);
+		assert_eq!(Metric::Manhattan.distance(&a, &b), a.distance_from(&b));
+	}
+}
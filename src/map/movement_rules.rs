@@ -0,0 +1,148 @@
+mod error;
+
+use std::{fmt, str::FromStr};
+
+pub use error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// # Summary
+///
+/// Whether a diagonal move may cut the corner of an impassable tile.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CornerCutting
+{
+	/// # Summary
+	///
+	/// A diagonal move is only allowed when both of the orthogonal moves beside it are passable.
+	No,
+
+	/// # Summary
+	///
+	/// A diagonal move is allowed when at least one of the orthogonal moves beside it is passable,
+	/// so enemies may clip a single corner but not squeeze between two.
+	#[default]
+	OneSide,
+
+	/// # Summary
+	///
+	/// A diagonal move is always allowed, even between two impassable tiles.
+	Yes,
+}
+
+impl CornerCutting
+{
+	/// # Summary
+	///
+	/// Whether this is the [`Default`] rule.
+	pub fn is_default(&self) -> bool
+	{
+		*self == Self::default()
+	}
+}
+
+impl fmt::Display for CornerCutting
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(match self
+		{
+			CornerCutting::No => "no",
+			CornerCutting::OneSide => "one-side",
+			CornerCutting::Yes => "yes",
+		})
+	}
+}
+
+impl FromStr for CornerCutting
+{
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self>
+	{
+		Ok(match name
+		{
+			"no" => CornerCutting::No,
+			"one-side" => CornerCutting::OneSide,
+			"yes" => CornerCutting::Yes,
+			_ => return Err(Error::UnknownCornerCutting { name: name.into() }),
+		})
+	}
+}
+
+/// # Summary
+///
+/// How enemies move from one tile to the next.
+///
+/// # Remarks
+///
+/// A `bool` converts into the rules which only say whether `diagonals` are allowed, so anything
+/// which takes `impl Into<MovementRules>` can still be given `diagonals`.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub struct MovementRules
+{
+	/// # Summary
+	///
+	/// Whether diagonal moves may cut corners. Only matters when `diagonals` are allowed.
+	#[serde(default)]
+	pub corner_cutting: CornerCutting,
+
+	/// # Summary
+	///
+	/// Whether diagonal moves are allowed at all.
+	pub diagonals: bool,
+}
+
+impl MovementRules
+{
+	/// # Summary
+	///
+	/// Whether a diagonal move is allowed, given whether each of the two orthogonal moves beside it
+	/// is passable.
+	pub fn allows_diagonal(&self, first_passable: bool, second_passable: bool) -> bool
+	{
+		self.diagonals &&
+			match self.corner_cutting
+			{
+				CornerCutting::No => first_passable && second_passable,
+				CornerCutting::OneSide => first_passable || second_passable,
+				CornerCutting::Yes => true,
+			}
+	}
+}
+
+impl From<bool> for MovementRules
+{
+	/// # Summary
+	///
+	/// The [`MovementRules`] which allow `diagonals` (or not), cutting corners by default.
+	fn from(diagonals: bool) -> Self
+	{
+		Self { corner_cutting: CornerCutting::default(), diagonals }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::{CornerCutting, MovementRules};
+
+	#[test]
+	fn allows_diagonal()
+	{
+		let rules = |corner_cutting| MovementRules { corner_cutting, diagonals: true };
+
+		assert!(rules(CornerCutting::No).allows_diagonal(true, true));
+		assert!(!rules(CornerCutting::No).allows_diagonal(true, false));
+		assert!(rules(CornerCutting::OneSide).allows_diagonal(false, true));
+		assert!(!rules(CornerCutting::OneSide).allows_diagonal(false, false));
+		assert!(rules(CornerCutting::Yes).allows_diagonal(false, false));
+
+		// Without diagonals, no diagonal move is allowed.
+		assert!(!MovementRules::from(false).allows_diagonal(true, true));
+	}
+}
@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::COORDINATE_ON_TILESET, Coordinate, CornerCutting, MovementRules, Tile};
+use crate::Container;
+
+/// # Summary
+///
+/// Which [`Coordinate`]s can be moved to from some other [`Coordinate`] in a single move.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Neighborhood
+{
+	/// # Summary
+	///
+	/// Any move which is `(dx, dy)` away, e.g. [`Neighborhood::KNIGHT`].
+	Custom(Vec<(isize, isize)>),
+
+	/// # Summary
+	///
+	/// Orthogonal and diagonal moves.
+	EightWay,
+
+	/// # Summary
+	///
+	/// Only up, right, down, and left.
+	#[default]
+	Orthogonal,
+}
+
+impl Neighborhood
+{
+	/// # Summary
+	///
+	/// The offsets of [`Neighborhood::EightWay`], orthogonal ones first.
+	pub const EIGHT_WAY: [(isize, isize); 8] =
+		[(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)];
+	/// # Summary
+	///
+	/// The offsets of a knight in chess, for use with [`Neighborhood::Custom`].
+	pub const KNIGHT: [(isize, isize); 8] =
+		[(1, -2), (2, -1), (2, 1), (1, 2), (-1, 2), (-2, 1), (-2, -1), (-1, -2)];
+	/// # Summary
+	///
+	/// The offsets of [`Neighborhood::Orthogonal`].
+	pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+	/// # Summary
+	///
+	/// The neighbors of a `coord` which are on the `grid`.
+	pub fn neighbors<'n, T>(
+		&'n self,
+		grid: &'n [impl AsRef<[T]>],
+		coord: Coordinate,
+	) -> impl Iterator<Item = Coordinate> + 'n
+	{
+		self.offsets().iter().filter_map(move |(dx, dy)| coord.offset(grid, *dx, *dy))
+	}
+
+	/// # Summary
+	///
+	/// Like [`Neighborhood::neighbors`], but a diagonal move is only allowed to cut the corner of
+	/// an impassable tile (given the `build`) as far as the `corner_cutting` rule says.
+	pub fn neighbors_with_build<'n>(
+		&'n self,
+		grid: &'n [impl AsRef<[Tile]>],
+		build: Option<&'n impl Container<Coordinate>>,
+		coord: Coordinate,
+		corner_cutting: CornerCutting,
+	) -> impl Iterator<Item = Coordinate> + 'n
+	{
+		let movement = MovementRules { corner_cutting, diagonals: true };
+		let can_move = move |dx, dy| {
+			coord.offset(grid, dx, dy).is_some_and(|c| {
+				c.get_from_with_build(grid, build).expect(COORDINATE_ON_TILESET).is_passable()
+			})
+		};
+
+		self.offsets().iter().filter_map(move |(dx, dy)| {
+			let is_diagonal_step = dx.abs() == 1 && dy.abs() == 1;
+			if is_diagonal_step && !movement.allows_diagonal(can_move(*dx, 0), can_move(0, *dy))
+			{
+				return None;
+			}
+
+			coord.offset(grid, *dx, *dy)
+		})
+	}
+
+	/// # Summary
+	///
+	/// Every `(dx, dy)` move which this [`Neighborhood`] allows.
+	pub fn offsets(&self) -> &[(isize, isize)]
+	{
+		match self
+		{
+			Neighborhood::Custom(offsets) => offsets,
+			Neighborhood::EightWay => &Self::EIGHT_WAY,
+			Neighborhood::Orthogonal => &Self::ORTHOGONAL,
+		}
+	}
+}
+
+impl From<bool> for Neighborhood
+{
+	/// # Summary
+	///
+	/// The [`Neighborhood`] which matches whether or not `diagonals` are allowed.
+	fn from(diagonals: bool) -> Self
+	{
+		if diagonals
+		{
+			Neighborhood::EightWay
+		}
+		else
+		{
+			Neighborhood::Orthogonal
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{Coordinate, CornerCutting, Neighborhood, Tile, Tile::*};
+	use crate::map::Adjacent;
+
+	#[rustfmt::skip]
+	const ARRAY: [[Tile; 5]; 5] = [
+		// 0    1       2      3      4
+		[Empty, Empty,  Empty, Empty, Empty], // 0
+		[Spawn, Empty,  Empty, Empty, Core],  // 1
+		[Spawn, Impass, Empty, Impass, Core], // 2
+		[Spawn, Empty,  Empty, Empty, Core],  // 3
+		[Empty, Empty,  Empty, Empty, Core],  // 4
+	];
+
+	#[test]
+	fn neighbors()
+	{
+		let start = Instant::now();
+		let knight: Vec<_> = Neighborhood::Custom(Neighborhood::KNIGHT.to_vec())
+			.neighbors(&ARRAY, Coordinate(0, 0))
+			.collect();
+		println!("Neighborhood::neighbors {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(knight, vec![Coordinate(2, 1), Coordinate(1, 2)]);
+
+		// The built-in neighborhoods agree with `Adjacent`.
+		[false, true].into_iter().for_each(|diagonals| {
+			let coord = Coordinate(2, 2);
+			assert_eq!(
+				Neighborhood::from(diagonals).neighbors(&ARRAY, coord).collect::<Vec<_>>(),
+				Adjacent::from_grid_coordinate(&ARRAY, &coord, diagonals)
+					.into_iter()
+					.map(|(_, c)| c)
+					.collect::<Vec<_>>(),
+			);
+		});
+	}
+
+	#[test]
+	fn neighbors_with_build()
+	{
+		let build: HashSet<_> = [Coordinate(2, 1)].into_iter().collect();
+
+		let start = Instant::now();
+		let neighbors: Vec<_> = Neighborhood::EightWay
+			.neighbors_with_build(&ARRAY, Some(&build), Coordinate(2, 2), CornerCutting::OneSide)
+			.collect();
+		println!(
+			"Neighborhood::neighbors_with_build {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		// Both diagonals above (2, 2) are cut off, since the block and the impasses surround them.
+		assert!(!neighbors.contains(&Coordinate(1, 1)));
+		assert!(!neighbors.contains(&Coordinate(3, 1)));
+		assert!(neighbors.contains(&Coordinate(1, 3)));
+		assert!(neighbors.contains(&Coordinate(3, 3)));
+	}
+}
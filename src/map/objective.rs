@@ -0,0 +1,237 @@
+mod error;
+mod formula;
+
+pub use error::{Error, Result};
+pub use formula::{Formula, Term};
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, MovementRules, ShortestPath, Tile};
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IntoParallelRefIterator, ParallelIterator};
+
+/// # Summary
+///
+/// What makes one [`Build`] better than another.
+///
+/// # Remarks
+///
+/// The [`Default`] objective only cares about path length.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Objective
+{
+	/// # Summary
+	///
+	/// The most, as a percentage, that any region's path may be shorter than the longest. A path
+	/// which is longer than that allows is [scored](Objective::scores) as if it were no longer,
+	/// so that a build gains nothing by lengthening one lane at the expense of the others.
+	#[serde(default)]
+	pub fairness: Option<usize>,
+
+	/// # Summary
+	///
+	/// If [`Some`], what a [`Build`] is [scored](Objective::score) by in place of the total of
+	/// its [`scores`](Objective::scores).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub formula: Option<Formula>,
+
+	/// # Summary
+	///
+	/// How many points each [`Tile::Hazard`] on a path is worth, on top of the one point every
+	/// tile of a path is worth.
+	pub hazard_damage: usize,
+
+	/// # Summary
+	///
+	/// Whether to [score](Objective::score) a [`Build`] by what it is expected to be worth after
+	/// losing a single block at random, rather than by what it is worth when whole.
+	#[serde(default)]
+	pub robust: bool,
+}
+
+impl Objective
+{
+	/// # Summary
+	///
+	/// The total of every region's [score](Objective::scores), ignoring regions with no path, or
+	/// the value of the [`formula`](Objective::formula) if there is one. Negative values of the
+	/// formula are scored as zero.
+	///
+	/// # Remarks
+	///
+	/// When [`robust`](Objective::robust), this is instead the average total after removing each
+	/// block of the `build` in turn, so that builds which depend on a few blocks score worse.
+	pub fn score(
+		&self,
+		tileset: &Tileset,
+		build: &Build,
+		movement: impl Into<MovementRules>,
+	) -> usize
+	{
+		let movement = movement.into();
+		let total = |b: &Build| -> usize {
+			let paths =
+				ShortestPath::from_entrances_to_any_core(tileset, Some(&b.blocks), movement);
+			let scores = self.scores_of(tileset, &paths);
+			match self.formula.as_ref()
+			{
+				Some(formula) =>
+				{
+					formula.evaluate(tileset, b, &paths, &scores).max(0.0).round() as usize
+				},
+				None => scores.into_iter().flatten().sum(),
+			}
+		};
+
+		if !self.robust || build.blocks.is_empty()
+		{
+			return total(build);
+		}
+
+		let sum: usize = build
+			.blocks
+			.par_iter()
+			.map(|block| {
+				let mut without_block = build.clone();
+				without_block.blocks.remove(block);
+				total(&without_block)
+			})
+			.sum();
+
+		sum / build.blocks.len()
+	}
+
+	/// # Summary
+	///
+	/// Score the [`ShortestPath`] of each region through the `build`: its length, plus
+	/// `hazard_damage` for each [`Tile::Hazard`] it crosses.
+	///
+	/// # Remarks
+	///
+	/// With some [`fairness`](Objective::fairness), no score is higher than the lowest score
+	/// allows. E.g. at `20`%, a region scores at most `125` if another scores `100`.
+	pub fn scores(
+		&self,
+		tileset: &Tileset,
+		build: &Build,
+		movement: impl Into<MovementRules>,
+	) -> Vec<Option<usize>>
+	{
+		self.scores_of(
+			tileset,
+			&ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), movement),
+		)
+	}
+
+	/// # Summary
+	///
+	/// [`Objective::scores`], given the `paths` of each region.
+	pub(super) fn scores_of(
+		&self,
+		tileset: &Tileset,
+		paths: &[Option<ShortestPath>],
+	) -> Vec<Option<usize>>
+	{
+		let scores: Vec<_> = paths
+			.iter()
+			.map(|path| {
+				path.as_ref().map(|p| {
+					let hazards = p
+						.iter()
+						.filter(|coord| coord.get_from(&tileset.grid) == Some(Tile::Hazard))
+						.count();
+
+					p.len() + hazards * self.hazard_damage
+				})
+			})
+			.collect();
+
+		match self.fairness.filter(|fairness| *fairness < 100)
+		{
+			Some(fairness) =>
+			{
+				let cap =
+					scores.iter().flatten().min().map(|lowest| lowest * 100 / (100 - fairness));
+				scores.into_iter().map(|score| score.map(|s| cap.map_or(s, |c| s.min(c)))).collect()
+			},
+			None => scores,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{Build, Objective, Tile::*, Tileset};
+	use crate::map::Coordinate;
+
+	#[test]
+	fn fairness()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0     1      2     3      4      5      6      7      8
+			vec![Spawn, Empty, Core, Empty, Empty, Empty, Empty, Empty, Spawn], // 0
+		]);
+		let build = Build { blocks: HashSet::new() };
+		let fair = |fairness| Objective { fairness: Some(fairness), ..Default::default() };
+
+		let start = Instant::now();
+		let scores = fair(50).scores(&test_tileset, &build, false);
+		println!("Objective::scores {}us", Instant::now().duration_since(start).as_micros());
+
+		// The longer lane is worth no more than twice the shorter one at 50%.
+		assert_eq!(Objective::default().scores(&test_tileset, &build, false), vec![
+			Some(3),
+			Some(7)
+		]);
+		assert_eq!(scores, vec![Some(3), Some(6)]);
+		assert_eq!(fair(0).scores(&test_tileset, &build, false), vec![Some(3), Some(3)]);
+		assert_eq!(fair(100).scores(&test_tileset, &build, false), vec![Some(3), Some(7)]);
+	}
+
+	#[test]
+	fn score()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1      2      3
+			vec![Spawn,  Empty, Empty, Core],  // 0
+			vec![Impass, Empty, Empty, Empty], // 1
+		]);
+		let build = Build { blocks: [Coordinate(2, 0)].into_iter().collect() };
+		let objective = Objective { robust: true, ..Default::default() };
+
+		let start = Instant::now();
+		let score = objective.score(&test_tileset, &build, false);
+		println!("Objective::score {}us", Instant::now().duration_since(start).as_micros());
+
+		// Whole, the build detours through the second row; without its only block, it does not.
+		assert_eq!(Objective::default().score(&test_tileset, &build, false), 6);
+		assert_eq!(score, 4);
+	}
+
+	#[test]
+	fn scores()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0     1      2       3       4
+			vec![Spawn, Empty, Hazard, Hazard, Core], // 0
+		]);
+		let build = Build { blocks: HashSet::new() };
+
+		let start = Instant::now();
+		let scores = Objective { hazard_damage: 10, ..Default::default() }.scores(
+			&test_tileset,
+			&build,
+			false,
+		);
+		println!("Objective::scores {}us", Instant::now().duration_since(start).as_micros());
+
+		// Enemies go straight over the two hazards.
+		assert_eq!(scores, vec![Some(5 + 2 * 10)]);
+		assert_eq!(Objective::default().scores(&test_tileset, &build, false), vec![Some(5)]);
+	}
+}
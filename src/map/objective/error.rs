@@ -0,0 +1,25 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("{:?} is not of the form `weight*term`, e.g. `0.5*turns`", term))]
+	InvalidTerm
+	{
+		term: String
+	},
+
+	#[snafu(display(
+		"There is no term named {:?}. Try `blocks`, `hazards`, `length`, `max_path`, `min_path`, \
+		 `overlap`, `score`, or `turns`",
+		name
+	))]
+	UnknownTerm
+	{
+		name: String
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
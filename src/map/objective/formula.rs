@@ -0,0 +1,357 @@
+use std::{
+	cmp::Ordering,
+	collections::HashMap,
+	fmt,
+	hash::{Hash, Hasher},
+	mem,
+	str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result};
+use crate::map::{tileset::Tileset, Build, Coordinate, ShortestPath, Tile};
+
+/// # Summary
+///
+/// Something about a [`Build`] which a [`Formula`] can weigh.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Term
+{
+	/// # Summary
+	///
+	/// How many blocks the build has.
+	Blocks,
+
+	/// # Summary
+	///
+	/// How many [`Tile::Hazard`]s the paths cross, in total.
+	Hazards,
+
+	/// # Summary
+	///
+	/// The total length of every region's path.
+	Length,
+
+	/// # Summary
+	///
+	/// The length of the longest region's path.
+	MaxPath,
+
+	/// # Summary
+	///
+	/// The length of the shortest region's path.
+	MinPath,
+
+	/// # Summary
+	///
+	/// How many tiles are on the paths of more than one region, where towers can hit several
+	/// lanes at once.
+	Overlap,
+
+	/// # Summary
+	///
+	/// What the [`Objective`](super::Objective) would score the build as without a formula.
+	Score,
+
+	/// # Summary
+	///
+	/// How many times the paths change direction, in total.
+	Turns,
+}
+
+impl Term
+{
+	/// # Summary
+	///
+	/// Every [`Term`], by the name it is written as.
+	pub const ALL: [(&'static str, Term); 8] = [
+		("blocks", Term::Blocks),
+		("hazards", Term::Hazards),
+		("length", Term::Length),
+		("max_path", Term::MaxPath),
+		("min_path", Term::MinPath),
+		("overlap", Term::Overlap),
+		("score", Term::Score),
+		("turns", Term::Turns),
+	];
+}
+
+/// # Summary
+///
+/// A weighted sum of [`Term`]s, such as `2*min_path + overlap + 0.5*turns`, which
+/// [scores](super::Objective::score) a [`Build`] in place of the total path length.
+///
+/// # Remarks
+///
+/// Each term may be given a weight before or after a `*`, and is subtracted rather than added if
+/// it follows a `-`. Formulas are compared by how they were written.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Formula
+{
+	source: String,
+	terms: Vec<(f64, Term)>,
+}
+
+impl Formula
+{
+	/// # Summary
+	///
+	/// Weigh the [`Term`]s of a `build` on a `tileset`, given the `paths` of each region through
+	/// it and their `scores`.
+	pub(super) fn evaluate(
+		&self,
+		tileset: &Tileset,
+		build: &Build,
+		paths: &[Option<ShortestPath>],
+		scores: &[Option<usize>],
+	) -> f64
+	{
+		let lengths = || paths.iter().flatten().map(ShortestPath::len);
+
+		self.terms
+			.iter()
+			.map(|(weight, term)| {
+				let value = match term
+				{
+					Term::Blocks => build.blocks.len(),
+					Term::Hazards => paths
+						.iter()
+						.flatten()
+						.flat_map(ShortestPath::iter)
+						.filter(|coord| coord.get_from(&tileset.grid) == Some(Tile::Hazard))
+						.count(),
+					Term::Length => lengths().sum(),
+					Term::MaxPath => lengths().max().unwrap_or(0),
+					Term::MinPath => lengths().min().unwrap_or(0),
+					Term::Overlap =>
+					{
+						let mut regions_on = HashMap::<Coordinate, usize>::new();
+						paths.iter().flatten().flat_map(ShortestPath::iter).for_each(|coord| {
+							*regions_on.entry(*coord).or_default() += 1;
+						});
+						regions_on.values().filter(|regions| **regions > 1).count()
+					},
+					Term::Score => scores.iter().flatten().sum(),
+					Term::Turns => paths
+						.iter()
+						.flatten()
+						.map(|path| {
+							path.coordinates()
+								.windows(3)
+								.filter(|moves| {
+									let step = |from: Coordinate, to: Coordinate| {
+										(
+											to.0 as isize - from.0 as isize,
+											to.1 as isize - from.1 as isize,
+										)
+									};
+									step(moves[0], moves[1]) != step(moves[1], moves[2])
+								})
+								.count()
+						})
+						.sum(),
+				};
+
+				weight * value as f64
+			})
+			.sum()
+	}
+}
+
+impl Eq for Formula {}
+
+impl fmt::Display for Formula
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		f.write_str(&self.source)
+	}
+}
+
+impl From<Formula> for String
+{
+	fn from(formula: Formula) -> Self
+	{
+		formula.source
+	}
+}
+
+impl FromStr for Formula
+{
+	type Err = Error;
+
+	/// # Summary
+	///
+	/// Read a [`Formula`] such as `2*min_path + overlap - 0.5*turns`.
+	///
+	/// # Errors
+	///
+	/// * [`Error::InvalidTerm`], if some term is empty, or its weight is not a number.
+	/// * [`Error::UnknownTerm`], if some term is not one of [`Term::ALL`].
+	fn from_str(source: &str) -> Result<Self>
+	{
+		let compact: String = source.chars().filter(|c| !c.is_whitespace()).collect();
+		let mut chunks = Vec::new();
+		let mut current = String::new();
+		let mut sign = 1.0;
+
+		for c in compact.chars()
+		{
+			match c
+			{
+				'+' | '-' =>
+				{
+					if !current.is_empty()
+					{
+						chunks.push((sign, mem::take(&mut current)));
+						sign = 1.0;
+					}
+
+					if c == '-'
+					{
+						sign = -sign;
+					}
+				},
+				_ => current.push(c),
+			}
+		}
+
+		if current.is_empty()
+		{
+			return Err(Error::InvalidTerm { term: source.trim().into() });
+		}
+
+		chunks.push((sign, current));
+
+		let terms = chunks
+			.into_iter()
+			.map(|(sign, chunk)| {
+				let weight = |text: &str| text.parse::<f64>().ok().filter(|w| w.is_finite());
+				let (weight, name) = match chunk.split_once('*')
+				{
+					Some((left, right)) => match (weight(left), weight(right))
+					{
+						(Some(w), _) => (w, right),
+						(_, Some(w)) => (w, left),
+						_ => return Err(Error::InvalidTerm { term: chunk.clone() }),
+					},
+					None => (1.0, chunk.as_str()),
+				};
+
+				Term::ALL
+					.iter()
+					.find(|(term_name, _)| *term_name == name)
+					.map(|(_, term)| (sign * weight, *term))
+					.ok_or_else(|| Error::UnknownTerm { name: name.into() })
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(Self { source: source.trim().into(), terms })
+	}
+}
+
+impl Hash for Formula
+{
+	fn hash<H: Hasher>(&self, state: &mut H)
+	{
+		self.source.hash(state);
+	}
+}
+
+impl Ord for Formula
+{
+	fn cmp(&self, other: &Self) -> Ordering
+	{
+		self.source.cmp(&other.source)
+	}
+}
+
+impl PartialEq for Formula
+{
+	fn eq(&self, other: &Self) -> bool
+	{
+		self.source == other.source
+	}
+}
+
+impl PartialOrd for Formula
+{
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering>
+	{
+		Some(self.cmp(other))
+	}
+}
+
+impl TryFrom<String> for Formula
+{
+	type Error = Error;
+
+	fn try_from(source: String) -> Result<Self>
+	{
+		source.parse()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{Formula, Term};
+	use crate::map::{tileset::Tileset, Build, Coordinate, ShortestPath, Tile::*};
+
+	#[test]
+	fn evaluate()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0      1       2       3       4
+			vec![Spawn,  Empty,  Hazard, Empty,  Empty], // 0
+			vec![Impass, Impass, Impass, Impass, Core],  // 1
+		]);
+		let build = Build { blocks: [Coordinate(1, 1)].into_iter().collect() };
+		let paths =
+			ShortestPath::from_entrances_to_any_core(&test_tileset, Some(&build.blocks), false);
+		let scores = vec![paths[0].as_ref().map(ShortestPath::len)];
+		let value = |formula: &str| {
+			formula.parse::<Formula>().unwrap().evaluate(&test_tileset, &build, &paths, &scores)
+		};
+
+		let start = Instant::now();
+		let length = value("length");
+		println!("Formula::evaluate {}us", Instant::now().duration_since(start).as_micros());
+
+		// The path runs along the top row, over the hazard, and turns down to the core once.
+		assert_eq!(length, 6.0);
+		assert_eq!(value("2*min_path + max_path"), 18.0);
+		assert_eq!(value("hazards + turns + overlap"), 2.0);
+		assert_eq!(value("score - 0.5*blocks"), 5.5);
+	}
+
+	#[test]
+	fn from_str()
+	{
+		let start = Instant::now();
+		let formula = " 2*min_path + overlap - turns*0.5 ".parse::<Formula>().unwrap();
+		println!("Formula::from_str {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(formula.terms, vec![
+			(2.0, Term::MinPath),
+			(1.0, Term::Overlap),
+			(-0.5, Term::Turns)
+		]);
+		assert_eq!(formula.to_string(), "2*min_path + overlap - turns*0.5");
+		assert_eq!(
+			serde_json::from_value::<Formula>(serde_json::to_value(&formula).unwrap()).unwrap(),
+			formula
+		);
+
+		assert!("".parse::<Formula>().is_err());
+		assert!("length +".parse::<Formula>().is_err());
+		assert!("2*lenght".parse::<Formula>().is_err());
+		assert!("two*length".parse::<Formula>().is_err());
+		assert!(HashSet::<Formula>::from([formula.clone()]).contains(&formula));
+	}
+}
@@ -0,0 +1,257 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, ChokePoints, Coordinate, DistanceField, Tile};
+
+/// # Summary
+///
+/// A place on a [`RegionGraph`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum Node
+{
+	/// # Summary
+	///
+	/// The [natural choke point](ChokePoints::natural) of some spawn `region`.
+	ChokePoint
+	{
+		region: usize, tiles: Vec<Coordinate>
+	},
+
+	/// # Summary
+	///
+	/// A [`Tile::Core`] region.
+	Core
+	{
+		id: usize
+	},
+
+	/// # Summary
+	///
+	/// An entrance of some spawn region.
+	Entrance
+	{
+		coordinate: Coordinate
+	},
+
+	/// # Summary
+	///
+	/// A [`Tile::Spawn`] region.
+	Spawn
+	{
+		id: usize
+	},
+}
+
+impl Node
+{
+	/// # Summary
+	///
+	/// A name for this [`Node`] which is unique within its [`RegionGraph`].
+	fn id(&self) -> String
+	{
+		match self
+		{
+			Node::ChokePoint { region, .. } => format!("choke_point_{}", region),
+			Node::Core { id } => format!("core_{}", id),
+			Node::Entrance { coordinate } => format!("entrance_{}_{}", coordinate.0, coordinate.1),
+			Node::Spawn { id } => format!("spawn_{}", id),
+		}
+	}
+}
+
+impl Display for Node
+{
+	/// # Summary
+	///
+	/// Write this [`Node`] as a DOT statement.
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+	{
+		let (label, shape) = match self
+		{
+			Node::ChokePoint { tiles, .. } => (
+				format!(
+					"choke point\\n{}",
+					tiles
+						.iter()
+						.map(|coord| format!("({}, {})", coord.0, coord.1))
+						.collect::<Vec<_>>()
+						.join(" ")
+				),
+				"octagon",
+			),
+			Node::Core { id } => (format!("core {}", id), "doublecircle"),
+			Node::Entrance { coordinate } =>
+			{
+				(format!("({}, {})", coordinate.0, coordinate.1), "box")
+			},
+			Node::Spawn { id } => (format!("spawn {}", id), "circle"),
+		};
+
+		write!(f, "\t{} [label=\"{}\", shape={}];", self.id(), label, shape)
+	}
+}
+
+/// # Summary
+///
+/// A connection between two [`Node`]s of a [`RegionGraph`], by their index.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Edge
+{
+	pub from: usize,
+
+	/// # Summary
+	///
+	/// How many moves it takes to get from one [`Node`] to the other, if that is meaningful.
+	pub length: Option<usize>,
+
+	pub to: usize,
+}
+
+/// # Summary
+///
+/// An abstract view of a map: which spawn regions use which entrances, how far each entrance is
+/// from each core, and where each region's natural choke point is.
+///
+/// # Remarks
+///
+/// This is [`Display`]ed in the DOT language, so it can be drawn with Graphviz.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RegionGraph
+{
+	pub edges: Vec<Edge>,
+	pub nodes: Vec<Node>,
+}
+
+impl RegionGraph
+{
+	/// # Summary
+	///
+	/// Create the [`RegionGraph`] of a `tileset` with some `build` placed.
+	pub fn of(tileset: &Tileset, build: &Build, diagonals: bool) -> Self
+	{
+		let mut graph = Self::default();
+		let add_node = |graph: &mut Self, node: Node| {
+			graph.nodes.push(node);
+			graph.nodes.len() - 1
+		};
+
+		let cores: Vec<_> = tileset
+			.regions(Tile::Core)
+			.expect("Expected cores to be regions")
+			.into_iter()
+			.map(|core| {
+				let field = DistanceField::to_coordinates(
+					&tileset.grid,
+					Some(&build.blocks),
+					core.coordinates.iter().copied(),
+					diagonals,
+				);
+				(add_node(&mut graph, Node::Core { id: core.id }), field)
+			})
+			.collect();
+
+		tileset.entrances().into_iter().for_each(|spawn| {
+			let spawn_node = add_node(&mut graph, Node::Spawn { id: spawn.id });
+
+			let mut entrances: Vec<_> = spawn.entrances.into_iter().collect();
+			entrances.sort_unstable_by_key(|(coord, _)| (coord.1, coord.0));
+			entrances.into_iter().for_each(|(coordinate, distance)| {
+				let entrance_node = add_node(&mut graph, Node::Entrance { coordinate });
+				graph.edges.push(Edge {
+					from: spawn_node,
+					length: Some(distance),
+					to: entrance_node,
+				});
+
+				cores.iter().for_each(|(core_node, field)| {
+					if let Some(length) = field.get(&coordinate)
+					{
+						graph.edges.push(Edge {
+							from: entrance_node,
+							length: Some(length),
+							to: *core_node,
+						});
+					}
+				});
+			});
+
+			if let Some(tiles) = ChokePoints::min_cut(tileset, spawn.id, diagonals)
+			{
+				let choke_point_node =
+					add_node(&mut graph, Node::ChokePoint { region: spawn.id, tiles });
+				graph.edges.push(Edge { from: spawn_node, length: None, to: choke_point_node });
+			}
+		});
+
+		graph
+	}
+}
+
+impl Display for RegionGraph
+{
+	/// # Summary
+	///
+	/// Write this [`RegionGraph`] in the DOT language.
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+	{
+		writeln!(f, "digraph map {{")?;
+		self.nodes.iter().try_for_each(|node| writeln!(f, "{}", node))?;
+
+		self.edges.iter().try_for_each(|edge| {
+			let (from, to) = (self.nodes[edge.from].id(), self.nodes[edge.to].id());
+			match edge.length
+			{
+				Some(length) => writeln!(f, "\t{} -> {} [label=\"{}\"];", from, to, length),
+				None => writeln!(f, "\t{} -> {} [style=dashed];", from, to),
+			}
+		})?;
+
+		writeln!(f, "}}")
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, Coordinate, Node, RegionGraph, Tileset};
+	use crate::map::Tile::*;
+
+	#[test]
+	fn of()
+	{
+		#[rustfmt::skip]
+		let mut grid = vec![
+			// 0    1      2      3
+			vec![Spawn, Empty, Empty, Empty], // 0
+			vec![Empty, Block, Empty, Core],  // 1
+			vec![Empty, Empty, Empty, Empty], // 2
+		];
+		let build = Build::take_from(&mut grid);
+		let test_tileset = Tileset::new(grid);
+
+		let start = Instant::now();
+		let graph = RegionGraph::of(&test_tileset, &build, false);
+		println!("RegionGraph::of {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(graph.nodes, vec![
+			Node::Core { id: 0 },
+			Node::Spawn { id: 0 },
+			Node::Entrance { coordinate: Coordinate(1, 0) },
+			Node::Entrance { coordinate: Coordinate(0, 1) },
+			Node::ChokePoint { region: 0, tiles: vec![Coordinate(1, 0), Coordinate(0, 1)] },
+		]);
+
+		let dot = graph.to_string();
+		assert!(dot.starts_with("digraph map {"));
+		assert!(dot.contains("spawn_0 -> entrance_1_0 [label=\"1\"];"));
+		assert!(dot.contains("entrance_1_0 -> core_0 [label=\"3\"];"));
+
+		// The block is in the way, so the lower entrance has to go around it.
+		assert!(dot.contains("entrance_0_1 -> core_0 [label=\"5\"];"));
+		assert!(dot.contains("spawn_0 -> choke_point_0 [style=dashed];"));
+	}
+}
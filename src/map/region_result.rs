@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, Coordinate, MovementRules, ShortestPath};
+
+/// # Summary
+///
+/// How a single spawn region fared in a solved [`Map`](super::Map).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RegionResult
+{
+	/// # Summary
+	///
+	/// The length of the region's shortest path before any blocks were placed, or [`None`] if it
+	/// had no path to a core.
+	pub baseline_length: Option<usize>,
+
+	/// # Summary
+	///
+	/// The tiles which enemies of the region enter the map from, in reading order.
+	pub entrances: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The length of the region's shortest path through the build, or [`None`] if it has no path
+	/// to a core.
+	pub final_length: Option<usize>,
+
+	/// # Summary
+	///
+	/// The name of the region, if it has one (see [`Tileset::region_names`]).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// # Summary
+	///
+	/// The region's shortest path through the build, from the entrance to the core. Empty if it
+	/// has no path to a core.
+	pub path: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// The longest that any build could make the region's path (see [`Tileset::upper_bounds`]).
+	/// The closer the `final_length` is to this, the less there is to gain from searching for a
+	/// better build.
+	pub upper_bound: usize,
+}
+
+impl RegionResult
+{
+	/// # Summary
+	///
+	/// The [`RegionResult`] of each region of a `tileset` with some `build`, in the same order as
+	/// the [`entrances_by_region`](Tileset::entrances_by_region).
+	pub fn of(tileset: &Tileset, build: &Build, movement: impl Into<MovementRules>) -> Vec<Self>
+	{
+		let movement = movement.into();
+		let baseline =
+			Build { blocks: Default::default() }.shortest_path_lengths(tileset, movement);
+		let paths =
+			ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), movement);
+		let upper_bounds = tileset.upper_bounds(movement);
+
+		tileset
+			.entrances_by_region
+			.iter()
+			.zip(tileset.region_names())
+			.zip(baseline.into_iter().zip(paths).zip(upper_bounds))
+			.map(|((entrances, name), ((baseline_length, path), upper_bound))| {
+				let mut entrances: Vec<_> = entrances.keys().copied().collect();
+				entrances.sort_unstable_by_key(|coord| (coord.1, coord.0));
+
+				Self {
+					baseline_length,
+					entrances,
+					final_length: path.as_ref().map(ShortestPath::len),
+					name,
+					path: path.map(|p| p.coordinates().to_vec()).unwrap_or_default(),
+					upper_bound,
+				}
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, RegionResult, Tileset};
+	use crate::map::tileset::tests::PARK_TWO_SPAWN;
+
+	#[test]
+	fn of()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(10), |_| ()).unwrap();
+
+		let start = Instant::now();
+		let regions = RegionResult::of(&test_tileset, &build, false);
+		println!("RegionResult::of {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(regions.len(), 2);
+		assert_eq!(
+			regions.iter().map(|r| r.final_length).collect::<Vec<_>>(),
+			build.shortest_path_lengths(&test_tileset, false)
+		);
+
+		regions.iter().for_each(|region| {
+			assert!(region.baseline_length <= region.final_length);
+			assert!(region.final_length <= Some(region.upper_bound));
+			assert!(region.entrances.contains(&region.path[0]));
+			assert!(region.path.iter().all(|coord| !build.blocks.contains(coord)));
+		});
+	}
+}
@@ -0,0 +1,744 @@
+mod error;
+mod migration;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+pub use error::{Error, Result};
+use migration::Legacy;
+use serde::{
+	de::{value, IntoDeserializer},
+	Deserialize,
+	Serialize,
+};
+
+use super::{
+	tileset::{RegionRule, Targeting, Tileset},
+	Adjacent,
+	Coordinate,
+	DisplayTile,
+	DistanceField,
+	Map,
+	Metadata,
+	RegionResult,
+	Stats,
+	Tile,
+	Warning,
+};
+
+/// # Summary
+///
+/// The most tiles a [sparse](Grid::Sparse) grid may have, so that a mistaken (or malicious) `size`
+/// does not try to allocate more memory than any machine has.
+pub const MAX_SPARSE_TILES: usize = 1 << 28;
+
+/// # Summary
+///
+/// A row of a [`Repr`]'s grid.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Row
+{
+	/// # Summary
+	///
+	/// One character per [`Tile`], as described by a legend.
+	Codes(String),
+
+	/// # Summary
+	///
+	/// One number per [`Tile`], as described by a table of tile codes.
+	Numbers(Vec<usize>),
+
+	/// # Summary
+	///
+	/// Every [`Tile`] by name.
+	Tiles(Vec<Tile>),
+
+	/// # Summary
+	///
+	/// Names, some of which are not [`Tile`]s.
+	Names(Vec<String>),
+}
+
+/// # Summary
+///
+/// The grid of a [`Repr`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Grid
+{
+	/// # Summary
+	///
+	/// Every row of the grid.
+	Rows(Vec<Row>),
+
+	/// # Summary
+	///
+	/// The `width` and `height` of the grid, and where each kind of [`Tile`] is within it. Any
+	/// [`Coordinate`] which is not listed is a [`Tile::Impass`]. There may be at most
+	/// [`MAX_SPARSE_TILES`].
+	Sparse
+	{
+		size: (usize, usize), tiles: BTreeMap<Tile, Vec<Coordinate>>
+	},
+}
+
+/// # Summary
+///
+/// How a [`Map`] is written to and read from a file.
+///
+/// # Remarks
+///
+/// Rows of the grid may be arrays of [`Tile`]s, strings of single-character codes, or arrays of
+/// numeric codes. Alternatively, the grid may be [sparse](Grid::Sparse). The characters are those
+/// of [`char::from`] unless the `legend` says otherwise, and the numbers are those of
+/// [`Tile::number`] unless the `tile_codes` say otherwise.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Repr
+{
+	/// # Summary
+	///
+	/// The version of the map format which the file was written in, or [`None`] for files from
+	/// before versions were written (which are version `0`).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	version: Option<u32>,
+
+	name: String,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	legend: Option<BTreeMap<char, Tile>>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	tile_codes: Option<BTreeMap<usize, Tile>>,
+
+	grid: Grid,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	hazard_damage: Option<usize>,
+
+	#[serde(default, skip_serializing_if = "Metadata::is_empty")]
+	metadata: Metadata,
+
+	#[serde(default, skip_serializing_if = "RegionRule::is_default")]
+	region_rule: RegionRule,
+
+	#[serde(default, skip_serializing_if = "Targeting::is_default")]
+	targeting: Targeting,
+
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	core_assignments: BTreeMap<usize, Coordinate>,
+
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	declared_entrances: BTreeMap<usize, Vec<Coordinate>>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	regions: Vec<RegionResult>,
+
+	shortest_path_length: Option<Vec<Option<usize>>>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	stats: Option<Stats>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	players: Vec<Vec<Coordinate>>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	distance_to_core: Option<DistanceField>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	display: Option<Vec<Vec<DisplayTile>>>,
+
+	/// # Summary
+	///
+	/// Warnings describe how a [`Map`] was made or read, so ones which were written to a file no
+	/// longer apply when it is read back.
+	#[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+	warnings: Vec<Warning>,
+
+	/// # Summary
+	///
+	/// See [`Legacy::entrances`].
+	#[serde(default, skip_serializing)]
+	entrances: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// See [`Legacy::exits`].
+	#[serde(default, skip_serializing)]
+	exits: Vec<Coordinate>,
+}
+
+/// # Summary
+///
+/// Extend a `table` of codes so that every [`Tile`] on a `grid` has one. Tiles which the `table`
+/// does not mention use their `default` code, unless it is taken.
+///
+/// # Returns
+///
+/// The code of each [`Tile`], and the extended `table`; or [`None`] if some [`Tile`] could not be
+/// given a code.
+fn complete_table<K>(
+	table: &BTreeMap<K, Tile>,
+	grid: &[Vec<Tile>],
+	default: impl Fn(Tile) -> K,
+) -> Option<(HashMap<Tile, K>, BTreeMap<K, Tile>)>
+where
+	K: Copy + Ord,
+{
+	let mut codes: HashMap<_, _> = table.iter().map(|(code, tile)| (*tile, *code)).collect();
+	let mut table = table.clone();
+
+	grid.iter().flatten().try_for_each(|tile| {
+		if codes.contains_key(tile)
+		{
+			return Some(());
+		}
+
+		let code = default(*tile);
+		if table.contains_key(&code)
+		{
+			return None;
+		}
+
+		codes.insert(*tile, code);
+		table.insert(code, *tile);
+		Some(())
+	})?;
+
+	Some((codes, table))
+}
+
+/// # Summary
+///
+/// The table of codes which is used when a [`Repr`] has rows of some kind of codes, but no table
+/// to go with them.
+fn default_table<K>(code: impl Fn(Tile) -> K) -> BTreeMap<K, Tile>
+where
+	K: Ord,
+{
+	Tile::ALL.iter().map(|tile| (code(*tile), *tile)).collect()
+}
+
+impl From<Map> for Repr
+{
+	fn from(map: Map) -> Self
+	{
+		let legend =
+			map.legend.as_ref().and_then(|legend| complete_table(legend, &map.grid, char::from));
+		let tile_codes = map
+			.tile_codes
+			.as_ref()
+			.and_then(|tile_codes| complete_table(tile_codes, &map.grid, |tile| tile.number()));
+
+		let (grid, legend, tile_codes) = match (map.sparse, legend, tile_codes)
+		{
+			(true, ..) =>
+			{
+				let mut tiles = BTreeMap::<_, Vec<_>>::new();
+				map.grid.iter().enumerate().for_each(|(y, row)| {
+					row.iter().enumerate().filter(|(_, tile)| **tile != Tile::Impass).for_each(
+						|(x, tile)| tiles.entry(*tile).or_default().push(Coordinate(x, y)),
+					)
+				});
+
+				let width = map.grid.iter().map(Vec::len).max().unwrap_or(0);
+				(Grid::Sparse { size: (width, map.grid.len()), tiles }, None, None)
+			},
+			(false, Some((codes, legend)), _) => (
+				Grid::Rows(
+					map.grid
+						.into_iter()
+						.map(|row| Row::Codes(row.iter().map(|tile| codes[tile]).collect()))
+						.collect(),
+				),
+				Some(legend),
+				None,
+			),
+			(false, None, Some((codes, tile_codes))) => (
+				Grid::Rows(
+					map.grid
+						.into_iter()
+						.map(|row| Row::Numbers(row.iter().map(|tile| codes[tile]).collect()))
+						.collect(),
+				),
+				None,
+				Some(tile_codes),
+			),
+			(false, None, None) =>
+			{
+				(Grid::Rows(map.grid.into_iter().map(Row::Tiles).collect()), None, None)
+			},
+		};
+
+		Self {
+			version: Some(Map::VERSION),
+			name: map.name,
+			legend,
+			tile_codes,
+			grid,
+			hazard_damage: map.hazard_damage,
+			metadata: map.metadata,
+			region_rule: map.region_rule,
+			targeting: map.targeting,
+			core_assignments: map.core_assignments,
+			declared_entrances: map.declared_entrances,
+			regions: map.regions,
+			shortest_path_length: map.shortest_path_length,
+			stats: map.stats,
+			players: map.players,
+			distance_to_core: map.distance_to_core,
+			display: map.display,
+			warnings: map.warnings,
+			entrances: Vec::new(),
+			exits: Vec::new(),
+		}
+	}
+}
+
+impl TryFrom<Repr> for Map
+{
+	type Error = Error;
+
+	/// # Summary
+	///
+	/// Read a [`Map`] from its [`Repr`], upgrading it from older versions of the format and
+	/// repairing what can be repaired.
+	///
+	/// # Remarks
+	///
+	/// Unknown tiles are read as [`Tile::Impass`], and short rows are padded. Each repair, and any
+	/// other problem which would stop the map from being solved, is added to the `warnings`.
+	///
+	/// # Errors
+	///
+	/// * See [`migration::upgrade`].
+	/// * [`Error::OutOfBounds`], if a tile of a [sparse](Grid::Sparse) grid is not within its size.
+	/// * [`Error::TooLarge`], if a [sparse](Grid::Sparse) grid has more than [`MAX_SPARSE_TILES`].
+	fn try_from(repr: Repr) -> Result<Self>
+	{
+		let mut warnings = Vec::new();
+
+		let (mut grid, legend, tile_codes, sparse) = match repr.grid
+		{
+			Grid::Rows(rows) =>
+			{
+				// Maps which were compact or numeric when read should stay that way when written.
+				let legend = repr.legend.or_else(|| {
+					rows.iter()
+						.any(|row| matches!(row, Row::Codes(_)))
+						.then(|| default_table(char::from))
+				});
+				let tile_codes = repr.tile_codes.or_else(|| {
+					rows.iter()
+						.any(|row| matches!(row, Row::Numbers(_)))
+						.then(|| default_table(|tile| tile.number()))
+				});
+
+				let grid = rows
+					.into_iter()
+					.enumerate()
+					.map(|(y, row)| {
+						let mut unknown = |x, code| {
+							warnings
+								.push(Warning::UnknownTile { coordinate: Coordinate(x, y), code });
+							Tile::Impass
+						};
+
+						match row
+						{
+							Row::Codes(codes) => codes
+								.chars()
+								.enumerate()
+								.map(|(x, code)| {
+									legend
+										.as_ref()
+										.and_then(|l| l.get(&code).copied())
+										.unwrap_or_else(|| unknown(x, code.to_string()))
+								})
+								.collect(),
+							Row::Names(names) => names
+								.into_iter()
+								.enumerate()
+								.map(|(x, name)| {
+									Tile::deserialize(
+										IntoDeserializer::<value::Error>::into_deserializer(
+											name.as_str(),
+										),
+									)
+									.unwrap_or_else(|_| unknown(x, name))
+								})
+								.collect(),
+							Row::Numbers(codes) => codes
+								.into_iter()
+								.enumerate()
+								.map(|(x, code)| {
+									tile_codes
+										.as_ref()
+										.and_then(|t| t.get(&code).copied())
+										.unwrap_or_else(|| unknown(x, code.to_string()))
+								})
+								.collect(),
+							Row::Tiles(tiles) => tiles,
+						}
+					})
+					.collect::<Vec<Vec<_>>>();
+
+				// Sparse grids are expected to be padded, but rows are not.
+				let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+				grid.iter()
+					.enumerate()
+					.filter(|(_, row)| row.len() < width)
+					.for_each(|(row, _)| warnings.push(Warning::RaggedRow { row }));
+
+				(grid, legend, tile_codes, false)
+			},
+			Grid::Sparse { size: (width, height), tiles } =>
+			{
+				if width.checked_mul(height).is_none_or(|area| area > MAX_SPARSE_TILES)
+				{
+					return Err(Error::TooLarge { width, height, max: MAX_SPARSE_TILES });
+				}
+
+				// Check every tile before allocating anything.
+				if let Some(coord) =
+					tiles.values().flatten().find(|coord| coord.0 >= width || coord.1 >= height)
+				{
+					return Err(Error::OutOfBounds { coord: *coord, size: (width, height) });
+				}
+
+				let mut grid = vec![vec![Tile::Impass; width]; height];
+				tiles.into_iter().for_each(|(tile, coordinates)| {
+					coordinates.into_iter().for_each(|coord| grid[coord.1][coord.0] = tile)
+				});
+
+				(grid, repr.legend, repr.tile_codes, true)
+			},
+		};
+
+		migration::upgrade(repr.version.unwrap_or(0), &mut grid, Legacy {
+			entrances: repr.entrances,
+			exits: repr.exits,
+		})?;
+
+		let mut map = Self {
+			name: repr.name,
+			grid,
+			distance_to_core: repr.distance_to_core,
+			display: repr.display,
+			hazard_damage: repr.hazard_damage,
+			legend,
+			metadata: repr.metadata,
+			region_rule: repr.region_rule,
+			targeting: repr.targeting,
+			core_assignments: repr.core_assignments,
+			declared_entrances: repr.declared_entrances,
+			regions: repr.regions,
+			shortest_path_length: repr.shortest_path_length,
+			sparse,
+			stats: repr.stats,
+			players: repr.players,
+			tile_codes,
+			warnings: Vec::new(),
+		};
+
+		map.pad();
+		warnings.extend(unreachable_cores(&map));
+		map.warnings = warnings;
+
+		Ok(map)
+	}
+}
+
+/// # Summary
+///
+/// A [`Warning::UnreachableCore`] for each [`Tile::Core`] region of a `map` which no spawn can
+/// reach.
+///
+/// # Remarks
+///
+/// Only the tiles which can be walked to from a spawn are visited, so a huge map which is mostly
+/// [`Tile::Impass`] is neither searched nor copied as a whole.
+fn unreachable_cores(map: &Map) -> Vec<Warning>
+{
+	let mut queue: VecDeque<_> = map
+		.grid
+		.iter()
+		.enumerate()
+		.flat_map(|(y, row)| {
+			row.iter()
+				.enumerate()
+				.filter(|(_, tile)| **tile == Tile::Spawn)
+				.map(move |(x, _)| Coordinate(x, y))
+		})
+		.collect();
+	let mut reached: HashSet<_> = queue.iter().copied().collect();
+
+	while let Some(coord) = queue.pop_front()
+	{
+		if coord.get_from(&map.grid) == Some(Tile::Core)
+		{
+			continue;
+		}
+
+		// Diagonal movement can't reach anything new, since it may not cut corners.
+		Adjacent::from_grid_coordinate_with_build(
+			&map.grid,
+			Option::<&HashSet<_>>::None,
+			&coord,
+			false,
+		)
+		.into_iter()
+		.for_each(|(_, adjacent)| {
+			let walkable = adjacent
+				.get_from(&map.grid)
+				.is_some_and(|tile| tile.is_passable() || tile == Tile::Core);
+			if walkable && reached.insert(adjacent)
+			{
+				queue.push_back(adjacent);
+			}
+		});
+	}
+
+	Tileset::find_regions(&map.grid, Tile::Core, &map.metadata, map.region_rule)
+		.unwrap_or_default()
+		.into_iter()
+		.enumerate()
+		.filter(|(_, core)| core.is_disjoint(&reached))
+		.map(|(region, _)| Warning::UnreachableCore { region })
+		.collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Coordinate, Map, Tile::*, Warning};
+
+	#[test]
+	fn binary()
+	{
+		let mut map: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"tile_codes": {"10": "Spawn", "11": "Empty", "12": "Core"},
+				"grid": [[10, 11, 12]],
+				"metadata": [{"coordinate": [1, 0], "properties": {"cost": 2}}]
+			}"#,
+		)
+		.unwrap();
+		map.shortest_path_length = Some(vec![Some(2)]);
+
+		let start = Instant::now();
+		let bytes = rmp_serde::to_vec_named(&map).unwrap();
+		println!(
+			"Map::serialize(MessagePack) {}us",
+			Instant::now().duration_since(start).as_micros()
+		);
+
+		assert_eq!(rmp_serde::from_slice::<Map>(&bytes).unwrap(), map);
+	}
+
+	#[test]
+	fn compact()
+	{
+		let verbose: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"grid": [["Spawn", "Empty", "Core"], ["Impass", "Water", "Pass"]]
+			}"#,
+		)
+		.unwrap();
+
+		let start = Instant::now();
+		let compact: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"grid": ["S.C", "X~,"]
+			}"#,
+		)
+		.unwrap();
+		println!("Map::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(compact.grid, verbose.grid);
+		assert!(verbose.legend.is_none());
+
+		// A compact map stays compact, and tiles added later (e.g. blocks) get a code.
+		let mut solved = compact.clone();
+		solved.grid[1][1] = Block;
+		let json = serde_json::to_value(&solved).unwrap();
+		assert_eq!(json["grid"], serde_json::json!(["S.C", "X#,"]));
+		assert_eq!(serde_json::from_value::<Map>(json).unwrap(), solved);
+
+		// Legends can use any characters.
+		let custom: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"legend": {"s": "Spawn", " ": "Empty", "c": "Core", "0": "Impass", "w": "Water", "p": "Pass"},
+				"grid": ["s c", "0wp"]
+			}"#,
+		)
+		.unwrap();
+		assert_eq!(custom.grid, verbose.grid);
+		assert_eq!(
+			serde_json::from_str::<Map>(r#"{"name": "test", "grid": ["S?C"]}"#).unwrap().grid,
+			vec![vec![Spawn, Impass, Core]]
+		);
+	}
+
+	#[test]
+	fn sparse()
+	{
+		let verbose: Map = serde_json::from_str(
+			r#"{"name": "test", "grid": [["Spawn", "Empty", "Impass"], ["Impass", "Empty", "Core"]]}"#,
+		)
+		.unwrap();
+
+		let start = Instant::now();
+		let sparse: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"grid": {
+					"size": [3, 2],
+					"tiles": {"Core": [[2, 1]], "Empty": [[1, 0], [1, 1]], "Spawn": [[0, 0]]}
+				}
+			}"#,
+		)
+		.unwrap();
+		println!("Map::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(sparse.grid, verbose.grid);
+		assert_eq!(
+			serde_json::from_value::<Map>(serde_json::to_value(&sparse).unwrap()).unwrap(),
+			sparse
+		);
+		assert_eq!(
+			serde_json::to_value(&sparse).unwrap()["grid"]["size"],
+			serde_json::json!([3, 2])
+		);
+	}
+
+	#[test]
+	fn sparse_out_of_bounds()
+	{
+		let read = |grid: &str| {
+			serde_json::from_str::<Map>(&format!(r#"{{"name": "test", "grid": {}}}"#, grid))
+				.unwrap_err()
+				.to_string()
+		};
+
+		// Tiles are checked against the size, rather than growing the grid to fit them.
+		assert!(read(r#"{"size": [2, 1], "tiles": {"Spawn": [[18446744073709551615, 0]]}}"#)
+			.contains("is not within its size"));
+		assert!(read(r#"{"size": [2, 1], "tiles": {"Core": [[0, 1]]}}"#)
+			.contains("is not within its size"));
+
+		// A size which does not fit in memory is refused before anything is allocated.
+		assert!(
+			read(r#"{"size": [4000000000, 4000000000], "tiles": {}}"#).contains("is larger than")
+		);
+		assert!(
+			read(r#"{"size": [18446744073709551615, 2], "tiles": {}}"#).contains("is larger than")
+		);
+	}
+
+	#[test]
+	fn numeric()
+	{
+		let verbose: Map = serde_json::from_str(
+			r#"{"name": "test", "grid": [["Spawn", "Empty", "Core"], ["Impass", "Water", "Pass"]]}"#,
+		)
+		.unwrap();
+
+		let start = Instant::now();
+		let numeric: Map =
+			serde_json::from_str(r#"{"name": "test", "grid": [[4, 1, 5], [0, 7, 2]]}"#).unwrap();
+		println!("Map::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(numeric.grid, verbose.grid);
+
+		// A numeric map stays numeric, and tiles added later (e.g. blocks) get a code.
+		let mut solved = numeric.clone();
+		solved.grid[1][1] = Block;
+		let json = serde_json::to_value(&solved).unwrap();
+		assert_eq!(json["grid"], serde_json::json!([[4, 1, 5], [0, 3, 2]]));
+		assert_eq!(serde_json::from_value::<Map>(json).unwrap(), solved);
+
+		// Tables can use any numbers.
+		let custom: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"tile_codes": {"10": "Spawn", "11": "Empty", "12": "Core", "13": "Impass", "14": "Water", "15": "Pass"},
+				"grid": [[10, 11, 12], [13, 14, 15]]
+			}"#,
+		)
+		.unwrap();
+		assert_eq!(custom.grid, verbose.grid);
+		assert_eq!(
+			serde_json::from_str::<Map>(r#"{"name": "test", "grid": [[4, 1, 255]]}"#).unwrap().grid,
+			vec![vec![Spawn, Empty, Impass]]
+		);
+	}
+
+	#[test]
+	fn versions()
+	{
+		let start = Instant::now();
+		let legacy: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"grid": ["...", "XX."],
+				"entrances": [[0, 0]],
+				"exits": [[2, 1]]
+			}"#,
+		)
+		.unwrap();
+		println!("Map::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		// Version 0 entrances and exits are marked on the grid.
+		assert_eq!(legacy.grid, vec![vec![Spawn, Empty, Empty], vec![Impass, Impass, Core]]);
+		assert!(legacy.warnings.is_empty());
+
+		// Maps are always written in the newest version.
+		let json = serde_json::to_value(&legacy).unwrap();
+		assert_eq!(json["version"], serde_json::json!(Map::VERSION));
+		assert!(json.get("entrances").is_none());
+		assert_eq!(serde_json::from_value::<Map>(json).unwrap(), legacy);
+
+		let newer = serde_json::from_str::<Map>(&format!(
+			r#"{{"version": {}, "name": "test", "grid": ["S.C"]}}"#,
+			Map::VERSION + 1
+		))
+		.unwrap_err();
+		assert!(newer.to_string().contains("Try a newer version"));
+	}
+
+	#[test]
+	fn warnings()
+	{
+		let start = Instant::now();
+		let map: Map = serde_json::from_str(
+			r#"{
+				"name": "test",
+				"grid": [["Spawn", "Lava", "Empty"], ["Empty", "Empty"], ["Impass", "Impass", "Core"]]
+			}"#,
+		)
+		.unwrap();
+		println!("Map::deserialize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(map.grid, vec![vec![Spawn, Impass, Empty], vec![Empty, Empty, Impass], vec![
+			Impass, Impass, Core
+		]]);
+		assert_eq!(map.warnings, vec![
+			Warning::UnknownTile { coordinate: Coordinate(1, 0), code: "Lava".into() },
+			Warning::RaggedRow { row: 1 },
+			Warning::UnreachableCore { region: 0 },
+		]);
+
+		// Warnings are written, but not read back.
+		let json = serde_json::to_value(&map).unwrap();
+		assert_eq!(json["warnings"][1], serde_json::json!({"kind": "ragged-row", "row": 1}));
+		assert_eq!(serde_json::from_value::<Map>(json).unwrap().warnings, vec![
+			Warning::UnreachableCore { region: 0 }
+		]);
+	}
+}
@@ -0,0 +1,44 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+use crate::map::Coordinate;
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display(
+		"This map is version {} of the map format, but only up to version {} can be read. Try a \
+		 newer version of sanctum-solver",
+		version,
+		supported
+	))]
+	NewerVersion
+	{
+		supported: u32, version: u32
+	},
+
+	#[snafu(display(
+		"Tile {:?} of the sparse grid is not within its size of {} by {}",
+		coord,
+		size.0,
+		size.1
+	))]
+	OutOfBounds
+	{
+		coord: Coordinate, size: (usize, usize)
+	},
+
+	#[snafu(display(
+		"A sparse grid of {} by {} is larger than the {} tiles which can be read",
+		width,
+		height,
+		max
+	))]
+	TooLarge
+	{
+		width: usize, height: usize, max: usize
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
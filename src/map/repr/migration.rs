@@ -0,0 +1,54 @@
+use super::{Error, Result};
+use crate::map::{Coordinate, Map, Tile};
+
+/// # Summary
+///
+/// The fields of older versions of the map format, which are only read so that they can be
+/// upgraded.
+#[derive(Clone, Debug, Default)]
+pub struct Legacy
+{
+	/// # Summary
+	///
+	/// Where enemies came from, before [`Tile::Spawn`]s were marked on the grid (version `0`).
+	pub entrances: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// Where enemies went, before [`Tile::Core`]s were marked on the grid (version `0`).
+	pub exits: Vec<Coordinate>,
+}
+
+/// # Summary
+///
+/// Upgrade the `grid` of a map which was written in some `version` of the map format to
+/// [`Map::VERSION`].
+///
+/// # Errors
+///
+/// * [`Error::NewerVersion`], if the `version` is newer than [`Map::VERSION`].
+pub fn upgrade(version: u32, grid: &mut [Vec<Tile>], legacy: Legacy) -> Result<()>
+{
+	if version > Map::VERSION
+	{
+		return Err(Error::NewerVersion { supported: Map::VERSION, version });
+	}
+
+	// Each version only has to be upgraded to the next, so older files go through every step.
+	if version < 1
+	{
+		let mut mark = |coords: Vec<Coordinate>, tile| {
+			coords.into_iter().for_each(|coord| {
+				if let Some(t) = grid.get_mut(coord.1).and_then(|row| row.get_mut(coord.0))
+				{
+					*t = tile;
+				}
+			})
+		};
+
+		mark(legacy.entrances, Tile::Spawn);
+		mark(legacy.exits, Tile::Core);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::Tile;
+
+/// # Summary
+///
+/// A color, as `[red, green, blue]`.
+pub type Rgb = [u8; 3];
+
+/// # Summary
+///
+/// The color of each kind of [`Tile`] in a [`Screenshot`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Palette(pub BTreeMap<Tile, Rgb>);
+
+impl Palette
+{
+	/// # Summary
+	///
+	/// The [`Tile`] whose color is closest to some `color`.
+	pub fn classify(&self, color: Rgb) -> Option<Tile>
+	{
+		let distance = |other: &Rgb| -> u32 {
+			color
+				.iter()
+				.zip(other)
+				.map(|(a, b)| (i32::from(*a) - i32::from(*b)).pow(2) as u32)
+				.sum()
+		};
+
+		self.0.iter().min_by_key(|(_, c)| distance(c)).map(|(tile, _)| *tile)
+	}
+}
+
+impl Default for Palette
+{
+	/// # Summary
+	///
+	/// Colors which roughly match the top-down view of the game.
+	fn default() -> Self
+	{
+		Self(
+			[
+				(Tile::Core, [60, 150, 230]),
+				(Tile::Empty, [190, 190, 180]),
+				(Tile::Hazard, [230, 100, 30]),
+				(Tile::Impass, [40, 40, 40]),
+				(Tile::Pass, [120, 120, 110]),
+				(Tile::Spawn, [200, 40, 40]),
+				(Tile::TowerBase, [220, 200, 80]),
+				(Tile::Water, [40, 90, 140]),
+			]
+			.into_iter()
+			.collect(),
+		)
+	}
+}
+
+/// # Summary
+///
+/// Where the tiles of a [`Screenshot`] are.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Lattice
+{
+	/// # Summary
+	///
+	/// The `(x, y)` pixel of the first edge between tiles.
+	pub offset: (usize, usize),
+
+	/// # Summary
+	///
+	/// The `(width, height)` of each tile, in pixels.
+	pub tile_size: (usize, usize),
+}
+
+/// # Summary
+///
+/// A top-down picture of a map, which can be turned into a grid of [`Tile`]s.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Screenshot
+{
+	height: usize,
+	pixels: Vec<Rgb>,
+	width: usize,
+}
+
+impl Screenshot
+{
+	/// # Summary
+	///
+	/// The [`Lattice`] of the tiles, found from the edges between them.
+	///
+	/// # Parameters
+	///
+	/// * `tile_sizes`, the smallest and largest tile size (in pixels) to look for.
+	///
+	/// # Returns
+	///
+	/// [`None`] if no repeating edges could be found.
+	///
+	/// # Remarks
+	///
+	/// Each column (and row) of pixels gets a score of how different it is from the one before it,
+	/// and the strongest are taken to be edges. Multiples of the real tile size fit those edges
+	/// almost as well, so the smallest size which is close to the best is chosen.
+	pub fn lattice(&self, tile_sizes: (usize, usize)) -> Option<Lattice>
+	{
+		let luma = |x: usize, y: usize| -> i32 {
+			let [r, g, b] = self.pixels[y * self.width + x];
+			(299 * i32::from(r) + 587 * i32::from(g) + 114 * i32::from(b)) / 1000
+		};
+
+		let columns: Vec<u64> = (0..self.width)
+			.map(|x| match x
+			{
+				0 => 0,
+				_ => (0..self.height).map(|y| luma(x, y).abs_diff(luma(x - 1, y)) as u64).sum(),
+			})
+			.collect();
+		let rows: Vec<u64> = (0..self.height)
+			.map(|y| match y
+			{
+				0 => 0,
+				_ => (0..self.width).map(|x| luma(x, y).abs_diff(luma(x, y - 1)) as u64).sum(),
+			})
+			.collect();
+
+		let (x_offset, width) = Self::period(&columns, tile_sizes)?;
+		let (y_offset, height) = Self::period(&rows, tile_sizes)?;
+
+		Some(Lattice { offset: (x_offset, y_offset), tile_size: (width, height) })
+	}
+
+	/// # Summary
+	///
+	/// Create a [`Screenshot`] out of some `pixels`, row by row.
+	///
+	/// # Returns
+	///
+	/// [`None`] if there are not `width * height` pixels.
+	pub fn new(width: usize, height: usize, pixels: Vec<Rgb>) -> Option<Self>
+	{
+		(pixels.len() == width * height).then_some(Self { height, pixels, width })
+	}
+
+	/// # Summary
+	///
+	/// The `(offset, period)` at which the edges of some `energy` repeat most strongly, looking at
+	/// periods within `bounds`.
+	///
+	/// # Remarks
+	///
+	/// A line is an edge if its energy is at least a quarter of the strongest. Each lattice is
+	/// scored by how many of its lines are edges, and how many edges are on its lines.
+	fn period(energy: &[u64], bounds: (usize, usize)) -> Option<(usize, usize)>
+	{
+		let threshold = energy.iter().copied().max().filter(|max| *max > 0)?.div_ceil(4);
+		let edges: Vec<_> = energy.iter().map(|e| *e >= threshold).collect();
+		let edge_count = edges.iter().filter(|e| **e).count() as f64;
+
+		let (min, max) = (bounds.0.max(2), bounds.1.min(energy.len() / 2));
+		let scores: Vec<_> = (min..=max)
+			.map(|period| {
+				(0..period)
+					.map(|offset| {
+						let lines = (offset..edges.len()).step_by(period).count() as f64;
+						let hits = edges.iter().skip(offset).step_by(period).filter(|e| **e).count()
+							as f64;
+						(hits * hits / (lines * edge_count), offset, period)
+					})
+					.fold((0.0, 0, period), |best, score| {
+						if score.0 > best.0
+						{
+							score
+						}
+						else
+						{
+							best
+						}
+					})
+			})
+			.collect();
+
+		let best = scores.iter().map(|(score, ..)| *score).fold(0.0, f64::max);
+		scores
+			.into_iter()
+			.find(|(score, ..)| *score > 0.0 && *score >= best * 0.9)
+			.map(|(_, offset, period)| (offset, period))
+	}
+
+	/// # Summary
+	///
+	/// The [`Tile`] of every whole cell of a `lattice`, picked by the [`Palette`] from the average
+	/// color of the middle of each cell.
+	pub fn tiles(&self, lattice: &Lattice, palette: &Palette) -> Vec<Vec<Tile>>
+	{
+		let (tile_width, tile_height) = lattice.tile_size;
+		// A cell is only read if the middle half of it is in the picture.
+		let count = |length: usize, offset: usize, size: usize| {
+			length
+				.checked_sub(offset + size / 4 + (size / 2).max(1))
+				.map_or(0, |l| l / size.max(1) + 1)
+		};
+		let (columns, rows) = (
+			count(self.width, lattice.offset.0, tile_width),
+			count(self.height, lattice.offset.1, tile_height),
+		);
+
+		(0..rows)
+			.map(|row| {
+				(0..columns)
+					.map(|column| {
+						// Only look at the middle half, so that borders and shadows are left out.
+						let (left, top) = (
+							lattice.offset.0 + column * tile_width + tile_width / 4,
+							lattice.offset.1 + row * tile_height + tile_height / 4,
+						);
+						let (width, height) = ((tile_width / 2).max(1), (tile_height / 2).max(1));
+
+						let mut sum = [0_u64; 3];
+						(top..top + height).for_each(|y| {
+							(left..left + width).for_each(|x| {
+								let pixel = self.pixels[y * self.width + x];
+								sum.iter_mut().zip(pixel).for_each(|(s, c)| *s += u64::from(c));
+							})
+						});
+
+						let area = (width * height) as u64;
+						palette.classify(sum.map(|s| (s / area) as u8)).unwrap_or(Tile::Impass)
+					})
+					.collect()
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Palette, Screenshot};
+	use crate::map::Tile::*;
+
+	#[test]
+	fn tiles()
+	{
+		let palette = Palette::default();
+		let grid = vec![vec![Spawn, Empty, Empty, Core], vec![Impass, Empty, Water, Impass]];
+
+		// Draw each tile as 10x10 pixels with a 1 pixel black border, after a 3 pixel margin.
+		let (width, height) = (3 + 4 * 10, 3 + 2 * 10);
+		let pixels = (0..height)
+			.flat_map(|y| (0..width).map(move |x| (x, y)))
+			.map(|(x, y): (usize, usize)| {
+				let (x, y) = (x.wrapping_sub(3), y.wrapping_sub(3));
+				match grid.get(y / 10).and_then(|row| row.get(x / 10))
+				{
+					Some(_) if x % 10 == 0 || y % 10 == 0 => [0, 0, 0],
+					Some(tile) => palette.0[tile],
+					None => [0, 0, 0],
+				}
+			})
+			.collect();
+		let screenshot = Screenshot::new(width, height, pixels).unwrap();
+
+		let start = Instant::now();
+		let lattice = screenshot.lattice((4, 32)).unwrap();
+		println!("Screenshot::lattice {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(lattice.tile_size, (10, 10));
+		assert_eq!(screenshot.tiles(&lattice, &palette), grid);
+	}
+}
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, Coordinate, MovementRules};
+#[cfg_attr(not(feature = "parallel"), allow(unused_imports))]
+use crate::parallel::{IntoParallelRefIterator, ParallelIterator};
+
+/// # Summary
+///
+/// The effect which removing a single block has on a [`Build`].
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BlockImpact
+{
+	/// # Summary
+	///
+	/// The block which was removed.
+	pub block: Coordinate,
+
+	/// # Summary
+	///
+	/// Whether losing the block costs some region at least the critical fraction of its path.
+	pub critical: bool,
+
+	/// # Summary
+	///
+	/// The greatest number of tiles which any region's path lost.
+	pub loss: usize,
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path once the block is removed.
+	pub shortest_path_length: Vec<Option<usize>>,
+}
+
+/// # Summary
+///
+/// How a [`Build`] holds up when each of its blocks is removed in turn (e.g. by block-destroying
+/// enemies or a misclick).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Sensitivity
+{
+	/// # Summary
+	///
+	/// Every block's [`BlockImpact`], from most to least damaging.
+	pub impacts: Vec<BlockImpact>,
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path with the whole [`Build`] in place.
+	pub shortest_path_length: Vec<Option<usize>>,
+}
+
+impl Sensitivity
+{
+	/// # Summary
+	///
+	/// The number of tiles, summed across all regions, which the paths are expected to lose when
+	/// a single block chosen at random is removed.
+	///
+	/// # Remarks
+	///
+	/// A [`Build`] with no blocks cannot lose any, so its fragility is `0.0`.
+	pub fn fragility(&self) -> f64
+	{
+		if self.impacts.is_empty()
+		{
+			return 0.0;
+		}
+
+		let total_loss: usize = self
+			.impacts
+			.iter()
+			.map(|impact| {
+				self.shortest_path_length
+					.iter()
+					.zip(impact.shortest_path_length.iter())
+					.map(|(before, after)| match (before, after)
+					{
+						(Some(b), Some(a)) => b.saturating_sub(*a),
+						_ => 0,
+					})
+					.sum::<usize>()
+			})
+			.sum();
+
+		total_loss as f64 / self.impacts.len() as f64
+	}
+
+	/// # Summary
+	///
+	/// Remove each block of the `build` from a `tileset` in turn and measure its impact.
+	///
+	/// # Parameters
+	///
+	/// * `critical_loss`, the fraction (`0.0..=1.0`) of a region's path which must be lost for a
+	///   block to be considered critical.
+	pub fn of(
+		tileset: &Tileset,
+		build: &Build,
+		movement: impl Into<MovementRules>,
+		critical_loss: f64,
+	) -> Self
+	{
+		let movement = movement.into();
+		let shortest_path_length = build.shortest_path_lengths(tileset, movement);
+
+		let mut impacts: Vec<_> = build
+			.blocks
+			.par_iter()
+			.map(|block| {
+				let mut without_block = build.clone();
+				without_block.blocks.remove(block);
+
+				let lengths = without_block.shortest_path_lengths(tileset, movement);
+				let (loss, critical) = shortest_path_length.iter().zip(lengths.iter()).fold(
+					(0, false),
+					|(loss, critical), (before, after)| match (before, after)
+					{
+						(Some(b), Some(a)) =>
+						{
+							let region_loss = b.saturating_sub(*a);
+							(
+								loss.max(region_loss),
+								critical || region_loss as f64 >= *b as f64 * critical_loss,
+							)
+						},
+						_ => (loss, critical),
+					},
+				);
+
+				BlockImpact { block: *block, critical, loss, shortest_path_length: lengths }
+			})
+			.collect();
+
+		impacts.sort_by(|a, b| b.loss.cmp(&a.loss).then_with(|| a.block.cmp(&b.block)));
+
+		Self { impacts, shortest_path_length }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, Sensitivity, Tileset};
+	use crate::map::tileset::tests::PARK_TWO_SPAWN;
+
+	#[test]
+	fn of()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(10), |_| ()).unwrap();
+
+		let start = Instant::now();
+		let sensitivity = Sensitivity::of(&test_tileset, &build, false, 0.25);
+		println!("Sensitivity::of {}us", Instant::now().duration_since(start).as_micros());
+
+		// Every block should be measured exactly once.
+		assert_eq!(sensitivity.impacts.len(), build.blocks.len());
+
+		// Removing a block can only ever shorten a path.
+		sensitivity.impacts.iter().for_each(|impact| {
+			impact
+				.shortest_path_length
+				.iter()
+				.zip(sensitivity.shortest_path_length.iter())
+				.for_each(|(after, before)| assert!(after <= before));
+		});
+
+		// The most damaging blocks come first.
+		assert!(sensitivity.impacts.windows(2).all(|pair| pair[0].loss >= pair[1].loss));
+
+		// On average, a block costs at least the average of its worst region's loss.
+		let mean_loss = sensitivity.impacts.iter().map(|impact| impact.loss).sum::<usize>() as f64 /
+			sensitivity.impacts.len() as f64;
+		assert!(sensitivity.fragility() >= mean_loss);
+	}
+}
@@ -1,20 +1,25 @@
 #![allow(clippy::len_without_is_empty)]
 
-use std::{
-	cmp::Ordering,
-	collections::{HashMap, LinkedList},
-};
+mod search_context;
+
+use std::{cmp::Ordering, slice};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use search_context::SearchContext;
 use serde::{Deserialize, Serialize};
 
 use super::{
 	tileset::{Tileset, COORDINATE_ON_TILESET},
 	Adjacent,
 	Coordinate,
+	MovementRules,
 	Tile,
 };
-use crate::Container;
+use crate::{
+	parallel::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
+	Container,
+};
+
+const PATH_HAS_COORDINATE: &str = "Expected this `ShortestPath` to have at least 1 coordinate";
 
 /// # Summary
 ///
@@ -28,12 +33,20 @@ pub struct ShortestPath
 
 impl ShortestPath
 {
+	/// # Summary
+	///
+	/// Every [`Coordinate`] along this [`ShortestPath`], from the entrance to the core.
+	pub fn coordinates(&self) -> &[Coordinate]
+	{
+		&self.path
+	}
+
 	/// # Summary
 	///
 	/// Return the [`Tile::Core`] which this [`ShortestPath`] navigates to.
 	pub fn core(&self) -> Coordinate
 	{
-		*self.path.last().expect("Expected this `ShortestPath` to have at least 1 coordinate")
+		*self.path.last().expect(PATH_HAS_COORDINATE)
 	}
 
 	/// # Summary
@@ -50,48 +63,75 @@ impl ShortestPath
 		build: Option<&impl Container<Coordinate>>,
 		start_points: impl ParallelIterator<Item = (&'coord Coordinate, &'distance usize)>,
 		end_tile: Tile,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 	) -> Option<Self>
 	{
+		let movement = movement.into();
 		start_points
-			.map(|(coord, start_distance)| {
+			.filter_map(|(coord, start_distance)| {
 				ShortestPath::from_grid_coordinate_to_tile(
-					&grid,
+					grid,
 					build,
 					*coord,
 					Some(*start_distance),
 					end_tile,
-					diagonals,
+					movement,
 				)
 			})
-			.flatten()
 			.reduce_with(ShortestPath::return_shorter)
 	}
 
 	/// # Summary
 	///
-	/// Get the [`ShortestPath`]s from all [`Tileset::entrances`] to any [`Tileset::exits`].
+	/// Get the [`ShortestPath`]s from all [`Tileset::entrances`] to any [`Tile::Core`] which
+	/// they [target](Tileset::targets). See [`ShortestPath::from_region_to_target`].
 	pub fn from_entrances_to_any_core(
 		tileset: &Tileset,
 		build: Option<&impl Container<Coordinate>>,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 	) -> Vec<Option<Self>>
 	{
-		tileset
-			.entrances_by_region
-			.par_iter()
-			.map(|entrances| {
-				ShortestPath::from_any_grid_coordinate_to_tile(
-					&tileset.grid,
-					build,
-					entrances.par_iter(),
-					Tile::Core,
-					diagonals,
-				)
-			})
+		let movement = movement.into();
+		(0..tileset.entrances_by_region.len())
+			.into_par_iter()
+			.map(|region| ShortestPath::from_region_to_target(tileset, build, region, movement))
 			.collect()
 	}
 
+	/// # Summary
+	///
+	/// Get the shortest [`ShortestPath`] from some `start`ing [`Coordinate`] of a spawn `region` to
+	/// a [`Tile::Core`] which the region [targets](Tileset::targets).
+	pub fn from_grid_coordinate_to_target(
+		tileset: &Tileset,
+		build: Option<&impl Container<Coordinate>>,
+		region: usize,
+		start: Coordinate,
+		start_distance: Option<usize>,
+		movement: impl Into<MovementRules>,
+	) -> Option<Self>
+	{
+		match tileset.targets(region)
+		{
+			Some(targets) => ShortestPath::search(
+				&tileset.grid,
+				build,
+				start,
+				start_distance,
+				|coord, _| targets.contains(coord),
+				movement,
+			),
+			None => ShortestPath::from_grid_coordinate_to_tile(
+				&tileset.grid,
+				build,
+				start,
+				start_distance,
+				Tile::Core,
+				movement,
+			),
+		}
+	}
+
 	/// # Summary
 	///
 	/// Get the shortest [`ShortestPath`] to a [`Tile`] of `end_tile`'s type from some `start`ing
@@ -102,62 +142,53 @@ impl ShortestPath
 		start: Coordinate,
 		start_distance: Option<usize>,
 		end_point: Tile,
-		diagonals: bool,
+		movement: impl Into<MovementRules>,
 	) -> Option<Self>
 	{
-		let start_tile = start.get_from_with_build(&grid, build).expect(COORDINATE_ON_TILESET);
-
-		// We don't want to start the search on a tile which cannot be walked over.
-		// This is to prevent accidentally crossing over the other side of a barrier.
-		if !start_tile.is_passable()
-		{
-			return None;
-		}
-
-		let mut coordinate_path_queue = LinkedList::new();
-		let mut visited = HashMap::new();
-
-		coordinate_path_queue.push_back((start, vec![start]));
-
-		while let Some((coord, current_path)) = coordinate_path_queue.pop_front()
-		{
-			// If the current path is longer than the previous path (defaulting to `false` if there
-			// is no previous path).
-			if match visited.get(&coord)
-			{
-				Some(visited_path_len) => &current_path.len() >= visited_path_len,
-				_ => false,
-			}
-			{
-				continue;
-			}
-
-			let tile: Tile = coord.get_from_with_build(&grid, build).expect(COORDINATE_ON_TILESET);
-
-			// Using BFS, so if the `tile` is the `end_tile` we've found the shortest path.
-			if tile == end_point
-			{
-				return Some(ShortestPath { path: current_path, start_distance });
-			}
-			// Only keep looking beyond a passable tile, and if the current tile is not what we're
-			// searching for.
-			else if tile.is_passable()
-			{
-				Adjacent::from_grid_coordinate_with_build(&grid, build, &coord, diagonals)
-					.for_each(|adjacent_coord| {
-						let mut new_path = Vec::with_capacity(current_path.len() + 1);
-						new_path.extend_from_slice(&current_path);
-						new_path.push(adjacent_coord);
-
-						coordinate_path_queue.push_back((adjacent_coord, new_path))
-					});
-			}
+		ShortestPath::search(
+			grid,
+			build,
+			start,
+			start_distance,
+			|_, tile| tile == end_point,
+			movement,
+		)
+	}
 
-			// Now that the current coordinate has been fully evaluated, mark it as visited.
-			visited.insert(coord, current_path.len());
-		}
+	/// # Summary
+	///
+	/// Get the shortest [`ShortestPath`] from any entrance of a spawn `region` to a [`Tile::Core`]
+	/// which the region [targets](Tileset::targets).
+	pub fn from_region_to_target(
+		tileset: &Tileset,
+		build: Option<&impl Container<Coordinate>>,
+		region: usize,
+		movement: impl Into<MovementRules>,
+	) -> Option<Self>
+	{
+		let movement = movement.into();
+		tileset.entrances_by_region[region]
+			.par_iter()
+			.filter_map(|(coord, start_distance)| {
+				ShortestPath::from_grid_coordinate_to_target(
+					tileset,
+					build,
+					region,
+					*coord,
+					Some(*start_distance),
+					movement,
+				)
+			})
+			.reduce_with(ShortestPath::return_shorter)
+	}
 
-		None
+	/// # Summary
+	///
+	/// Iterate over the [`Coordinate`]s along this [`ShortestPath`] without taking ownership of
+	/// them. See [`ShortestPath::coordinates`].
+	pub fn iter(&self) -> slice::Iter<'_, Coordinate>
+	{
+		self.path.iter()
 	}
 
 	/// # Summary
@@ -174,15 +205,88 @@ impl ShortestPath
 	///
 	/// # Remarks
 	///
-	/// If paths are equally long, the current path is preferred.
+	/// If paths are equally long, the one which starts first in reading order is preferred, so
+	/// that the same path is chosen no matter which order the entrances were searched in.
 	fn return_shorter(self, other: Self) -> Self
 	{
-		if self.len() > other.len()
+		let key = |path: &Self| (path.len(), path.path[0].1, path.path[0].0);
+		if key(&self) > key(&other)
 		{
 			return other;
 		}
 		self
 	}
+
+	/// # Summary
+	///
+	/// Search breadth-first from some `start`ing [`Coordinate`] until reaching one which `is_end`.
+	///
+	/// # Remarks
+	///
+	/// The search uses this thread's [`SearchContext`], rather than allocating its own buffers.
+	fn search(
+		grid: &[impl AsRef<[Tile]>],
+		build: Option<&impl Container<Coordinate>>,
+		start: Coordinate,
+		start_distance: Option<usize>,
+		is_end: impl Fn(&Coordinate, Tile) -> bool,
+		movement: impl Into<MovementRules>,
+	) -> Option<Self>
+	{
+		let movement = movement.into();
+		let start_tile = start.get_from_with_build(grid, build)?;
+
+		// We don't want to start the search on a tile which cannot be walked over.
+		// This is to prevent accidentally crossing over the other side of a barrier.
+		if !start_tile.is_passable()
+		{
+			return None;
+		}
+
+		let width = grid.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+		SearchContext::with(width, grid.len(), |context| {
+			// The context remembers where each visited coordinate was first reached from, so that
+			// only the path which is found has to be built.
+			context.visit(start, None);
+			context.queue.push_back(start);
+
+			while let Some(coord) = context.queue.pop_front()
+			{
+				let tile: Tile =
+					coord.get_from_with_build(grid, build).expect(COORDINATE_ON_TILESET);
+
+				// Using BFS, so if `coord` is the end we've found the shortest path.
+				if is_end(&coord, tile)
+				{
+					let mut path = vec![coord];
+					while let Some(prev) = context.previous(path.last().expect(PATH_HAS_COORDINATE))
+					{
+						path.push(prev);
+					}
+
+					path.reverse();
+					return Some(ShortestPath { path, start_distance });
+				}
+				// Only keep looking beyond a passable tile, and if the current tile is not what
+				// we're searching for.
+				else if tile.is_passable()
+				{
+					Adjacent::from_grid_coordinate_with_build(grid, build, &coord, movement)
+						.into_iter()
+						.for_each(|(_, adjacent_coord)| {
+							// The first time a coordinate is reached is always along a shortest
+							// path.
+							if context.visit(adjacent_coord, Some(coord))
+							{
+								context.queue.push_back(adjacent_coord);
+							}
+						});
+				}
+			}
+
+			None
+		})
+	}
 }
 
 impl From<ShortestPath> for Vec<Coordinate>
@@ -193,6 +297,17 @@ impl From<ShortestPath> for Vec<Coordinate>
 	}
 }
 
+impl<'path> IntoIterator for &'path ShortestPath
+{
+	type IntoIter = slice::Iter<'path, Coordinate>;
+	type Item = &'path Coordinate;
+
+	fn into_iter(self) -> Self::IntoIter
+	{
+		self.iter()
+	}
+}
+
 impl Ord for ShortestPath
 {
 	fn cmp(&self, other: &Self) -> Ordering
@@ -205,7 +320,7 @@ impl PartialOrd for ShortestPath
 {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering>
 	{
-		self.len().partial_cmp(&other.len())
+		Some(self.cmp(other))
 	}
 }
 
@@ -214,21 +329,24 @@ mod tests
 {
 	use std::{collections::HashSet, time::Instant};
 
-	use rayon::iter::IntoParallelRefIterator;
-
 	use super::{Coordinate, ShortestPath, Tile, Tileset, COORDINATE_ON_TILESET};
-	use crate::map::tileset::tests::{PARK, PARK_TWO_SPAWN};
+	use crate::{
+		map::tileset::tests::{PARK, PARK_TWO_SPAWN},
+		parallel::IntoParallelRefIterator,
+	};
 
 	fn assertion(tileset: &Tileset, paths: &[ShortestPath], index: usize, desired_len: usize)
 	{
 		// Since there may be multiple ways to do this we aren't going to test it
 		// directly, rather we're going to assert things about the path instead.
 		assert_eq!(paths[index].len(), desired_len);
-		assert!(paths[index].path[0..(desired_len - 1)].iter().all(|coord| coord
+
+		let last = paths[index].path.len() - 1;
+		assert!(paths[index].path[0..last].iter().all(|coord| coord
 			.get_from(&tileset.grid)
 			.expect(COORDINATE_ON_TILESET)
 			.is_passable()));
-		assert!(paths[index].path[desired_len - 1]
+		assert!(paths[index].path[last]
 			.get_from(&tileset.grid)
 			.expect(COORDINATE_ON_TILESET)
 			.is_region());
@@ -237,15 +355,14 @@ mod tests
 	#[test]
 	fn from_any_grid_coordinate_to_tile()
 	{
-		let test_tileset = Tileset::new(
-			PARK_TWO_SPAWN.iter().map(|inner| inner.iter().copied().collect()).collect(),
-		);
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
 
 		let start = Instant::now();
 		let test_paths: Vec<_> = test_tileset
 			.entrances_by_region
 			.iter()
-			.map(|entrances| {
+			.filter_map(|entrances| {
 				ShortestPath::from_any_grid_coordinate_to_tile(
 					&test_tileset.grid,
 					Option::<&HashSet<_>>::None,
@@ -254,7 +371,6 @@ mod tests
 					true,
 				)
 			})
-			.flatten()
 			.collect();
 		println!(
 			"ShortestPath::from_any_grid_coordinate_to_tile {}us",
@@ -282,18 +398,17 @@ mod tests
 		// There should be two paths to the core since there are two spawn points.
 		assert_eq!(test_paths.len(), 2);
 
-		// The shortest path from the left-hand Spawn should be of length nine.
-		assertion(&test_tileset, &test_paths, 0, 8);
+		// The shortest path from the left-hand Spawn should be of length 14.
+		assertion(&test_tileset, &test_paths, 0, 14);
 
-		// The shortest path from the right-hand Spawn should be of length 15.
-		assertion(&test_tileset, &test_paths, 1, 9);
+		// The shortest path from the right-hand Spawn should be of length 10.
+		assertion(&test_tileset, &test_paths, 1, 10);
 	}
 
 	#[test]
 	fn from_grid_coordinate_to_tile()
 	{
-		let test_tileset =
-			Tileset::new(PARK.iter().map(|inner| inner.iter().copied().collect()).collect());
+		let test_tileset = Tileset::new(PARK.iter().map(|inner| inner.to_vec()).collect());
 
 		let entrance = test_tileset
 			.entrances_by_region
@@ -317,6 +432,6 @@ mod tests
 			Instant::now().duration_since(start).as_micros()
 		);
 
-		assertion(&test_tileset, &[test_path], 0, 8);
+		assertion(&test_tileset, &[test_path], 0, 14);
 	}
 }
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, Coordinate, ShortestPath};
+
+/// # Summary
+///
+/// An enemy walking towards a core.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Enemy
+{
+	/// # Summary
+	///
+	/// The order in which this enemy left its spawn region, starting from `0`.
+	pub id: usize,
+
+	/// # Summary
+	///
+	/// The index of the spawn region which this enemy came from.
+	pub region: usize,
+
+	/// # Summary
+	///
+	/// Where this enemy is standing.
+	pub coordinate: Coordinate,
+}
+
+/// # Summary
+///
+/// The position of every [`Enemy`] on the map at one point in time.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Frame
+{
+	pub tick: usize,
+	pub enemies: Vec<Enemy>,
+}
+
+/// # Summary
+///
+/// A wave of enemies which walks the [`ShortestPath`] of each spawn region, one tile per tick.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Simulation
+{
+	enemies_per_region: usize,
+	paths: Vec<Option<Vec<Coordinate>>>,
+	spawn_interval: usize,
+}
+
+impl Simulation
+{
+	/// # Summary
+	///
+	/// Get the [`Frame`] for some `tick` of the [`Simulation`].
+	///
+	/// # Returns
+	///
+	/// * `None`, if every [`Enemy`] has reached a core before `tick`.
+	/// * `Some(Frame)`, otherwise.
+	pub fn frame(&self, tick: usize) -> Option<Frame>
+	{
+		let mut in_progress = false;
+		let mut enemies = Vec::new();
+
+		self.paths.iter().enumerate().for_each(|(region, path)| {
+			if let Some(p) = path
+			{
+				(0..self.enemies_per_region).for_each(|id| {
+					let spawn_tick = id * self.spawn_interval;
+					if tick < spawn_tick
+					{
+						in_progress = true;
+					}
+					else if let Some(coordinate) = p.get(tick - spawn_tick)
+					{
+						in_progress = true;
+						enemies.push(Enemy { id, region, coordinate: *coordinate });
+					}
+				})
+			}
+		});
+
+		if in_progress
+		{
+			return Some(Frame { tick, enemies });
+		}
+
+		None
+	}
+
+	/// # Summary
+	///
+	/// Every [`Frame`] of the [`Simulation`], in order, until all enemies have reached a core.
+	pub fn frames(&self) -> impl Iterator<Item = Frame> + '_
+	{
+		(0..).map_while(move |tick| self.frame(tick))
+	}
+
+	/// # Summary
+	///
+	/// Create a new [`Simulation`] of a wave walking through a `build` on the `tileset`.
+	///
+	/// # Parameters
+	///
+	/// * `enemies_per_region`, how many enemies come out of each spawn region.
+	/// * `spawn_interval`, how many ticks pass between each enemy leaving its spawn region.
+	pub fn new(
+		tileset: &Tileset,
+		build: &Build,
+		diagonals: bool,
+		enemies_per_region: usize,
+		spawn_interval: usize,
+	) -> Self
+	{
+		Self {
+			enemies_per_region,
+			paths: ShortestPath::from_entrances_to_any_core(
+				tileset,
+				Some(&build.blocks),
+				diagonals,
+			)
+			.into_iter()
+			.map(|path| path.map(Vec::from))
+			.collect(),
+			spawn_interval,
+		}
+	}
+
+	/// # Summary
+	///
+	/// The path which the enemies of each spawn region walk, or [`None`] if it cannot reach a
+	/// core.
+	pub fn paths(&self) -> &[Option<Vec<Coordinate>>]
+	{
+		&self.paths
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, Simulation, Tileset};
+	use crate::map::tileset::tests::PARK_TWO_SPAWN;
+
+	#[test]
+	fn frames()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+		let build =
+			Build::from_entrances_to_any_core(&test_tileset, false, Some(10), |_| ()).unwrap();
+		let simulation = Simulation::new(&test_tileset, &build, false, 3, 2);
+
+		let start = Instant::now();
+		let frames: Vec<_> = simulation.frames().collect();
+		println!("Simulation::frames {}us", Instant::now().duration_since(start).as_micros());
+
+		// Ticks should be contiguous from zero.
+		assert!(frames.iter().enumerate().all(|(tick, frame)| frame.tick == tick));
+
+		// The first enemy of each region leaves immediately.
+		assert_eq!(frames[0].enemies.len(), 2);
+
+		// The last enemy of the longest path is the last one on the map.
+		let longest = simulation.paths.iter().flatten().map(|path| path.len()).max().unwrap();
+		assert_eq!(frames.len(), longest + 2 * 2);
+		assert!(simulation.frame(frames.len()).is_none());
+	}
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	tileset::Tileset,
+	Build,
+	Coordinate,
+	EnemyClass,
+	Metric,
+	MovementRules,
+	Objective,
+	Sensitivity,
+	ShortestPath,
+	Strategy,
+	Tile,
+};
+use crate::SolveOptions;
+
+/// # Summary
+///
+/// Figures describing a solved [`Map`](super::Map).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Stats
+{
+	/// # Summary
+	///
+	/// How many blocks the build uses.
+	pub blocks: usize,
+
+	/// # Summary
+	///
+	/// The [`Build::to_code`](super::Build::to_code) of the build.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub build_code: Option<String>,
+
+	/// # Summary
+	///
+	/// How many blocks were removed by [`Build::minimize`](super::Build::minimize) without
+	/// shortening any path.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub blocks_saved: Option<usize>,
+
+	/// # Summary
+	///
+	/// How many [`Tileset::buildable_tiles`](super::tileset::Tileset::buildable_tiles) the map
+	/// has.
+	#[serde(default)]
+	pub buildable_tiles: usize,
+
+	/// # Summary
+	///
+	/// The core tile which each region's shortest path ends at, when some regions only walk to
+	/// certain cores (see [`Map::targeting`](super::Map) and
+	/// [`Map::core_assignments`](super::Map)).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub core_by_region: Vec<Option<Coordinate>>,
+
+	/// # Summary
+	///
+	/// The [`Sensitivity::fragility`](super::Sensitivity::fragility) of the build.
+	pub fragility: f64,
+
+	/// # Summary
+	///
+	/// The [`SolveOptions`] which the build was found with, so that it can be found again.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub options: Option<SolveOptions>,
+
+	/// # Summary
+	///
+	/// The name of each region, when some are named (see
+	/// [`Tileset::region_names`](super::tileset::Tileset::region_names)).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub region_names: Vec<Option<String>>,
+
+	/// # Summary
+	///
+	/// The [`Objective::scores`](super::Objective::scores) of each region, when they differ from
+	/// the path lengths (e.g. because the map has [`Tile::Hazard`](super::Tile::Hazard)s).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub score: Vec<Option<usize>>,
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path for every [`EnemyClass`], when they differ
+	/// (e.g. because the map has [`Tile::Water`](super::Tile::Water)).
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub shortest_path_length_by_class: BTreeMap<EnemyClass, Vec<Option<usize>>>,
+
+	/// # Summary
+	///
+	/// How each [`Strategy`] of a [`Strategy::Portfolio`] fared.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub strategies: Vec<StrategyStats>,
+}
+
+impl Stats
+{
+	/// # Summary
+	///
+	/// The [`Stats`] of a `build` on a `tileset` which do not depend on how it was found.
+	///
+	/// # Parameters
+	///
+	/// * `movement` and `metric`, which the `tileset` is solved with.
+	pub fn of(
+		tileset: &Tileset,
+		build: &Build,
+		objective: &Objective,
+		movement: MovementRules,
+		metric: Metric,
+	) -> Self
+	{
+		let has = |tile: Tile| tileset.grid.iter().flatten().any(|t| *t == tile);
+
+		Self {
+			blocks: build.blocks.len(),
+			buildable_tiles: tileset.buildable_tiles().len(),
+			core_by_region: if (0..tileset.entrances_by_region.len())
+				.any(|region| tileset.targets(region).is_some())
+			{
+				ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), movement)
+					.into_iter()
+					.map(|path| path.map(|p| p.core()))
+					.collect()
+			}
+			else
+			{
+				Vec::new()
+			},
+			fragility: Sensitivity::of(tileset, build, movement, 1.0).fragility(),
+			region_names: {
+				let names = tileset.region_names();
+				if names.iter().any(Option::is_some)
+				{
+					names
+				}
+				else
+				{
+					Vec::new()
+				}
+			},
+			score: if has(Tile::Hazard) || objective.fairness.is_some()
+			{
+				objective.scores(tileset, build, movement)
+			}
+			else
+			{
+				Vec::new()
+			},
+			shortest_path_length_by_class: if has(Tile::Water)
+			{
+				EnemyClass::ALL
+					.iter()
+					.map(|class| {
+						let mut class_tileset = Tileset::with_metric(
+							class.view(&tileset.grid),
+							metric,
+							tileset.diagonals,
+						);
+						class_tileset.metadata = tileset.metadata.clone();
+						class_tileset.set_region_rule(tileset.region_rule);
+						(*class, build.shortest_path_lengths(&class_tileset, movement))
+					})
+					.collect()
+			}
+			else
+			{
+				BTreeMap::new()
+			},
+			..Default::default()
+		}
+	}
+}
+
+/// # Summary
+///
+/// The result of running a single [`Strategy`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StrategyStats
+{
+	pub strategy: Strategy,
+
+	/// # Summary
+	///
+	/// Whether this strategy's build was the one which was kept.
+	#[serde(default)]
+	pub best: bool,
+
+	pub blocks: usize,
+	pub milliseconds: u64,
+
+	/// # Summary
+	///
+	/// Whether the strategy was stopped by its [`TimeSlice`](crate::solve::TimeSlice) before it
+	/// finished.
+	#[serde(default)]
+	pub out_of_time: bool,
+
+	pub shortest_path_length: Vec<Option<usize>>,
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use super::{tileset::Tileset, Build, Coordinate, ShortestPath, Tile};
+
+/// # Summary
+///
+/// A tower which can hit enemies on any tile within its `range`.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Tower
+{
+	pub coordinate: Coordinate,
+
+	/// # Summary
+	///
+	/// How many tiles away, in a straight line, the tower can hit.
+	pub range: usize,
+}
+
+impl Tower
+{
+	/// # Summary
+	///
+	/// The [`Metadata`](super::Metadata) key which sets the range of a tower on some tile.
+	pub const RANGE: &'static str = "range";
+
+	/// # Summary
+	///
+	/// Whether this tower can hit an enemy standing on `coord`.
+	pub fn covers(&self, coord: &Coordinate) -> bool
+	{
+		let dx = self.coordinate.0.abs_diff(coord.0);
+		let dy = self.coordinate.1.abs_diff(coord.1);
+		dx * dx + dy * dy <= self.range * self.range
+	}
+
+	/// # Summary
+	///
+	/// A tower on every [`Tile::TowerBase`] of a `tileset`, in reading order. Each has the
+	/// [`Tower::RANGE`] set in the metadata of its tile, or else the `default_range`.
+	pub fn on_tower_bases(tileset: &Tileset, default_range: usize) -> Vec<Self>
+	{
+		tileset
+			.grid
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| {
+				row.iter()
+					.enumerate()
+					.filter(|(_, tile)| **tile == Tile::TowerBase)
+					.map(move |(x, _)| Coordinate(x, y))
+			})
+			.map(|coordinate| Self {
+				coordinate,
+				range: tileset
+					.metadata
+					.get_value(&coordinate, Self::RANGE)
+					.and_then(|value| value.as_u64())
+					.map_or(default_range, |range| range as usize),
+			})
+			.collect()
+	}
+}
+
+/// # Summary
+///
+/// How much opportunity some [`Tower`]s have to damage the enemies walking a [`Build`], which
+/// tells apart builds whose paths are equally long.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ThreatMap
+{
+	/// # Summary
+	///
+	/// For each region, how many towers cover each tile of its shortest path, summed over the
+	/// path; or [`None`] if it cannot reach a core.
+	pub exposure: Vec<Option<usize>>,
+
+	/// # Summary
+	///
+	/// For each tile, how many towers cover it, times how many regions' shortest paths cross it.
+	pub opportunity: Vec<Vec<usize>>,
+
+	/// # Summary
+	///
+	/// The `exposure` of every region, added together.
+	pub total: usize,
+}
+
+impl ThreatMap
+{
+	/// # Summary
+	///
+	/// Find the [`ThreatMap`] of some `towers` over a `build` on a `tileset`.
+	pub fn of(tileset: &Tileset, build: &Build, towers: &[Tower], diagonals: bool) -> Self
+	{
+		let covering = |coord: &Coordinate| towers.iter().filter(|t| t.covers(coord)).count();
+
+		let mut opportunity: Vec<Vec<_>> =
+			tileset.grid.iter().map(|row| vec![0; row.len()]).collect();
+		let exposure: Vec<_> =
+			ShortestPath::from_entrances_to_any_core(tileset, Some(&build.blocks), diagonals)
+				.into_iter()
+				.map(|path| {
+					path.map(|p| {
+						p.iter()
+							.map(|coord| {
+								let towers = covering(coord);
+								opportunity[coord.1][coord.0] += towers;
+								towers
+							})
+							.sum()
+					})
+				})
+				.collect();
+
+		let total = exposure.iter().flatten().sum();
+		Self { exposure, opportunity, total }
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{Build, Coordinate, ThreatMap, Tileset, Tower};
+	use crate::map::Tile::*;
+
+	#[test]
+	fn of()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1      2      3      4
+			vec![Spawn, Empty, Empty, Empty, Core],  // 0
+			vec![Empty, Empty, Empty, Empty, Empty], // 1
+			vec![Empty, Empty, Empty, Empty, Empty], // 2
+			vec![Spawn, Empty, Empty, Empty, Core],  // 3
+		];
+		let test_tileset = Tileset::new(grid);
+		let build = Build { blocks: HashSet::new() };
+		let towers = [Tower { coordinate: Coordinate(2, 1), range: 1 }];
+
+		let start = Instant::now();
+		let threat = ThreatMap::of(&test_tileset, &build, &towers, false);
+		println!("ThreatMap::of {}us", Instant::now().duration_since(start).as_micros());
+
+		// Only the top path passes within range of the tower, and only on a single tile.
+		assert_eq!(threat.exposure, vec![Some(1), Some(0)]);
+		assert_eq!(threat.opportunity[0][2], 1);
+		assert_eq!(threat.total, 1);
+
+		// Tower bases are found with their range, or the default.
+		let mut grid = test_tileset.grid.clone();
+		grid[2][2] = TowerBase;
+		let mut test_tileset = Tileset::new(grid);
+		assert_eq!(Tower::on_tower_bases(&test_tileset, 3), vec![Tower {
+			coordinate: Coordinate(2, 2),
+			range: 3
+		}]);
+
+		test_tileset.metadata.insert(Coordinate(2, 2), Tower::RANGE, 5.into());
+		assert_eq!(Tower::on_tower_bases(&test_tileset, 3)[0].range, 5);
+	}
+}
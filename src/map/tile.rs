@@ -1,8 +1,17 @@
+mod error;
+
+pub use error::{Error, Result};
 use serde::{Deserialize, Serialize};
 
 /// # Summary
 ///
 /// A square on a [`Map`](super::Map).
+///
+/// # Remarks
+///
+/// Each [`Tile`] has a single-character code for compact [`Map`](super::Map) files, which is
+/// converted with [`char::from`] and [`Tile::try_from`]. Likewise, each has a number for numeric
+/// files, which is converted with [`Tile::number`] and [`Tile::from_number`].
 #[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Tile
 {
@@ -22,6 +31,13 @@ pub enum Tile
 	/// A [`Pass`](Self::Pass) which may have blocks placed on top of it.
 	Empty,
 
+	/// # Summary
+	///
+	/// A [`Pass`](Self::Pass) which damages enemies that cross it (e.g. a lava floor).
+	///
+	/// See [`Objective::hazard_damage`](super::Objective::hazard_damage).
+	Hazard,
+
 	/// # Summary
 	///
 	/// Opposite of [`Pass`](Self::Pass).
@@ -36,16 +52,84 @@ pub enum Tile
 	///
 	/// An [`Impass`](Self::Impass) where enemies may come from. Serves as a __starting point__.
 	Spawn,
+
+	/// # Summary
+	///
+	/// An [`Empty`](Self::Empty) which is also a pre-set slot for a tower. Enemies may walk over
+	/// it until something is built there.
+	TowerBase,
+
+	/// # Summary
+	///
+	/// A [`Block`](Self::Block) which was placed on a [`TowerBase`](Self::TowerBase), so that the
+	/// base is not lost when the block is taken away again.
+	TowerBlock,
+
+	/// # Summary
+	///
+	/// An [`Impass`](Self::Impass) for enemies on foot, which flying enemies may still cross.
+	///
+	/// See [`EnemyClass`](super::EnemyClass).
+	Water,
 }
 
 impl Tile
 {
+	/// # Summary
+	///
+	/// Every [`Tile`].
+	pub const ALL: [Tile; 10] = [
+		Tile::Block,
+		Tile::Core,
+		Tile::Empty,
+		Tile::Hazard,
+		Tile::Impass,
+		Tile::Pass,
+		Tile::Spawn,
+		Tile::TowerBase,
+		Tile::TowerBlock,
+		Tile::Water,
+	];
+
+	/// # Summary
+	///
+	/// The [`Tile`] which a `number` of a numeric [`Map`](super::Map) file stands for.
+	///
+	/// # Errors
+	///
+	/// * [`Error::UnknownCode`], if no [`Tile`] has that [number](Tile::number).
+	pub fn from_number(number: usize) -> Result<Self>
+	{
+		Ok(match number
+		{
+			0 => Tile::Impass,
+			1 => Tile::Empty,
+			2 => Tile::Pass,
+			3 => Tile::Block,
+			4 => Tile::Spawn,
+			5 => Tile::Core,
+			6 => Tile::TowerBase,
+			7 => Tile::Water,
+			8 => Tile::Hazard,
+			9 => Tile::TowerBlock,
+			_ => return Err(Error::UnknownCode { code: number }),
+		})
+	}
+
+	/// # Summary
+	///
+	/// Whether or not a block may be placed on some [`Tile`].
+	pub fn is_buildable(&self) -> bool
+	{
+		matches!(self, Tile::Empty | Tile::TowerBase)
+	}
+
 	/// # Summary
 	///
 	/// Whether or not some [`Tile`] can be moved through.
 	pub fn is_passable(&self) -> bool
 	{
-		matches!(self, Tile::Empty | Tile::Pass)
+		matches!(self, Tile::Empty | Tile::Hazard | Tile::Pass | Tile::TowerBase)
 	}
 
 	/// # Summary
@@ -56,4 +140,86 @@ impl Tile
 	{
 		matches!(self, Tile::Core | Tile::Spawn)
 	}
+
+	/// # Summary
+	///
+	/// The number which stands for some [`Tile`] in a numeric [`Map`](super::Map) file, unless
+	/// its `tile_codes` say otherwise.
+	pub fn number(&self) -> usize
+	{
+		match self
+		{
+			Tile::Impass => 0,
+			Tile::Empty => 1,
+			Tile::Pass => 2,
+			Tile::Block => 3,
+			Tile::Spawn => 4,
+			Tile::Core => 5,
+			Tile::TowerBase => 6,
+			Tile::Water => 7,
+			Tile::Hazard => 8,
+			Tile::TowerBlock => 9,
+		}
+	}
+}
+
+impl From<Tile> for char
+{
+	fn from(tile: Tile) -> Self
+	{
+		match tile
+		{
+			Tile::Block => '#',
+			Tile::Core => 'C',
+			Tile::Empty => '.',
+			Tile::Hazard => '^',
+			Tile::Impass => 'X',
+			Tile::Pass => ',',
+			Tile::Spawn => 'S',
+			Tile::TowerBase => 'T',
+			Tile::TowerBlock => '%',
+			Tile::Water => '~',
+		}
+	}
+}
+
+impl TryFrom<char> for Tile
+{
+	type Error = Error;
+
+	fn try_from(code: char) -> Result<Self>
+	{
+		Ok(match code
+		{
+			'#' => Tile::Block,
+			'C' => Tile::Core,
+			'.' => Tile::Empty,
+			'^' => Tile::Hazard,
+			'X' => Tile::Impass,
+			',' => Tile::Pass,
+			'S' => Tile::Spawn,
+			'T' => Tile::TowerBase,
+			'%' => Tile::TowerBlock,
+			'~' => Tile::Water,
+			_ => return Err(Error::UnknownChar { code }),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::Tile;
+
+	#[test]
+	fn try_from()
+	{
+		Tile::ALL.iter().for_each(|tile| {
+			assert_eq!(Tile::from_number(tile.number()).unwrap(), *tile);
+			assert_eq!(Tile::try_from(char::from(*tile)).unwrap(), *tile);
+		});
+
+		assert!(Tile::from_number(usize::MAX).is_err());
+		assert!(Tile::try_from('?').is_err());
+	}
 }
@@ -0,0 +1,21 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("There is no tile with the character {:?}", code))]
+	UnknownChar
+	{
+		code: char
+	},
+
+	#[snafu(display("There is no tile with the code {}", code))]
+	UnknownCode
+	{
+		code: usize
+	},
+}
+
+pub type Result<T> = StdResult<T, Error>;
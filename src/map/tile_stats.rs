@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use super::{build::Result, tileset::Tileset, Build, Coordinate, DistanceField, Simulation, Tile};
+
+/// # Summary
+///
+/// Figures describing a single tile of a solved map.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TileStats
+{
+	pub coordinate: Coordinate,
+
+	/// # Summary
+	///
+	/// How many moves it takes to get to the nearest [`Tile::Core`], if it can be reached.
+	pub distance_to_core: Option<usize>,
+
+	/// # Summary
+	///
+	/// The spawn regions whose [`ShortestPath`](super::ShortestPath) crosses this tile.
+	pub paths: Vec<usize>,
+
+	/// # Summary
+	///
+	/// The [`Tile`], with the build placed.
+	pub tile: Tile,
+
+	/// # Summary
+	///
+	/// How many ticks an [`Enemy`](super::Enemy) spent here, summed over every enemy of a
+	/// [`Simulation`].
+	pub visits: usize,
+}
+
+impl TileStats
+{
+	/// # Summary
+	///
+	/// The [`TileStats`] of every tile of a `tileset` with some `build` placed, in reading order.
+	///
+	/// # Parameters
+	///
+	/// * `simulation`, the wave of enemies to count the `visits` of.
+	///
+	/// # Errors
+	///
+	/// See [`Build::apply_to`].
+	pub fn of(
+		tileset: &Tileset,
+		build: &Build,
+		simulation: &Simulation,
+		diagonals: bool,
+	) -> Result<Vec<Self>>
+	{
+		let field = DistanceField::to_coordinates(
+			&tileset.grid,
+			Some(&build.blocks),
+			tileset.core_coordinates().iter().copied(),
+			diagonals,
+		);
+
+		let mut grid = tileset.grid.clone();
+		build.apply_to(&mut grid)?;
+
+		let mut stats: Vec<Vec<_>> = grid
+			.iter()
+			.enumerate()
+			.map(|(y, row)| {
+				row.iter()
+					.enumerate()
+					.map(|(x, tile)| {
+						let coordinate = Coordinate(x, y);
+						Self {
+							coordinate,
+							distance_to_core: field.get(&coordinate),
+							paths: Vec::new(),
+							tile: *tile,
+							visits: 0,
+						}
+					})
+					.collect()
+			})
+			.collect();
+
+		simulation.paths().iter().enumerate().for_each(|(region, path)| {
+			path.iter().flatten().for_each(|coord| {
+				if let Some(s) = stats.get_mut(coord.1).and_then(|row| row.get_mut(coord.0))
+				{
+					s.paths.push(region);
+				}
+			})
+		});
+
+		simulation.frames().flat_map(|frame| frame.enemies).for_each(|enemy| {
+			let coord = enemy.coordinate;
+			if let Some(s) = stats.get_mut(coord.1).and_then(|row| row.get_mut(coord.0))
+			{
+				s.visits += 1;
+			}
+		});
+
+		Ok(stats.into_iter().flatten().collect())
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Build, Coordinate, Simulation, TileStats, Tileset};
+	use crate::map::Tile::*;
+
+	#[test]
+	fn of()
+	{
+		#[rustfmt::skip]
+		let mut grid = vec![
+			// 0    1      2      3
+			vec![Spawn, Empty, Empty, Empty], // 0
+			vec![Spawn, Block, Empty, Core],  // 1
+		];
+		let build = Build::take_from(&mut grid);
+		let test_tileset = Tileset::new(grid);
+		let simulation = Simulation::new(&test_tileset, &build, false, 3, 1);
+
+		let start = Instant::now();
+		let stats = TileStats::of(&test_tileset, &build, &simulation, false).unwrap();
+		println!("TileStats::of {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(stats.len(), 8);
+		assert_eq!(stats[5].coordinate, Coordinate(1, 1));
+		assert_eq!(stats[5].tile, Block);
+		assert_eq!(stats[5].distance_to_core, None);
+
+		// Every enemy walks through the entrance, and spends one tick there.
+		assert_eq!(stats[1].paths, vec![0]);
+		assert_eq!(stats[1].visits, 3);
+		assert_eq!(stats[1].distance_to_core, Some(3));
+	}
+}
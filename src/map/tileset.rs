@@ -1,11 +1,33 @@
+mod builder;
 mod error;
+mod region;
+mod region_rule;
+mod targeting;
 
-use std::collections::{HashMap, HashSet, LinkedList};
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, BinaryHeap, HashMap, HashSet, LinkedList},
+};
 
+pub use builder::TilesetBuilder;
 pub use error::{Error, Result};
+pub use region::Region;
+pub use region_rule::RegionRule;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+pub use targeting::Targeting;
 
-use super::{Adjacent, Coordinate, Tile};
+use super::{
+	Adjacent,
+	Coordinate,
+	DistanceField,
+	Metadata,
+	Metric,
+	MovementRules,
+	ShortestPath,
+	Tile,
+};
+use crate::parallel::IntoParallelRefIterator;
 
 pub const COORDINATE_ON_TILESET: &str = "Expected to visit coordinate which exists on tileset.";
 const IS_REGION: &str = "Expected to separate tiles which are regions.";
@@ -19,47 +41,804 @@ pub struct Tileset
 {
 	pub grid: Vec<Vec<Tile>>,
 	pub entrances_by_region: Vec<HashMap<Coordinate, usize>>,
+
+	/// # Summary
+	///
+	/// See [`Tileset::articulation_candidates`].
+	#[serde(default)]
+	articulation_candidates: HashSet<Coordinate>,
+
+	/// # Summary
+	///
+	/// See [`Tileset::avoided`].
+	#[serde(default)]
+	avoided: Vec<(Coordinate, Tile)>,
+
+	/// # Summary
+	///
+	/// See [`Tileset::buildable_mask`].
+	#[serde(default)]
+	buildable: Vec<Vec<bool>>,
+
+	/// # Summary
+	///
+	/// A tile of the core which some spawn regions always walk to, whatever the `targeting`.
+	/// [`Tileset::set_targeting`] must be called after this is changed.
+	#[serde(default)]
+	pub core_assignments: BTreeMap<usize, Coordinate>,
+
+	/// # Summary
+	///
+	/// See [`Tileset::core_coordinates`].
+	#[serde(default)]
+	core_coordinates: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// See [`Tileset::dead_ends`].
+	#[serde(default)]
+	dead_ends: HashSet<Coordinate>,
+
+	/// # Summary
+	///
+	/// Whether diagonal moves are allowed while looking for the entrances of each region and the
+	/// core which it [targets](Tileset::targets).
+	#[serde(default)]
+	pub diagonals: bool,
+
+	/// # Summary
+	///
+	/// The [buildable](Tile::is_buildable) tiles which no entrance can reach, so placing a block
+	/// there can never change a path.
+	pub dead_tiles: HashSet<Coordinate>,
+
+	/// # Summary
+	///
+	/// Extra data attached to some tiles of the `grid`. See [`Map::metadata`](super::Map).
+	pub metadata: Metadata,
+
+	/// # Summary
+	///
+	/// How the distance from each spawn to its entrances was measured.
+	pub metric: Metric,
+
+	/// # Summary
+	///
+	/// How touching spawns (and cores) are grouped into [`Region`]s. See
+	/// [`Tileset::set_region_rule`].
+	#[serde(default)]
+	pub region_rule: RegionRule,
+
+	/// # Summary
+	///
+	/// See [`Tileset::reserve`].
+	#[serde(default)]
+	reserved: HashSet<Coordinate>,
+
+	/// # Summary
+	///
+	/// Every [`Coordinate`] which was looked at while finding the entrances of each region. An
+	/// edit anywhere else cannot change that region's entrances.
+	searched_by_region: Vec<HashSet<Coordinate>>,
+
+	/// # Summary
+	///
+	/// Which cores the enemies of each region walk to. See [`Tileset::set_targeting`].
+	#[serde(default)]
+	pub targeting: Targeting,
+
+	/// # Summary
+	///
+	/// The core region which each spawn region [targets](Tileset::targets).
+	#[serde(default)]
+	targets_by_region: Vec<Option<HashSet<Coordinate>>>,
 }
 
-impl Tileset
-{
+impl Tileset
+{
+	/// # Summary
+	///
+	/// Work out everything about the `grid` which does not depend on a build, so that it is not
+	/// worked out again for every block placed: the `dead_tiles`, the
+	/// [`dead_ends`](Tileset::dead_ends), the
+	/// [`buildable_mask`](Tileset::buildable_mask), the
+	/// [`core_coordinates`](Tileset::core_coordinates), and the
+	/// [`articulation_candidates`](Tileset::articulation_candidates).
+	///
+	/// # Remarks
+	///
+	/// Must be called whenever the `grid` or the entrances change.
+	fn analyze(&mut self)
+	{
+		self.dead_tiles = Self::dead_tiles(&self.grid, &self.entrances_by_region);
+		self.dead_ends = Self::find_dead_ends(&self.grid, &self.entrances_by_region);
+		self.buildable = self
+			.grid
+			.iter()
+			.enumerate()
+			.map(|(y, row)| {
+				row.iter()
+					.enumerate()
+					.map(|(x, tile)| {
+						let coord = Coordinate(x, y);
+						tile.is_buildable() &&
+							!self.dead_tiles.contains(&coord) &&
+							!self.reserved.contains(&coord)
+					})
+					.collect()
+			})
+			.collect();
+		self.core_coordinates = Self::coordinates_of(&self.grid, Tile::Core).collect();
+		self.articulation_candidates = Self::articulation_points(&self.grid)
+			.into_iter()
+			.filter(|coord| self.is_buildable(coord))
+			.collect();
+	}
+
+	/// # Summary
+	///
+	/// The [buildable](Tileset::buildable_mask) tiles which, with no other blocks placed, would
+	/// split the `grid` in two if blocked, such as a spawn from its core.
+	///
+	/// # Remarks
+	///
+	/// Connections are made without diagonals, which is how [`Build`](super::Build)s are checked
+	/// for a path to a core. A block anywhere else can only cut a spawn off from every core
+	/// together with other blocks.
+	pub fn articulation_candidates(&self) -> &HashSet<Coordinate>
+	{
+		&self.articulation_candidates
+	}
+
+	/// # Summary
+	///
+	/// Make some tiles [impassable](Tile::Impass), so that no path may cross them, remembering what
+	/// they were (see [`Tileset::avoided`]).
+	pub fn avoid(&mut self, coords: impl IntoIterator<Item = Coordinate>)
+	{
+		for coord in coords
+		{
+			if let Some(tile) = self.set_tile(coord, Tile::Impass).filter(|t| *t != Tile::Impass)
+			{
+				self.avoided.push((coord, tile));
+			}
+		}
+	}
+
+	/// # Summary
+	///
+	/// The tiles which were made impassable by [`Tileset::avoid`], and what they were before, so
+	/// that they can be put back once solving is done.
+	pub fn avoided(&self) -> &[(Coordinate, Tile)]
+	{
+		&self.avoided
+	}
+
+	/// # Summary
+	///
+	/// Find the passable tiles of a `grid` which, if made impassable, would split the tiles which
+	/// can be walked through or to (passable tiles, spawns, and cores) into more pieces. Moves are
+	/// made without diagonals.
+	///
+	/// # Remarks
+	///
+	/// This is Tarjan's algorithm, with an explicit stack so large maps do not overflow the call
+	/// stack.
+	fn articulation_points(grid: &[impl AsRef<[Tile]>]) -> HashSet<Coordinate>
+	{
+		let walkable_neighbors = |coord: &Coordinate| -> Vec<Coordinate> {
+			Adjacent::from_grid_coordinate(grid, coord, false)
+				.into_iter()
+				.map(|(_, adjacent_coord)| adjacent_coord)
+				.filter(|adjacent_coord| {
+					let tile = adjacent_coord.get_from(grid).expect(COORDINATE_ON_TILESET);
+					tile.is_passable() || tile.is_region()
+				})
+				.collect()
+		};
+
+		let mut discovered = HashMap::<Coordinate, usize>::new();
+		let mut low = HashMap::<Coordinate, usize>::new();
+		let mut points = HashSet::new();
+
+		let walkable = grid.iter().enumerate().flat_map(|(y, row)| {
+			row.as_ref()
+				.iter()
+				.enumerate()
+				.filter(|(_, tile)| tile.is_passable() || tile.is_region())
+				.map(move |(x, _)| Coordinate(x, y))
+		});
+
+		for root in walkable
+		{
+			if discovered.contains_key(&root)
+			{
+				continue;
+			}
+
+			discovered.insert(root, discovered.len());
+			low.insert(root, discovered[&root]);
+
+			let mut root_children = 0;
+			let mut stack = vec![(root, walkable_neighbors(&root), 0)];
+			while let Some((coord, neighbors, next)) = stack.last_mut()
+			{
+				let coord = *coord;
+				if let Some(neighbor) = neighbors.get(*next).copied()
+				{
+					*next += 1;
+					match discovered.get(&neighbor).copied()
+					{
+						Some(order) => low.insert(coord, low[&coord].min(order)),
+						None =>
+						{
+							discovered.insert(neighbor, discovered.len());
+							low.insert(neighbor, discovered[&neighbor]);
+							stack.push((neighbor, walkable_neighbors(&neighbor), 0));
+							None
+						},
+					};
+
+					continue;
+				}
+
+				stack.pop();
+				if let Some((parent, ..)) = stack.last()
+				{
+					low.insert(*parent, low[parent].min(low[&coord]));
+					if *parent == root
+					{
+						root_children += 1;
+					}
+					else if low[&coord] >= discovered[parent]
+					{
+						points.insert(*parent);
+					}
+				}
+			}
+
+			if root_children > 1
+			{
+				points.insert(root);
+			}
+		}
+
+		points
+	}
+
+	/// # Summary
+	///
+	/// Whether each tile of the `grid` is one where a block could ever make a difference: a
+	/// [buildable](Tile::is_buildable) tile which is not [dead](Tileset::dead_tiles) or
+	/// [reserved](Tileset::reserve). Indexed by
+	/// `[y][x]`.
+	pub fn buildable_mask(&self) -> &[Vec<bool>]
+	{
+		&self.buildable
+	}
+
+	/// # Summary
+	///
+	/// Every [`Coordinate`] of the [`buildable_mask`](Tileset::buildable_mask), in reading order.
+	///
+	/// # Remarks
+	///
+	/// A block on one of these may still be invalid for some build, e.g. if it would cut a spawn
+	/// off from every core.
+	pub fn buildable_tiles(&self) -> Vec<Coordinate>
+	{
+		self.buildable
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| {
+				row.iter()
+					.enumerate()
+					.filter(|(_, buildable)| **buildable)
+					.map(move |(x, _)| Coordinate(x, y))
+			})
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Every [`Coordinate`] of some kind of `tile` on a `grid`, in reading order.
+	fn coordinates_of(
+		grid: &[impl AsRef<[Tile]>],
+		tile: Tile,
+	) -> impl Iterator<Item = Coordinate> + '_
+	{
+		grid.iter().enumerate().flat_map(move |(y, row)| {
+			row.as_ref()
+				.iter()
+				.enumerate()
+				.filter(move |(_, t)| **t == tile)
+				.map(move |(x, _)| Coordinate(x, y))
+		})
+	}
+
+	/// # Summary
+	///
+	/// Every [`Tile::Core`] of the `grid`, in reading order.
+	pub fn core_coordinates(&self) -> &[Coordinate]
+	{
+		&self.core_coordinates
+	}
+
+	/// # Summary
+	///
+	/// The [buildable](Tile::is_buildable) tiles in dead-end corridors: those which no path
+	/// between an entrance and a [`Tile::Core`] can pass through without diagonals, however the
+	/// grid is blocked.
+	///
+	/// # Remarks
+	///
+	/// Unlike the [`dead_tiles`](Tileset::dead_tiles), these can be reached, and with diagonal
+	/// movement, blocking one can still stop a path from cutting a corner. Without diagonals, a
+	/// block on one can never lengthen a path.
+	pub fn dead_ends(&self) -> &HashSet<Coordinate>
+	{
+		&self.dead_ends
+	}
+
+	/// # Summary
+	///
+	/// Find the [buildable](Tile::is_buildable) tiles on a `grid` which cannot be reached from any
+	/// of the `entrances`.
+	///
+	/// # Remarks
+	///
+	/// Dead-end corridors are still reachable, so they are not included: with diagonal movement,
+	/// blocking them can still stop a path from cutting a corner. See [`Tileset::dead_ends`].
+	fn dead_tiles(
+		grid: &[impl AsRef<[Tile]>],
+		entrances_by_region: &[HashMap<Coordinate, usize>],
+	) -> HashSet<Coordinate>
+	{
+		let reachable = Self::reachable_from(
+			grid,
+			entrances_by_region.iter().flat_map(|entrances| entrances.keys()),
+		);
+
+		grid.iter()
+			.enumerate()
+			.flat_map(|(y, row)| {
+				row.as_ref()
+					.iter()
+					.enumerate()
+					.filter(|(_, tile)| tile.is_buildable())
+					.map(move |(x, _)| Coordinate(x, y))
+			})
+			.filter(|coord| !reachable.contains(coord))
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Every [`Tile::Spawn`] [`Region`] of this [`Tileset`], with the entrances it uses.
+	pub fn entrances(&self) -> Vec<Region>
+	{
+		Self::find_regions(&self.grid, Tile::Spawn, &self.metadata, self.region_rule)
+			.expect(IS_REGION)
+			.into_iter()
+			.zip(self.entrances_by_region.iter())
+			.enumerate()
+			.map(|(id, (coordinates, entrances))| Region {
+				entrances: entrances.clone(),
+				id,
+				name: Self::region_name(&self.metadata, &coordinates),
+				coordinates,
+			})
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Find the entrances of every region again, from scratch.
+	fn find_all_entrances(&mut self)
+	{
+		let (entrances_by_region, searched_by_region) = Self::find_entrances(
+			&self.grid,
+			self.metric,
+			self.diagonals,
+			&self.metadata,
+			self.region_rule,
+		)
+		.into_iter()
+		.unzip();
+		self.entrances_by_region = entrances_by_region;
+		self.searched_by_region = searched_by_region;
+	}
+
+	/// # Summary
+	///
+	/// Find the core region which each region [targets](Tileset::targets) again, from scratch.
+	fn find_all_targets(&mut self)
+	{
+		if self.targeting.is_default() && self.core_assignments.is_empty()
+		{
+			self.targets_by_region = Vec::new();
+			return;
+		}
+
+		let mut targets_by_region: Vec<_> = match self.targeting
+		{
+			Targeting::AnyCore => vec![None; self.entrances_by_region.len()],
+			Targeting::NearestCore => self
+				.entrances_by_region
+				.iter()
+				.map(|entrances| {
+					ShortestPath::from_any_grid_coordinate_to_tile(
+						&self.grid,
+						Option::<&HashSet<_>>::None,
+						entrances.par_iter(),
+						Tile::Core,
+						self.diagonals,
+					)
+					.map(|path| {
+						Self::region_at(&self.grid, path.core(), &self.metadata, self.region_rule)
+					})
+				})
+				.collect(),
+		};
+
+		// An edit may have removed an assigned core, in which case the region is left as it was.
+		self.core_assignments
+			.iter()
+			.filter(|(_, core)| core.get_from(&self.grid) == Some(Tile::Core))
+			.for_each(|(region, core)| {
+				if let Some(targets) = targets_by_region.get_mut(*region)
+				{
+					*targets =
+						Some(Self::region_at(&self.grid, *core, &self.metadata, self.region_rule));
+				}
+			});
+
+		self.targets_by_region = targets_by_region;
+	}
+
+	/// # Summary
+	///
+	/// See [`Tileset::dead_ends`].
+	///
+	/// # Remarks
+	///
+	/// Passable tiles with at most one passable neighbour are removed until none are left. Paths
+	/// begin at the `entrances` and end beside a [`Tile::Core`], so those tiles are never removed.
+	fn find_dead_ends(
+		grid: &[impl AsRef<[Tile]>],
+		entrances_by_region: &[HashMap<Coordinate, usize>],
+	) -> HashSet<Coordinate>
+	{
+		let passable = |coord: &Coordinate| coord.get_from(grid).is_some_and(|t| t.is_passable());
+		let neighbours = |coord: &Coordinate| {
+			Adjacent::from_grid_coordinate(grid, coord, false).into_iter().map(|(_, c)| c)
+		};
+
+		let mut remaining: HashSet<_> = grid
+			.iter()
+			.enumerate()
+			.flat_map(|(y, row)| (0..row.as_ref().len()).map(move |x| Coordinate(x, y)))
+			.filter(passable)
+			.collect();
+		let kept: HashSet<_> = entrances_by_region
+			.iter()
+			.flat_map(|entrances| entrances.keys().copied())
+			.chain(
+				Self::coordinates_of(grid, Tile::Core)
+					.flat_map(|core| neighbours(&core).collect::<Vec<_>>()),
+			)
+			.collect();
+
+		let degree = |remaining: &HashSet<Coordinate>, coord: &Coordinate| {
+			neighbours(coord).filter(|c| remaining.contains(c)).count()
+		};
+		let mut queue: Vec<_> = remaining
+			.iter()
+			.filter(|coord| !kept.contains(coord) && degree(&remaining, coord) <= 1)
+			.copied()
+			.collect();
+		let mut dead_ends = HashSet::new();
+
+		while let Some(coord) = queue.pop()
+		{
+			if kept.contains(&coord) ||
+				!remaining.contains(&coord) ||
+				degree(&remaining, &coord) > 1
+			{
+				continue;
+			}
+
+			remaining.remove(&coord);
+			if coord.get_from(grid).is_some_and(|t| t.is_buildable())
+			{
+				dead_ends.insert(coord);
+			}
+			queue.extend(neighbours(&coord).filter(|c| remaining.contains(c)));
+		}
+
+		dead_ends
+	}
+
+	/// # Summary
+	///
+	/// Select all of the [`Tile::Empty`]s next to [`Tile::Spawn`] points on this [`Tileset`],
+	/// measuring their distance from the spawn with some `metric`, and moving diagonally if
+	/// `diagonals` are allowed.
+	///
+	/// # Returns
+	///
+	/// The entrances of each region, and the [`Coordinate`]s which were searched to find them.
+	fn find_entrances(
+		tileset: &[impl AsRef<[Tile]>],
+		metric: Metric,
+		diagonals: bool,
+		metadata: &Metadata,
+		region_rule: RegionRule,
+	) -> Vec<(HashMap<Coordinate, usize>, HashSet<Coordinate>)>
+	{
+		Self::find_regions(tileset, Tile::Spawn, metadata, region_rule)
+			.expect(IS_REGION)
+			.into_iter()
+			.map(|region| Self::search(tileset, &region, Tile::Empty, metric, diagonals))
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Get all of the different regions for some type of `tile`, grouped by some `region_rule`
+	/// (which may look at the `metadata`).
+	///
+	/// # Remarks
+	///
+	/// Regions with a [name](Tileset::region_names) come first, ordered by name (numbers before
+	/// words, and by value). The rest follow in reading order of their first tile.
+	pub(super) fn find_regions(
+		tileset: &[impl AsRef<[Tile]>],
+		start_tile: Tile,
+		metadata: &Metadata,
+		region_rule: RegionRule,
+	) -> Result<Vec<HashSet<Coordinate>>>
+	{
+		if !start_tile.is_region()
+		{
+			return Err(Error::NotRegion { tile: start_tile });
+		}
+
+		let mut buckets = Vec::<HashSet<Coordinate>>::new();
+
+		tileset.iter().enumerate().for_each(|(y, row)| {
+			row.as_ref()
+				.iter()
+				.enumerate()
+				.filter(|(_, row_value)| *row_value == &start_tile)
+				.for_each(|(x, _)| {
+					let coord = Coordinate(x, y);
+					if buckets.iter().all(|set| !set.contains(&coord))
+					{
+						buckets.push(Self::region_at(tileset, coord, metadata, region_rule))
+					}
+				})
+		});
+
+		// The sort is stable, so regions without a name stay in reading order.
+		buckets.sort_by_cached_key(|region| {
+			let name = Self::region_name(metadata, region);
+			let number = name.as_deref().and_then(|n| n.parse::<u64>().ok());
+			(name.is_none(), number.is_none(), number, name)
+		});
+
+		Ok(buckets)
+	}
+
+	/// # Summary
+	///
+	/// Get the adjacent [`Tile`]s of `needle`'s type which are adjecent to the `start`ing
+	/// [`Coordinate`], and how far they are from it.
+	///
+	/// # Remarks
+	///
+	/// The distance is measured by walking from `start` through the tiles in between, so walls
+	/// inside of a spawn room are taken into account. Moving within the region which `start` is
+	/// part of (see [`RegionRule::Merge`]) is free, and each move beyond it is measured with some
+	/// `metric`. Diagonal moves are only made if `diagonals` are allowed.
+	pub fn get_adjacent_to(
+		grid: &[impl AsRef<[Tile]>],
+		start: Coordinate,
+		needle: Tile,
+		metric: Metric,
+		diagonals: bool,
+	) -> HashMap<Coordinate, usize>
+	{
+		let region = Self::region_at(grid, start, &Metadata::default(), RegionRule::Merge);
+		Self::search(grid, &region, needle, metric, diagonals).0
+	}
+
+	/// # Summary
+	///
+	/// Whether a block on `coord` could ever make a difference. See [`Tileset::buildable_mask`].
+	pub fn is_buildable(&self, coord: &Coordinate) -> bool
+	{
+		coord.get_from(&self.buildable).unwrap_or(false)
+	}
+
+	/// # Summary
+	///
+	/// Create a new [`Tileset`] from some two-dimensional `grid` of [`Tile`]s.
+	///
+	/// # Remarks
+	///
+	/// Entrance distances are measured with the default [`Metric`], without diagonal moves. See
+	/// [`Tileset::with_metric`].
+	pub fn new(grid: Vec<Vec<Tile>>) -> Self
+	{
+		Self::with_metric(grid, Metric::default(), false)
+	}
+
+	/// # Summary
+	///
+	/// Get every passable [`Coordinate`] which can be walked to from any of the `start` points.
+	fn reachable_from<'coord>(
+		grid: &[impl AsRef<[Tile]>],
+		start: impl IntoIterator<Item = &'coord Coordinate>,
+	) -> HashSet<Coordinate>
+	{
+		let mut coordinate_queue: LinkedList<_> = start.into_iter().copied().collect();
+		let mut visited = HashSet::new();
+
+		while let Some(coord) = coordinate_queue.pop_front()
+		{
+			if visited.contains(&coord) ||
+				!coord.get_from(grid).expect(COORDINATE_ON_TILESET).is_passable()
+			{
+				continue;
+			}
+
+			visited.insert(coord);
+
+			// Diagonal movement can't reach anything new, since it may not cut corners.
+			Adjacent::from_grid_coordinate(grid, &coord, false)
+				.into_iter()
+				.for_each(|(_, adjacent_coord)| coordinate_queue.push_back(adjacent_coord));
+		}
+
+		visited
+	}
+
+	/// # Summary
+	///
+	/// The tiles of the region which `start` is part of: every tile of the same kind which can be
+	/// reached from it across shared edges, as long as the `region_rule` joins them.
+	fn region_at(
+		grid: &[impl AsRef<[Tile]>],
+		start: Coordinate,
+		metadata: &Metadata,
+		region_rule: RegionRule,
+	) -> HashSet<Coordinate>
+	{
+		let start_tile = start.get_from(grid).expect(COORDINATE_ON_TILESET);
+		let mut coordinate_queue = LinkedList::new();
+		let mut visited = HashSet::new();
+
+		coordinate_queue.push_back(start);
+		visited.insert(start);
+
+		while let Some(coord) = coordinate_queue.pop_front()
+		{
+			Adjacent::from_grid_coordinate(grid, &coord, false).into_iter().for_each(
+				|(_, adjacent_coord)| {
+					if !visited.contains(&adjacent_coord) &&
+						adjacent_coord.get_from(grid) == Some(start_tile) &&
+						region_rule.joins(metadata, &coord, &adjacent_coord)
+					{
+						visited.insert(adjacent_coord);
+						coordinate_queue.push_back(adjacent_coord);
+					}
+				},
+			);
+		}
+
+		visited
+	}
+
+	/// # Summary
+	///
+	/// The name of a `region`: the smallest [`RegionRule::LABEL`] which any of its tiles has in
+	/// the `metadata`, or [`None`] if none of them have one.
+	fn region_name(metadata: &Metadata, region: &HashSet<Coordinate>) -> Option<String>
+	{
+		region
+			.iter()
+			.filter_map(|coord| metadata.get_value(coord, RegionRule::LABEL))
+			.map(|value| match value
+			{
+				Value::String(s) => s.clone(),
+				v => v.to_string(),
+			})
+			.min()
+	}
+
 	/// # Summary
 	///
-	/// Select all of the [`Tile::Empty`]s next to [`Tile::Spawn`] points on this [`Tileset`].
-	fn entrances(tileset: &[impl AsRef<[Tile]>]) -> Vec<HashMap<Coordinate, usize>>
+	/// The name of each spawn region, in the same order as the
+	/// [`entrances_by_region`](Tileset::entrances_by_region). See [`Tileset::find_regions`].
+	pub fn region_names(&self) -> Vec<Option<String>>
 	{
-		Self::separate_regions(tileset, Tile::Spawn)
+		Self::find_regions(&self.grid, Tile::Spawn, &self.metadata, self.region_rule)
 			.expect(IS_REGION)
+			.iter()
+			.map(|region| Self::region_name(&self.metadata, region))
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Every [`Region`] of some kind of `tile` on this [`Tileset`], in the order of
+	/// [`Tileset::find_regions`]. Entrance distances are measured with the [`Tileset::metric`].
+	///
+	/// # Errors
+	///
+	/// * [`Error::NotRegion`], if the `tile` [is not a region](Tile::is_region).
+	pub fn regions(&self, tile: Tile) -> Result<Vec<Region>>
+	{
+		Ok(Self::find_regions(&self.grid, tile, &self.metadata, self.region_rule)?
 			.into_iter()
-			.map(|region| {
-				// get a random point on the region and look for adjacent empty tiles
-				Self::get_adjacent_to(
-					tileset,
-					region.into_iter().next().expect(REGION_HAS_COORDINATE),
+			.enumerate()
+			.map(|(id, coordinates)| Region {
+				entrances: Self::search(
+					&self.grid,
+					&coordinates,
 					Tile::Empty,
+					self.metric,
+					self.diagonals,
 				)
+				.0,
+				id,
+				name: Self::region_name(&self.metadata, &coordinates),
+				coordinates,
 			})
-			.collect()
+			.collect())
 	}
 
 	/// # Summary
 	///
-	/// Get the adjacent [`Tile`]s of `needle`'s type which are adjecent to the `start`ing
-	/// [`Coordinate`].
-	pub fn get_adjacent_to(
+	/// Never place a block on some tiles, although paths may still cross them.
+	pub fn reserve(&mut self, coords: impl IntoIterator<Item = Coordinate>)
+	{
+		self.reserved.extend(coords);
+		self.analyze();
+	}
+
+	/// # Summary
+	///
+	/// Perform [`Tileset::get_adjacent_to`], starting from every tile of a `region`.
+	///
+	/// # Returns
+	///
+	/// The [`Tile`]s which were found, and every [`Coordinate`] which was looked at to find them.
+	fn search(
 		grid: &[impl AsRef<[Tile]>],
-		start: Coordinate,
+		region: &HashSet<Coordinate>,
 		needle: Tile,
-	) -> HashMap<Coordinate, usize>
+		metric: Metric,
+		diagonals: bool,
+	) -> (HashMap<Coordinate, usize>, HashSet<Coordinate>)
 	{
-		let start_tile = start.get_from(&grid).expect(COORDINATE_ON_TILESET);
+		/// # Summary
+		///
+		/// How many thousandths of a tile each move costs, so that [`Metric::Euclidean`] diagonals
+		/// can be measured without floating point.
+		const STEP: usize = 1000;
 
-		let mut coordinate_queue = LinkedList::new();
-		let mut visited = HashMap::new();
+		/// # Summary
+		///
+		/// The cost of a diagonal move with [`Metric::Euclidean`]: `STEP * sqrt(2)`.
+		const EUCLIDEAN_DIAGONAL_STEP: usize = 1414;
 
-		coordinate_queue.push_back(start);
+		let mut coordinate_queue: BinaryHeap<_> =
+			region.iter().map(|coord| Reverse((0, *coord))).collect();
+		let mut visited = HashMap::new();
 
-		while let Some(coord) = coordinate_queue.pop_front()
+		while let Some(Reverse((cost, coord))) = coordinate_queue.pop()
 		{
 			// Don't revisit a coordinate we've already been to.
 			if visited.contains_key(&coord)
@@ -68,98 +847,247 @@ impl Tileset
 			}
 
 			// All of the coordinates from `select` should exist in the `tileset`.
-			let tile = coord.get_from(&grid).expect(COORDINATE_ON_TILESET);
+			let tile = coord.get_from(grid).expect(COORDINATE_ON_TILESET);
 
 			// We shouldn't count a coordinate as 'visited' until we can extract its tile value.
-			visited.insert(coord, tile);
+			visited.insert(coord, (tile, cost));
+
+			let in_start_region = tile.is_region() && region.contains(&coord);
 
 			// These are the tiles which we want to keep looking beyond.
-			if (start_tile.is_region() && tile == start_tile) ||
-				(tile.is_passable() && tile != needle)
+			if in_start_region || (tile.is_passable() && tile != needle)
 			{
-				Adjacent::from_grid_coordinate(&grid, &coord, false)
-					.for_each(|adjacent_coord| coordinate_queue.push_back(adjacent_coord));
+				Adjacent::from_grid_coordinate_with_build(
+					grid,
+					Option::<&HashSet<_>>::None,
+					&coord,
+					diagonals,
+				)
+				.into_iter()
+				.for_each(|(direction, adjacent_coord)| {
+					let step = if in_start_region && region.contains(&adjacent_coord)
+					{
+						0
+					}
+					else if direction.is_diagonal()
+					{
+						match metric
+						{
+							Metric::Chebyshev => STEP,
+							Metric::Euclidean => EUCLIDEAN_DIAGONAL_STEP,
+							Metric::Manhattan => 2 * STEP,
+						}
+					}
+					else
+					{
+						STEP
+					};
+
+					coordinate_queue.push(Reverse((cost + step, adjacent_coord)));
+				});
 			}
 		}
 
 		// Whatever we visited which was an `Empty` tile, return.
-		visited
-			.into_iter()
-			.filter(|(_, tile)| tile == &needle)
-			.map(|(coord, _)| (coord, coord.distance_from(&start)))
-			.collect()
+		let found = visited
+			.iter()
+			.filter(|(_, (tile, _))| tile == &needle)
+			.map(|(coord, (_, cost))| (*coord, (cost + STEP / 2) / STEP))
+			.collect();
+
+		(found, visited.into_keys().collect())
 	}
 
 	/// # Summary
 	///
-	/// Create a new [`Tileset`] from some two-dimensional `grid` of [`Tile`]s.
-	pub fn new(grid: Vec<Vec<Tile>>) -> Self
+	/// Group touching spawns (and cores) into [`Region`]s by some `region_rule`, which may look at
+	/// the `metadata`, and find the entrances of each region again.
+	///
+	/// # Remarks
+	///
+	/// Regions are numbered the same way whichever rule is used (see [`Tileset::find_regions`]),
+	/// but the number of regions may change. Entrances given to
+	/// [`TilesetBuilder::entrance_overrides`] are not kept.
+	pub fn set_region_rule(&mut self, region_rule: RegionRule)
 	{
-		Self { entrances_by_region: Self::entrances(&grid), grid }
+		self.region_rule = region_rule;
+		self.find_all_entrances();
+		self.find_all_targets();
+		self.analyze();
 	}
 
 	/// # Summary
 	///
-	/// Get all of the different regions for some type of `tile`.
-	fn separate_regions(
-		tileset: &[impl AsRef<[Tile]>],
-		start_tile: Tile,
-	) -> Result<Vec<HashSet<Coordinate>>>
+	/// Choose which cores the enemies of each region walk to, and find them.
+	///
+	/// # Remarks
+	///
+	/// With [`Targeting::NearestCore`], each region targets the core region which its shortest
+	/// path reaches on the bare `grid`. Regions in the `core_assignments` target their assigned
+	/// core instead. The targets are found again whenever the `grid` or the regions change, but
+	/// never because of a build.
+	pub fn set_targeting(&mut self, targeting: Targeting)
 	{
-		if !start_tile.is_region()
+		self.targeting = targeting;
+		self.find_all_targets();
+	}
+
+	/// # Summary
+	///
+	/// Change the [`Tile`] at some `coord` of the `grid`, and update the entrances of every region
+	/// that the change could affect.
+	///
+	/// # Remarks
+	///
+	/// Only regions whose entrance search looked at `coord` are measured again, unless a
+	/// [`Tile::Spawn`] is placed or removed, which can merge, split, or renumber regions. The
+	/// `dead_tiles` and [targets](Tileset::targets) are always found again.
+	///
+	/// Entrances given to [`TilesetBuilder::entrance_overrides`] are kept, unless a
+	/// [`Tile::Spawn`] is placed or removed.
+	///
+	/// # Returns
+	///
+	/// The [`Tile`] which used to be at `coord`, or [`None`] if it is not on the `grid`.
+	pub fn set_tile(&mut self, coord: Coordinate, tile: Tile) -> Option<Tile>
+	{
+		let old_tile = coord.get_from(&self.grid)?;
+		if old_tile == tile
 		{
-			return Err(Error::NotRegion { tile: start_tile });
+			return Some(old_tile);
 		}
 
-		let mut buckets = Vec::<HashSet<Coordinate>>::new();
-
-		let get_region = |start: Coordinate| -> HashSet<Coordinate> {
-			let mut coordinate_queue = LinkedList::new();
-			let mut visited = HashSet::new();
-
-			coordinate_queue.push_back(start);
+		coord.set(&mut self.grid, tile).expect(COORDINATE_ON_TILESET);
 
-			while let Some(coord) = coordinate_queue.pop_front()
+		if old_tile == Tile::Spawn || tile == Tile::Spawn
+		{
+			self.find_all_entrances();
+		}
+		else
+		{
+			let spawns =
+				Self::find_regions(&self.grid, Tile::Spawn, &self.metadata, self.region_rule)
+					.expect(IS_REGION);
+			for (region, spawn) in spawns.into_iter().enumerate()
 			{
-				// Don't revisit a coordinate we've already been to.
-				if visited.contains(&coord)
+				if self.searched_by_region[region].contains(&coord)
 				{
-					continue;
+					(self.entrances_by_region[region], self.searched_by_region[region]) =
+						Self::search(&self.grid, &spawn, Tile::Empty, self.metric, self.diagonals);
 				}
+			}
+		}
 
-				// All of the coordinates from `select` should exist in the `tileset`.
-				let tile = coord.get_from(&tileset).expect(COORDINATE_ON_TILESET);
+		self.find_all_targets();
+		self.analyze();
+		Some(old_tile)
+	}
 
-				// These are the tiles which we want to keep looking beyond.
-				if tile == start_tile
-				{
-					// We shouldn't count a coordinate as 'visited' until we can extract its tile
-					// value.
-					visited.insert(coord);
+	/// # Summary
+	///
+	/// The core tiles which the enemies of some spawn `region` walk to, or [`None`] if they walk to
+	/// any core. See [`Tileset::set_targeting`].
+	pub fn targets(&self, region: usize) -> Option<&HashSet<Coordinate>>
+	{
+		self.targets_by_region.get(region).and_then(Option::as_ref)
+	}
 
-					Adjacent::from_grid_coordinate(&tileset, &coord, false)
-						.for_each(|adjacent_coord| coordinate_queue.push_back(adjacent_coord));
-				}
-			}
+	/// # Summary
+	///
+	/// An upper bound on the length of the [`ShortestPath`](super::ShortestPath) which any build
+	/// could force on each region.
+	///
+	/// # Remarks
+	///
+	/// A path may visit each passable tile it can reach at most once before arriving at a core, so
+	/// no build can make a path longer than that (plus the distance of the furthest entrance).
+	///
+	/// A shortest path also never crosses every tile of a 2x2 square of passable tiles, since it
+	/// could cut across the square instead. It leaves out at least one tile of each such square,
+	/// or two when moving diagonally, so one (or two) is taken off the bound for each square which
+	/// does not overlap another.
+	pub fn upper_bounds(&self, movement: impl Into<MovementRules>) -> Vec<usize>
+	{
+		let movement = movement.into();
+		let left_out = if movement.diagonals { 2 } else { 1 };
 
-			visited
-		};
+		self.entrances_by_region
+			.iter()
+			.map(|entrances| {
+				let reachable = DistanceField::to_coordinates(
+					&self.grid,
+					Option::<&HashSet<_>>::None,
+					entrances.keys().copied(),
+					movement,
+				);
 
-		tileset.iter().enumerate().for_each(|(y, row)| {
-			row.as_ref()
-				.iter()
-				.enumerate()
-				.filter(|(_, row_value)| *row_value == &start_tile)
-				.for_each(|(x, _)| {
-					let coord = Coordinate(x, y);
-					if buckets.iter().all(|set| !set.contains(&coord))
-					{
-						buckets.push(get_region(Coordinate(x, y)))
-					}
-				})
-		});
+				let mut squared = HashSet::new();
+				let (mut tiles, mut squares) = (0, 0);
+				self.grid.iter().enumerate().for_each(|(y, row)| {
+					(0..row.len()).map(|x| Coordinate(x, y)).for_each(|coord| {
+						if reachable.get(&coord).is_none()
+						{
+							return;
+						}
 
-		Ok(buckets)
+						tiles += 1;
+						let square = [
+							coord,
+							Coordinate(coord.0 + 1, coord.1),
+							Coordinate(coord.0, coord.1 + 1),
+							Coordinate(coord.0 + 1, coord.1 + 1),
+						];
+
+						if square.iter().all(|c| reachable.get(c).is_some() && !squared.contains(c))
+						{
+							squared.extend(square);
+							squares += 1;
+						}
+					});
+				});
+
+				tiles - squares * left_out + entrances.values().max().copied().unwrap_or(0) + 1
+			})
+			.collect()
+	}
+
+	/// # Summary
+	///
+	/// Create a new [`Tileset`] from some two-dimensional `grid` of [`Tile`]s, measuring the
+	/// distance from each spawn to its entrances with some `metric`, and moving diagonally if
+	/// `diagonals` are allowed.
+	pub fn with_metric(grid: Vec<Vec<Tile>>, metric: Metric, diagonals: bool) -> Self
+	{
+		let (entrances_by_region, searched_by_region): (Vec<_>, _) = Self::find_entrances(
+			&grid,
+			metric,
+			diagonals,
+			&Metadata::default(),
+			RegionRule::default(),
+		)
+		.into_iter()
+		.unzip();
+		let mut tileset = Self {
+			articulation_candidates: HashSet::new(),
+			avoided: Vec::new(),
+			buildable: Vec::new(),
+			core_assignments: BTreeMap::new(),
+			core_coordinates: Vec::new(),
+			dead_ends: HashSet::new(),
+			dead_tiles: HashSet::new(),
+			diagonals,
+			entrances_by_region,
+			grid,
+			metadata: Metadata::default(),
+			metric,
+			region_rule: RegionRule::default(),
+			reserved: HashSet::new(),
+			searched_by_region,
+			targeting: Targeting::default(),
+			targets_by_region: Vec::new(),
+		};
+		tileset.analyze();
+		tileset
 	}
 }
 
@@ -168,7 +1096,10 @@ pub mod tests
 {
 	use std::time::Instant;
 
-	use super::{Coordinate, Tile, Tile::*, Tileset};
+	use serde_json::json;
+
+	use super::{Coordinate, Metadata, Metric, RegionRule, Targeting, Tile, Tile::*, Tileset};
+	use crate::map::Build;
 
 	/// # Summary
 	///
@@ -214,17 +1145,114 @@ pub mod tests
 		[Impass, Impass, Impass, Impass, Pass,   Pass,   Pass,   Pass,   Empty,  Empty,  Empty,  Empty,  Empty, Empty, Empty,  Empty], // 13
 	];
 
+	#[test]
+	fn articulation_candidates()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1       2      3
+			vec![Spawn,  Empty,  Empty, Empty], // 0
+			vec![Impass, Impass, Empty, Empty], // 1
+			vec![Core,   Empty,  Empty, Empty], // 2
+		];
+
+		let start = Instant::now();
+		let test_tileset = Tileset::new(grid);
+		println!("Tileset::new {}us", Instant::now().duration_since(start).as_micros());
+
+		// The loop on the right can be blocked anywhere but its corners without splitting the map.
+		assert_eq!(
+			test_tileset.articulation_candidates(),
+			&[Coordinate(1, 0), Coordinate(2, 0), Coordinate(1, 2), Coordinate(2, 2)]
+				.iter()
+				.copied()
+				.collect()
+		);
+		assert_eq!(test_tileset.core_coordinates(), &[Coordinate(0, 2)]);
+	}
+
+	#[test]
+	fn buildable_tiles()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1       2      3
+			vec![Spawn, Empty,  Impass, Empty], // 0
+			vec![Empty, Pass,   Impass, Empty], // 1
+			vec![Empty, Core,   Impass, Empty], // 2
+		];
+		let test_tileset = Tileset::new(grid);
+
+		let start = Instant::now();
+		let buildable = test_tileset.buildable_tiles();
+		println!("Tileset::buildable_tiles {}us", Instant::now().duration_since(start).as_micros());
+
+		// The pocket behind the wall is dead, and the `Pass` cannot be built on.
+		assert_eq!(buildable, vec![Coordinate(1, 0), Coordinate(0, 1), Coordinate(0, 2)]);
+		assert_eq!(test_tileset.buildable_mask(), &[
+			vec![false, true, false, false],
+			vec![true, false, false, false],
+			vec![true, false, false, false],
+		]);
+		assert!(!test_tileset.is_buildable(&Coordinate(3, 0)));
+	}
+
+	#[test]
+	fn dead_ends()
+	{
+		#[rustfmt::skip]
+		let test_tileset = Tileset::new(vec![
+			// 0     1       2      3      4
+			vec![Spawn,  Empty,  Empty, Empty,  Core],   // 0
+			vec![Impass, Impass, Empty, Impass, Impass], // 1
+			vec![Impass, Empty,  Empty, Impass, Impass], // 2
+		]);
+
+		// The corridor below the path leads nowhere, but the path itself ends at the core.
+		assert_eq!(
+			*test_tileset.dead_ends(),
+			[Coordinate(2, 1), Coordinate(1, 2), Coordinate(2, 2)].into_iter().collect()
+		);
+		assert!(test_tileset.dead_tiles.is_empty());
+	}
+
+	#[test]
+	fn dead_tiles()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1       2      3
+			vec![Spawn, Empty,  Impass, Empty], // 0
+			vec![Empty, Empty,  Impass, Empty], // 1
+			vec![Empty, Core,   Impass, Empty], // 2
+		];
+
+		let start = Instant::now();
+		let test_tileset = Tileset::new(grid);
+		println!("Tileset::new {}us", Instant::now().duration_since(start).as_micros());
+
+		// The pocket behind the wall can never be reached.
+		assert_eq!(
+			test_tileset.dead_tiles,
+			[Coordinate(3, 0), Coordinate(3, 1), Coordinate(3, 2)].iter().copied().collect()
+		);
+	}
+
 	#[test]
 	fn entrances()
 	{
+		let test_tileset = Tileset::new(PARK.iter().map(|inner| inner.to_vec()).collect());
+
 		let start = Instant::now();
-		let entrances = Tileset::entrances(&PARK);
+		let entrances = test_tileset.entrances();
 		println!("Tileset::entrances {}us", Instant::now().duration_since(start).as_micros());
 
 		assert_eq!(entrances.len(), 1);
+		assert_eq!(entrances[0].id, 0);
+		assert_eq!(entrances[0].coordinates, [Coordinate(0, 2)].iter().copied().collect());
 		assert_eq!(
-			entrances.first().unwrap(),
-			&[
+			entrances[0].entrances,
+			[
 				(Coordinate(4, 1), 5),
 				(Coordinate(4, 2), 4),
 				(Coordinate(4, 3), 5),
@@ -233,24 +1261,75 @@ pub mod tests
 			.iter()
 			.copied()
 			.collect()
+		);
+
+		// With diagonal movement, every entrance is the same number of moves away.
+		assert!(Tileset::find_entrances(
+			&PARK,
+			Metric::Chebyshev,
+			true,
+			&Metadata::default(),
+			RegionRule::Merge
 		)
+		.first()
+		.unwrap()
+		.0
+		.values()
+		.all(|distance| *distance == 4));
+
+		// The entrance is close to the spawn, but the wall between them has to be walked around.
+		#[rustfmt::skip]
+		let walled = [
+			// 0     1       2
+			[Spawn,  Pass,   Pass], // 0
+			[Impass, Impass, Pass], // 1
+			[Empty,  Pass,   Pass], // 2
+		];
+		assert_eq!(
+			Tileset::find_entrances(
+				&walled,
+				Metric::Manhattan,
+				false,
+				&Metadata::default(),
+				RegionRule::Merge
+			)[0]
+			.0,
+			[(Coordinate(0, 2), 6)].iter().copied().collect()
+		);
+
+		// Whether diagonal moves are made does not depend on how they are measured.
+		[(Metric::Chebyshev, false, 6), (Metric::Chebyshev, true, 4), (Metric::Manhattan, true, 6)]
+			.into_iter()
+			.for_each(|(metric, diagonals, distance)| {
+				assert_eq!(
+					Tileset::find_entrances(
+						&walled,
+						metric,
+						diagonals,
+						&Metadata::default(),
+						RegionRule::Merge
+					)[0]
+					.0,
+					[(Coordinate(0, 2), distance)].iter().copied().collect()
+				);
+			});
 	}
 
 	#[test]
-	fn separate_regions()
+	fn regions()
 	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
 		let start = Instant::now();
-		let core_regions = Tileset::separate_regions(&PARK_TWO_SPAWN, Tile::Core).unwrap();
-		let spawn_regions = Tileset::separate_regions(&PARK_TWO_SPAWN, Tile::Spawn).unwrap();
-		println!(
-			"Tileset::separate_regions {}us",
-			Instant::now().duration_since(start).as_micros() / 2
-		);
+		let core_regions = test_tileset.regions(Tile::Core).unwrap();
+		let spawn_regions = test_tileset.regions(Tile::Spawn).unwrap();
+		println!("Tileset::regions {}us", Instant::now().duration_since(start).as_micros() / 2);
 
 		// Only one `core` region
 		assert_eq!(core_regions.len(), 1);
 		assert_eq!(
-			core_regions[0],
+			core_regions[0].coordinates,
 			[Coordinate(5, 11), Coordinate(5, 12), Coordinate(6, 11), Coordinate(6, 12)]
 				.iter()
 				.copied()
@@ -258,11 +1337,152 @@ pub mod tests
 		);
 
 		// Can't get a non-region.
-		assert!(Tileset::separate_regions(&PARK_TWO_SPAWN, Tile::Impass).is_err());
+		assert!(test_tileset.regions(Tile::Impass).is_err());
 
 		// Two `spawn` regions
 		assert_eq!(spawn_regions.len(), 2);
-		assert_eq!(spawn_regions[0], [Coordinate(0, 2)].iter().copied().collect());
-		assert_eq!(spawn_regions[1], [Coordinate(15, 5)].iter().copied().collect());
+		assert_eq!(spawn_regions[0].coordinates, [Coordinate(0, 2)].iter().copied().collect());
+		assert_eq!(spawn_regions[1].coordinates, [Coordinate(15, 5)].iter().copied().collect());
+
+		// The entrances of each `spawn` region are the ones the tileset uses.
+		assert!(spawn_regions
+			.iter()
+			.all(|region| region.entrances == test_tileset.entrances_by_region[region.id]));
+		assert_eq!(test_tileset.entrances(), spawn_regions);
+
+		// A named region comes before one without a name, wherever it is on the grid.
+		let mut named = test_tileset.clone();
+		named.metadata.insert(Coordinate(15, 5), RegionRule::LABEL, "east".into());
+		named.set_region_rule(RegionRule::Merge);
+		let spawn_regions = named.regions(Tile::Spawn).unwrap();
+		assert_eq!(spawn_regions[0].coordinates, [Coordinate(15, 5)].iter().copied().collect());
+		assert_eq!(spawn_regions[0].name.as_deref(), Some("east"));
+		assert_eq!(named.region_names(), vec![Some("east".into()), None]);
+		assert_eq!(named.entrances_by_region[0], test_tileset.entrances_by_region[1]);
+
+		// Numbered regions are ordered by value, rather than as words.
+		named.metadata.insert(Coordinate(0, 2), RegionRule::LABEL, "10".into());
+		named.metadata.insert(Coordinate(15, 5), RegionRule::LABEL, 9.into());
+		named.set_region_rule(RegionRule::Merge);
+		assert_eq!(named.region_names(), vec![Some("9".into()), Some("10".into())]);
+		assert_eq!(named.entrances_by_region[1], test_tileset.entrances_by_region[0]);
+	}
+
+	#[test]
+	fn set_region_rule()
+	{
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1      2      3
+			vec![Spawn, Spawn, Empty, Empty], // 0
+			vec![Empty, Empty, Empty, Empty], // 1
+			vec![Empty, Empty, Empty, Core],  // 2
+		];
+		let mut test_tileset = Tileset::new(grid);
+		assert_eq!(test_tileset.entrances_by_region.len(), 1);
+
+		// The pads are only kept apart when they are labelled differently.
+		test_tileset.metadata.insert(Coordinate(0, 0), RegionRule::LABEL, json!("first wave"));
+		test_tileset.metadata.insert(Coordinate(1, 0), RegionRule::LABEL, json!("second wave"));
+		assert_eq!(test_tileset.entrances_by_region.len(), 1);
+
+		let start = Instant::now();
+		test_tileset.set_region_rule(RegionRule::Split);
+		println!("Tileset::set_region_rule {}us", Instant::now().duration_since(start).as_micros());
+
+		// Enemies cannot walk through the other pad to reach its entrances.
+		assert_eq!(test_tileset.entrances_by_region, vec![
+			[(Coordinate(0, 1), 1)].iter().copied().collect(),
+			[(Coordinate(1, 1), 1), (Coordinate(2, 0), 1)].iter().copied().collect(),
+		]);
+		assert_eq!(test_tileset.regions(Spawn).unwrap().len(), 2);
+
+		// Pads which only touch at a corner are always apart, even when moving diagonally.
+		#[rustfmt::skip]
+		let grid = vec![
+			// 0    1      2
+			vec![Spawn, Empty, Empty], // 0
+			vec![Empty, Spawn, Empty], // 1
+			vec![Empty, Empty, Core],  // 2
+		];
+		let test_tileset = Tileset::with_metric(grid, Metric::Chebyshev, true);
+		assert_eq!(
+			test_tileset.entrances_by_region[0],
+			[(Coordinate(1, 0), 1), (Coordinate(0, 1), 1)].iter().copied().collect()
+		);
+	}
+
+	#[test]
+	fn set_targeting()
+	{
+		let mut test_tileset = Tileset::new(vec![vec![Core, Empty, Spawn, Empty, Empty, Core]]);
+		assert_eq!(test_tileset.targets(0), None);
+
+		let start = Instant::now();
+		test_tileset.set_targeting(Targeting::NearestCore);
+		println!("Tileset::set_targeting {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(test_tileset.targets(0), Some(&[Coordinate(0, 0)].iter().copied().collect()));
+
+		// The targets follow the grid, but not a build.
+		test_tileset.set_tile(Coordinate(1, 0), Impass);
+		assert_eq!(test_tileset.targets(0), Some(&[Coordinate(5, 0)].iter().copied().collect()));
+
+		test_tileset.set_targeting(Targeting::AnyCore);
+		assert_eq!(test_tileset.targets(0), None);
+	}
+
+	#[test]
+	fn set_tile()
+	{
+		let mut test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let start = Instant::now();
+		let old_tile = test_tileset.set_tile(Coordinate(4, 2), Impass);
+		println!("Tileset::set_tile {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(old_tile, Some(Empty));
+		assert!(!test_tileset.entrances_by_region[0].contains_key(&Coordinate(4, 2)));
+
+		// Every kind of edit should leave the tileset as if it had been made from scratch.
+		[
+			(Coordinate(3, 2), Impass),
+			(Coordinate(15, 5), Empty),
+			(Coordinate(15, 0), Spawn),
+			(Coordinate(4, 1), Pass),
+			(Coordinate(20, 20), Empty),
+		]
+		.iter()
+		.for_each(|(coord, tile)| {
+			test_tileset.set_tile(*coord, *tile);
+			assert_eq!(test_tileset, Tileset::new(test_tileset.grid.clone()));
+		});
+	}
+
+	#[test]
+	fn upper_bounds()
+	{
+		let test_tileset = Tileset::new(PARK.iter().map(|inner| inner.to_vec()).collect());
+
+		let start = Instant::now();
+		let upper_bounds = test_tileset.upper_bounds(false);
+		println!("Tileset::upper_bounds {}us", Instant::now().duration_since(start).as_micros());
+
+		// Every passable tile of Park can be reached from its entrances, but some must be left out
+		// of any shortest path.
+		let passable = PARK.iter().flatten().filter(|tile| tile.is_passable()).count();
+		assert!(upper_bounds[0] < passable + 6 + 1);
+		assert!(test_tileset.upper_bounds(true)[0] < upper_bounds[0]);
+
+		[false, true].iter().for_each(|diagonals| {
+			let build =
+				Build::from_entrances_to_any_core(&test_tileset, *diagonals, Some(20), |_| ())
+					.unwrap();
+			assert!(
+				build.shortest_path_lengths(&test_tileset, *diagonals)[0].unwrap() <=
+					test_tileset.upper_bounds(*diagonals)[0]
+			);
+		});
 	}
 }
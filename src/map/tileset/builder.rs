@@ -0,0 +1,363 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{Error, RegionRule, Result, Targeting, Tileset};
+use crate::map::{Coordinate, Map, Metadata, Metric, ShortestPath, Tile};
+
+/// # Summary
+///
+/// Creates a [`Tileset`] after checking that it can be solved.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TilesetBuilder
+{
+	core_assignments: BTreeMap<usize, Coordinate>,
+	declared_entrances: BTreeMap<usize, Vec<Coordinate>>,
+	diagonals: bool,
+	entrance_overrides: BTreeMap<usize, HashMap<Coordinate, usize>>,
+	grid: Vec<Vec<Tile>>,
+	metadata: Metadata,
+	metric: Option<Metric>,
+	region_rule: RegionRule,
+	targeting: Targeting,
+}
+
+impl TilesetBuilder
+{
+	/// # Summary
+	///
+	/// Create the [`Tileset`].
+	///
+	/// # Errors
+	///
+	/// * [`Error::MissingTile`], if there is no [`Tile::Spawn`] or no [`Tile::Core`].
+	/// * [`Error::UnknownRegion`], if a declared entrance, entrance override, or core assignment is
+	///   for a region which does not exist.
+	/// * [`Error::InvalidEntrance`], if a declared entrance or entrance override is not a passable
+	///   tile on the grid.
+	/// * [`Error::NotCore`], if a region is assigned to a tile which is not a [`Tile::Core`].
+	/// * [`Error::Unreachable`], if some region has no path to a [`Tile::Core`].
+	pub fn build(self) -> Result<Tileset>
+	{
+		for tile in [Tile::Spawn, Tile::Core]
+		{
+			if !self.grid.iter().flatten().any(|t| *t == tile)
+			{
+				return Err(Error::MissingTile { tile });
+			}
+		}
+
+		let metric = self.metric.unwrap_or_else(|| Metric::for_movement(self.diagonals));
+		let mut tileset = Tileset::with_metric(self.grid, metric, self.diagonals);
+		tileset.metadata = self.metadata;
+
+		// Named regions are ordered by their name, so the regions must be found again.
+		if !self.region_rule.is_default() ||
+			tileset.metadata.with_key(RegionRule::LABEL).next().is_some()
+		{
+			tileset.set_region_rule(self.region_rule);
+		}
+
+		let mut entrance_overrides = self.entrance_overrides;
+		if !self.declared_entrances.is_empty()
+		{
+			let spawns = tileset.regions(Tile::Spawn)?;
+			for (region, entrances) in self.declared_entrances
+			{
+				let spawn = spawns.get(region).ok_or(Error::UnknownRegion { region })?;
+				entrance_overrides.entry(region).or_insert_with(|| {
+					entrances
+						.into_iter()
+						.map(|entrance| {
+							let distance =
+								spawn.entrances.get(&entrance).copied().unwrap_or_else(|| {
+									spawn
+										.coordinates
+										.iter()
+										.map(|coord| metric.distance(coord, &entrance))
+										.min()
+										.unwrap_or(0)
+								});
+
+							(entrance, distance)
+						})
+						.collect()
+				});
+			}
+		}
+
+		if !entrance_overrides.is_empty()
+		{
+			for (region, entrances) in entrance_overrides
+			{
+				if region >= tileset.entrances_by_region.len()
+				{
+					return Err(Error::UnknownRegion { region });
+				}
+
+				if let Some(coord) = entrances.keys().find(|coord| {
+					coord.get_from(&tileset.grid).is_none_or(|tile| !tile.is_passable())
+				})
+				{
+					return Err(Error::InvalidEntrance { coord: *coord });
+				}
+
+				// An overridden region should keep its entrances through `Tileset::set_tile`.
+				tileset.entrances_by_region[region] = entrances;
+				tileset.searched_by_region[region] = HashSet::new();
+			}
+
+			tileset.analyze();
+		}
+
+		for (region, coord) in &self.core_assignments
+		{
+			if *region >= tileset.entrances_by_region.len()
+			{
+				return Err(Error::UnknownRegion { region: *region });
+			}
+
+			if coord.get_from(&tileset.grid) != Some(Tile::Core)
+			{
+				return Err(Error::NotCore { coord: *coord });
+			}
+		}
+
+		if !(self.core_assignments.is_empty() && self.targeting.is_default())
+		{
+			tileset.core_assignments = self.core_assignments;
+			tileset.set_targeting(self.targeting);
+		}
+
+		if let Some(region) = ShortestPath::from_entrances_to_any_core(
+			&tileset,
+			Option::<&HashSet<_>>::None,
+			self.diagonals,
+		)
+		.iter()
+		.position(Option::is_none)
+		{
+			return Err(Error::Unreachable { region });
+		}
+
+		Ok(tileset)
+	}
+
+	/// # Summary
+	///
+	/// Make some spawn regions always walk to the core which has a given tile, whatever the
+	/// [`targeting`](TilesetBuilder::targeting). Each region is identified by its
+	/// [`Region::id`](super::Region).
+	pub fn core_assignments(
+		mut self,
+		assignments: impl IntoIterator<Item = (usize, Coordinate)>,
+	) -> Self
+	{
+		self.core_assignments.extend(assignments);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Use the given entrances for some regions instead of finding them from the grid, e.g. when
+	/// the game only opens some of the doors beside a spawn. Each region is identified by its
+	/// [`Region::id`](super::Region).
+	///
+	/// # Remarks
+	///
+	/// Each entrance is as far from the spawn as it would be if it were found from the grid. When
+	/// it would not be found (e.g. it is beyond another entrance), it is as far from the spawn as
+	/// the [`metric`](TilesetBuilder::metric) says. Any
+	/// [`entrance_overrides`](TilesetBuilder::entrance_overrides) for the same region are used
+	/// instead.
+	pub fn declared_entrances(
+		mut self,
+		entrances: impl IntoIterator<Item = (usize, Vec<Coordinate>)>,
+	) -> Self
+	{
+		self.declared_entrances.extend(entrances);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Whether enemies may move diagonally. This picks the default [`Metric`], and is used to
+	/// check that every region can reach a core.
+	pub fn diagonals(mut self, diagonals: bool) -> Self
+	{
+		self.diagonals = diagonals;
+		self
+	}
+
+	/// # Summary
+	///
+	/// Use the given entrances, and their distances from the spawn, for some regions instead of
+	/// finding them from the grid. Each region is identified by its [`Region::id`](super::Region).
+	pub fn entrance_overrides(
+		mut self,
+		overrides: impl IntoIterator<Item = (usize, HashMap<Coordinate, usize>)>,
+	) -> Self
+	{
+		self.entrance_overrides.extend(overrides);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Extra data to attach to some tiles of the grid.
+	pub fn metadata(mut self, metadata: Metadata) -> Self
+	{
+		self.metadata = metadata;
+		self
+	}
+
+	/// # Summary
+	///
+	/// How to measure the distance from each spawn to its entrances. Defaults to
+	/// [`Metric::for_movement`].
+	pub fn metric(mut self, metric: Metric) -> Self
+	{
+		self.metric = Some(metric);
+		self
+	}
+
+	/// # Summary
+	///
+	/// How to group touching spawns (and cores) into regions. Labels are read from the
+	/// [`metadata`](TilesetBuilder::metadata).
+	pub fn region_rule(mut self, region_rule: RegionRule) -> Self
+	{
+		self.region_rule = region_rule;
+		self
+	}
+
+	/// # Summary
+	///
+	/// Which cores the enemies of each region walk to. See [`Tileset::set_targeting`].
+	pub fn targeting(mut self, targeting: Targeting) -> Self
+	{
+		self.targeting = targeting;
+		self
+	}
+
+	/// # Summary
+	///
+	/// Start building a [`Tileset`] for the grid of a `map`, with everything else the `map` says
+	/// about its regions and cores.
+	///
+	/// # Remarks
+	///
+	/// Any build should be [taken](crate::map::Build::take_from) from the `map` first.
+	pub fn from_map(map: &Map, diagonals: bool) -> Self
+	{
+		Self::new(map.grid.clone())
+			.core_assignments(map.core_assignments.clone())
+			.declared_entrances(map.declared_entrances.clone())
+			.diagonals(diagonals)
+			.metadata(map.metadata.clone())
+			.region_rule(map.region_rule)
+			.targeting(map.targeting)
+	}
+
+	/// # Summary
+	///
+	/// Start building a [`Tileset`] for some `grid`.
+	pub fn new(grid: Vec<Vec<Tile>>) -> Self
+	{
+		Self {
+			core_assignments: BTreeMap::new(),
+			declared_entrances: BTreeMap::new(),
+			diagonals: false,
+			entrance_overrides: BTreeMap::new(),
+			grid,
+			metadata: Metadata::default(),
+			metric: None,
+			region_rule: RegionRule::default(),
+			targeting: Targeting::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{Error, TilesetBuilder};
+	use crate::map::{tileset::tests::PARK, Coordinate, Metric, Tile, Tile::*};
+
+	#[test]
+	fn build()
+	{
+		let park = || PARK.iter().map(|inner| inner.to_vec()).collect::<Vec<_>>();
+
+		let start = Instant::now();
+		let test_tileset = TilesetBuilder::new(park()).diagonals(true).build().unwrap();
+		println!("TilesetBuilder::build {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(test_tileset.metric, Metric::Chebyshev);
+
+		let overridden = TilesetBuilder::new(park())
+			.entrance_overrides([(0, [(Coordinate(4, 2), 0)].into_iter().collect())])
+			.build()
+			.unwrap();
+		assert_eq!(
+			overridden.entrances_by_region[0],
+			[(Coordinate(4, 2), 0)].into_iter().collect()
+		);
+
+		// Declared entrances keep the distance they would have had, or are measured from the spawn.
+		let declared = TilesetBuilder::new(park())
+			.declared_entrances([(0, vec![Coordinate(4, 2), Coordinate(5, 2)])])
+			.build()
+			.unwrap();
+		assert_eq!(
+			declared.entrances_by_region[0],
+			[(Coordinate(4, 2), 4), (Coordinate(5, 2), 5)].into_iter().collect()
+		);
+
+		assert!(matches!(
+			TilesetBuilder::new(park()).declared_entrances([(1, Vec::new())]).build(),
+			Err(Error::UnknownRegion { region: 1 })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(park()).entrance_overrides([(1, Default::default())]).build(),
+			Err(Error::UnknownRegion { region: 1 })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(park())
+				.entrance_overrides([(0, [(Coordinate(0, 0), 0)].into_iter().collect())])
+				.build(),
+			Err(Error::InvalidEntrance { coord: Coordinate(0, 0) })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(vec![vec![Spawn, Empty, Empty]]).build(),
+			Err(Error::MissingTile { tile: Core })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(vec![vec![Spawn, Empty, Impass, Core]]).build(),
+			Err(Error::Unreachable { region: 0 })
+		));
+
+		// A region may be pinned to a core which is not its nearest, as long as it can reach it.
+		let two_cores = |far: Tile| vec![vec![Core, Empty, Spawn, Empty, far, Core]];
+		let pinned = TilesetBuilder::new(two_cores(Empty))
+			.core_assignments([(0, Coordinate(5, 0))])
+			.build()
+			.unwrap();
+		assert_eq!(pinned.targets(0), Some(&[Coordinate(5, 0)].into_iter().collect()));
+
+		assert!(matches!(
+			TilesetBuilder::new(two_cores(Empty)).core_assignments([(1, Coordinate(5, 0))]).build(),
+			Err(Error::UnknownRegion { region: 1 })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(two_cores(Empty)).core_assignments([(0, Coordinate(4, 0))]).build(),
+			Err(Error::NotCore { coord: Coordinate(4, 0) })
+		));
+		assert!(matches!(
+			TilesetBuilder::new(two_cores(Impass))
+				.core_assignments([(0, Coordinate(5, 0))])
+				.build(),
+			Err(Error::Unreachable { region: 0 })
+		));
+	}
+}
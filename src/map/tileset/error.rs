@@ -2,16 +2,46 @@ use std::result::Result as StdResult;
 
 use snafu::Snafu;
 
-use super::Tile;
+use super::{Coordinate, Tile};
 
 #[derive(Debug, Snafu)]
 pub enum Error
 {
+	#[snafu(display("Entrance {:?} is not a passable tile on the grid", coord))]
+	InvalidEntrance
+	{
+		coord: Coordinate
+	},
+
+	#[snafu(display("The grid has no {:?} tiles", tile))]
+	MissingTile
+	{
+		tile: Tile
+	},
+
+	#[snafu(display("{:?} is not a core", coord))]
+	NotCore
+	{
+		coord: Coordinate
+	},
+
 	#[snafu(display("Tried to make a region out of non-region tile {:?}", tile))]
 	NotRegion
 	{
 		tile: Tile
 	},
+
+	#[snafu(display("There is no spawn region {}", region))]
+	UnknownRegion
+	{
+		region: usize
+	},
+
+	#[snafu(display("Spawn region {} has no path to a core", region))]
+	Unreachable
+	{
+		region: usize
+	},
 }
 
 pub type Result<T> = StdResult<T, Error>;
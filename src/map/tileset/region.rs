@@ -0,0 +1,33 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::map::Coordinate;
+
+/// # Summary
+///
+/// A group of touching [`Tile`](crate::map::Tile)s of the same kind, such as a spawn room.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Region
+{
+	/// # Summary
+	///
+	/// Every [`Coordinate`] which is part of the region.
+	pub coordinates: HashSet<Coordinate>,
+
+	/// # Summary
+	///
+	/// The [`Tile::Empty`](crate::map::Tile::Empty)s which can be walked to from the region
+	/// without crossing another, and how far away each one is.
+	pub entrances: HashMap<Coordinate, usize>,
+
+	/// # Summary
+	///
+	/// The position of the region: named regions first, by name, then the rest in reading order of
+	/// their first tile. For [`Tile::Spawn`](crate::map::Tile::Spawn)s, this is also its index in
+	/// [`Tileset::entrances_by_region`](super::Tileset::entrances_by_region).
+	pub id: usize,
+
+	/// # Summary
+	///
+	/// The [name](super::Tileset::region_names) of the region, if it has one.
+	pub name: Option<String>,
+}
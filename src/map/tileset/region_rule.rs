@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::map::{Coordinate, Metadata};
+
+/// # Summary
+///
+/// How touching [`Tile`](crate::map::Tile)s of the same kind are grouped into [`Region`]s.
+///
+/// # Remarks
+///
+/// Tiles which only touch at a corner are never in the same [`Region`], whichever rule is used.
+///
+/// [`Region`]: super::Region
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegionRule
+{
+	/// # Summary
+	///
+	/// Tiles which share an edge are in the same region.
+	#[default]
+	Merge,
+
+	/// # Summary
+	///
+	/// Tiles which share an edge are only in the same region if they have the same
+	/// [`RegionRule::LABEL`] in the [`Metadata`] (or if neither has one), e.g. so that touching
+	/// spawn pads of different waves are kept apart.
+	Split,
+}
+
+impl RegionRule
+{
+	/// # Summary
+	///
+	/// The [`Metadata`] key which tells [`RegionRule::Split`] regions apart. It also names a
+	/// region, which decides its [order](crate::map::tileset::Tileset::region_names).
+	pub const LABEL: &'static str = "region";
+
+	/// # Summary
+	///
+	/// Whether this is the [`Default`] rule.
+	pub fn is_default(&self) -> bool
+	{
+		*self == Self::default()
+	}
+
+	/// # Summary
+	///
+	/// Whether `a` and `b`, which share an edge and are the same kind of tile, are in the same
+	/// region according to some `metadata`.
+	pub fn joins(&self, metadata: &Metadata, a: &Coordinate, b: &Coordinate) -> bool
+	{
+		match self
+		{
+			RegionRule::Merge => true,
+			RegionRule::Split =>
+			{
+				metadata.get_value(a, Self::LABEL) == metadata.get_value(b, Self::LABEL)
+			},
+		}
+	}
+}
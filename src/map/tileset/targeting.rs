@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// # Summary
+///
+/// Which [`Tile::Core`](crate::map::Tile::Core)s the enemies of each spawn region walk to.
+#[derive(
+	Copy, Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Targeting
+{
+	/// # Summary
+	///
+	/// Enemies walk to whichever core is closest through the current build.
+	#[default]
+	AnyCore,
+
+	/// # Summary
+	///
+	/// Enemies only ever walk to the core region which was closest to their spawn before any
+	/// blocks were placed, even if a build makes another core closer.
+	NearestCore,
+}
+
+impl Targeting
+{
+	/// # Summary
+	///
+	/// Whether this is the [`Default`] targeting.
+	pub fn is_default(&self) -> bool
+	{
+		*self == Self::default()
+	}
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Coordinate, EnemyClass, Strategy};
+
+/// # Summary
+///
+/// Something which went wrong with a solve, but not badly enough to stop it.
+///
+/// # Remarks
+///
+/// These are written alongside a solved [`Map`](super::Map), so that they can be noticed by
+/// programs reading the result. Their [`Display`](fmt::Display) is meant for people.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum Warning
+{
+	/// # Summary
+	///
+	/// The solve was cancelled before it finished, so the build is only the best one found up to
+	/// that point.
+	Cancelled,
+
+	/// # Summary
+	///
+	/// The maximum number of `blocks` was placed, so there may have been room for a longer build.
+	MaxBlocks
+	{
+		blocks: usize
+	},
+
+	/// # Summary
+	///
+	/// Some `strategy` ran out of time before it finished, so it may have found a longer build
+	/// with more.
+	OutOfTime
+	{
+		strategy: Strategy
+	},
+
+	/// # Summary
+	///
+	/// Some `row` of the grid was shorter than the others, so it was padded with
+	/// [`Tile::Impass`](super::Tile::Impass).
+	RaggedRow
+	{
+		row: usize
+	},
+
+	/// # Summary
+	///
+	/// No block could make the path of some spawn `region` any longer.
+	Unchanged
+	{
+		region: usize
+	},
+
+	/// # Summary
+	///
+	/// The tile at some `coordinate` had a `code` which is not any [`Tile`](super::Tile), so it
+	/// was read as a [`Tile::Impass`](super::Tile::Impass).
+	UnknownTile
+	{
+		coordinate: Coordinate, code: String
+	},
+
+	/// # Summary
+	///
+	/// Some `class` of enemy cannot reach a core from a spawn `region`.
+	Unreachable
+	{
+		class: EnemyClass, region: usize
+	},
+
+	/// # Summary
+	///
+	/// No spawn can reach some [`Tile::Core`](super::Tile::Core) `region`.
+	UnreachableCore
+	{
+		region: usize
+	},
+}
+
+impl fmt::Display for Warning
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			Warning::Cancelled => f.write_str("the solve was cancelled before it was finished"),
+			Warning::MaxBlocks { blocks } =>
+			{
+				write!(f, "all {} blocks were placed before the build was finished", blocks)
+			},
+			Warning::OutOfTime { strategy } =>
+			{
+				write!(f, "the {} strategy ran out of time before it was finished", strategy)
+			},
+			Warning::RaggedRow { row } =>
+			{
+				write!(f, "row {} was shorter than the others, and was padded with Impass", row)
+			},
+			Warning::Unchanged { region } =>
+			{
+				write!(f, "no block could lengthen the path of spawn region {}", region)
+			},
+			Warning::UnknownTile { coordinate, code } => write!(
+				f,
+				"{:?} at ({}, {}) is not a tile, and was read as Impass",
+				code, coordinate.0, coordinate.1
+			),
+			Warning::Unreachable { class, region } =>
+			{
+				write!(f, "{} enemies of spawn region {} cannot reach a core", class, region)
+			},
+			Warning::UnreachableCore { region } =>
+			{
+				write!(f, "core region {} cannot be reached from any spawn", region)
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use serde_json::json;
+
+	use super::{EnemyClass, Warning};
+
+	#[test]
+	fn serde()
+	{
+		let warning = Warning::Unreachable { class: EnemyClass::Ground, region: 2 };
+
+		let start = Instant::now();
+		let json = serde_json::to_value(&warning).unwrap();
+		println!("Warning::serialize {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(json, json!({"kind": "unreachable", "class": "ground", "region": 2}));
+		assert_eq!(serde_json::from_value::<Warning>(json).unwrap(), warning);
+		assert_eq!(warning.to_string(), "ground enemies of spawn region 2 cannot reach a core");
+	}
+}
@@ -0,0 +1,369 @@
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, HashSet},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{build::Result, tileset::Tileset, Build, Coordinate, Sensitivity, SolverSession};
+
+/// # Summary
+///
+/// How much a block costs, in hundredths, so that refunds can be any percentage.
+const BLOCK_COST: usize = 100;
+
+/// # Summary
+///
+/// What to do with the blocks gained before a single wave of a [`WavePlan`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Wave
+{
+	/// # Summary
+	///
+	/// The blocks to place, in reading order.
+	pub added: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// How many blocks have been gained so far.
+	pub budget: usize,
+
+	/// # Summary
+	///
+	/// The blocks to sell, in reading order. They are sold before any are placed.
+	pub removed: Vec<Coordinate>,
+
+	/// # Summary
+	///
+	/// How much shorter the paths of the spawning regions are, in total, than if the build had
+	/// been solved from scratch with as many blocks.
+	pub shortfall: usize,
+
+	/// # Summary
+	///
+	/// The length of each region's shortest path during the wave.
+	pub shortest_path_length: Vec<Option<usize>>,
+
+	/// # Summary
+	///
+	/// How many more blocks could be afforded after this wave.
+	pub spare: usize,
+}
+
+/// # Summary
+///
+/// A build which is put together a few blocks at a time, as they are gained between waves, so
+/// that it is valid (and as long as it can be) at every wave rather than only at the end.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WavePlan
+{
+	/// # Summary
+	///
+	/// The finished build.
+	pub build: Build,
+
+	/// # Summary
+	///
+	/// Each [`Wave`], in the order they are played.
+	pub waves: Vec<Wave>,
+}
+
+/// # Summary
+///
+/// How blocks are gained and sold over the waves of a map, and when each spawn region starts
+/// sending enemies.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WaveSchedule
+{
+	/// # Summary
+	///
+	/// The first wave (counted from `0`) in which some regions spawn enemies. Regions which are
+	/// not listed spawn from the first wave.
+	pub active_from: BTreeMap<usize, usize>,
+
+	/// # Summary
+	///
+	/// How many blocks are gained before each wave.
+	pub income: Vec<usize>,
+
+	/// # Summary
+	///
+	/// The percentage of a block's cost which is given back when it is sold.
+	pub refund: usize,
+
+	/// # Summary
+	///
+	/// The most blocks which may be sold before each wave, to make room for better ones. If
+	/// [`None`], blocks are only sold once they no longer lengthen any path, however many that is.
+	pub sells: Option<usize>,
+}
+
+impl WaveSchedule
+{
+	/// # Summary
+	///
+	/// Whether some `region` spawns enemies in a `wave`.
+	fn is_active(&self, region: usize, wave: usize) -> bool
+	{
+		self.active_from.get(&region).is_none_or(|first| *first <= wave)
+	}
+
+	/// # Summary
+	///
+	/// A schedule which gains some `income` of blocks before each wave, where every region spawns
+	/// from the first wave and blocks are refunded in full.
+	pub fn new(income: Vec<usize>) -> Self
+	{
+		Self { active_from: BTreeMap::new(), income, refund: 100, sells: None }
+	}
+}
+
+impl WavePlan
+{
+	/// # Summary
+	///
+	/// Continue a `build` with at most `max_blocks`, only placing blocks for `active` regions.
+	fn extend(
+		tileset: &Tileset,
+		diagonals: bool,
+		build: Build,
+		max_blocks: usize,
+		active: &[bool],
+	) -> Result<Build>
+	{
+		let mut session = SolverSession::resume(tileset, build, diagonals, Some(max_blocks))?;
+		session.set_split(
+			active
+				.iter()
+				.map(|a| {
+					if *a
+					{
+						max_blocks
+					}
+					else
+					{
+						0
+					}
+				})
+				.collect(),
+		);
+		while session.step().is_some()
+		{}
+
+		Ok(session.into_build())
+	}
+
+	/// # Summary
+	///
+	/// Plan a build for a `tileset` over the waves of a `schedule`.
+	///
+	/// # Remarks
+	///
+	/// Each wave keeps the build of the last, and places blocks on it for the regions which spawn
+	/// that wave until they run out or no longer lengthen any path. Blocks which go unused are
+	/// carried into the next wave.
+	///
+	/// When the `schedule` allows sells, the blocks which add the least to the spawning regions'
+	/// paths are tried being sold first, and the wave keeps whichever number of sells makes the
+	/// longest paths. This lets the build be reshaped when a new region starts spawning.
+	///
+	/// # Errors
+	///
+	/// * [`Error::NoPathToCore`](super::build::Error::NoPathToCore), if some region cannot reach a
+	///   core to begin with.
+	pub fn of(tileset: &Tileset, diagonals: bool, schedule: &WaveSchedule) -> Result<Self>
+	{
+		let regions = tileset.entrances_by_region.len();
+
+		let mut build = Build { blocks: HashSet::new() };
+		let mut budget = 0;
+		let mut funds = 0;
+		let mut waves = Vec::with_capacity(schedule.income.len());
+
+		for (wave, income) in schedule.income.iter().enumerate()
+		{
+			budget += income;
+			funds += income * BLOCK_COST;
+
+			let active: Vec<_> =
+				(0..regions).map(|region| schedule.is_active(region, wave)).collect();
+			let total = |b: &Build| -> usize {
+				b.shortest_path_lengths(tileset, diagonals)
+					.into_iter()
+					.zip(&active)
+					.filter_map(|(length, a)| length.filter(|_| *a))
+					.sum()
+			};
+
+			// The blocks which cost the spawning regions the least path to sell, in order.
+			let mut cheapest: Vec<_> = Sensitivity::of(tileset, &build, diagonals, 1.0)
+				.impacts
+				.into_iter()
+				.map(|impact| {
+					let kept: usize = impact
+						.shortest_path_length
+						.into_iter()
+						.zip(&active)
+						.filter_map(|(length, a)| length.filter(|_| *a))
+						.sum();
+					(Reverse(kept), impact.block)
+				})
+				.collect();
+			cheapest.sort_unstable();
+
+			let mut best: Option<(Build, usize, usize)> = None;
+			for sells in 0..=schedule.sells.unwrap_or(0).min(cheapest.len())
+			{
+				let mut start = build.clone();
+				cheapest.iter().take(sells).for_each(|(_, block)| {
+					start.blocks.remove(block);
+				});
+
+				// Blocks the solver takes away are sold too, so try with fewer blocks until the
+				// refunds cover everything placed.
+				let mut affordable = (funds + sells * schedule.refund) / BLOCK_COST;
+				let (candidate, left) = loop
+				{
+					let candidate = Self::extend(
+						tileset,
+						diagonals,
+						start.clone(),
+						start.blocks.len() + affordable,
+						&active,
+					)?;
+					let sold = build.blocks.difference(&candidate.blocks).count();
+					let placed = candidate.blocks.difference(&build.blocks).count();
+					match (funds + sold * schedule.refund).checked_sub(placed * BLOCK_COST)
+					{
+						Some(left) => break (candidate, left),
+						None => affordable -= 1,
+					}
+				};
+
+				let sold = build.blocks.difference(&candidate.blocks).count();
+				if schedule.sells.is_some_and(|limit| sold > limit)
+				{
+					continue;
+				}
+
+				if best.as_ref().is_none_or(|(b, ..)| total(&candidate) > total(b))
+				{
+					best = Some((candidate, left, sold));
+				}
+			}
+
+			let before = build;
+			let (after, left) = match best
+			{
+				Some((after, left, _)) => (after, left),
+				None => (before.clone(), funds),
+			};
+			build = after;
+			funds = left;
+
+			let sorted = |mut blocks: Vec<Coordinate>| {
+				blocks.sort_unstable_by_key(|coord| (coord.1, coord.0));
+				blocks
+			};
+			let from_scratch = Self::extend(
+				tileset,
+				diagonals,
+				Build { blocks: HashSet::new() },
+				build.blocks.len() + funds / BLOCK_COST,
+				&active,
+			)?;
+
+			waves.push(Wave {
+				added: sorted(build.blocks.difference(&before.blocks).copied().collect()),
+				budget,
+				removed: sorted(before.blocks.difference(&build.blocks).copied().collect()),
+				shortfall: total(&from_scratch).saturating_sub(total(&build)),
+				shortest_path_length: build.shortest_path_lengths(tileset, diagonals),
+				spare: funds / BLOCK_COST,
+			});
+		}
+
+		Ok(Self { build, waves })
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::HashSet, time::Instant};
+
+	use super::{Tileset, WavePlan, WaveSchedule};
+	use crate::map::tileset::tests::PARK_TWO_SPAWN;
+
+	#[test]
+	fn of()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		let start = Instant::now();
+		let plan = WavePlan::of(&test_tileset, false, &WaveSchedule::new(vec![4, 3, 3])).unwrap();
+		println!("WavePlan::of {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(plan.waves.iter().map(|wave| wave.budget).collect::<Vec<_>>(), vec![4, 7, 10]);
+
+		// Replaying the waves gives the finished build, which never goes over budget or cuts off a
+		// region along the way.
+		let mut blocks = HashSet::new();
+		plan.waves.iter().for_each(|wave| {
+			blocks.extend(wave.added.iter().copied());
+			wave.removed.iter().for_each(|coord| {
+				blocks.remove(coord);
+			});
+
+			assert!(blocks.len() + wave.spare <= wave.budget);
+			assert!(wave.shortest_path_length.iter().all(Option::is_some));
+		});
+		assert_eq!(blocks, plan.build.blocks);
+
+		// A region which only spawns later may have its build reshaped, but never by more sells
+		// than are allowed.
+		let schedule = WaveSchedule {
+			active_from: [(1, 2)].into_iter().collect(),
+			refund: 50,
+			sells: Some(2),
+			..WaveSchedule::new(vec![6, 2, 4])
+		};
+		let plan = WavePlan::of(&test_tileset, false, &schedule).unwrap();
+		plan.waves.iter().for_each(|wave| {
+			assert!(wave.removed.len() <= 2);
+			assert!(wave.shortest_path_length.iter().all(Option::is_some));
+		});
+	}
+
+	#[test]
+	fn sells()
+	{
+		let test_tileset =
+			Tileset::new(PARK_TWO_SPAWN.iter().map(|inner| inner.to_vec()).collect());
+
+		// Every block is placed for region 1, and region 0 starts spawning once there are no more
+		// blocks to gain.
+		let schedule = |refund: usize, sells: Option<usize>| WaveSchedule {
+			active_from: [(0, 1)].into_iter().collect(),
+			refund,
+			sells,
+			..WaveSchedule::new(vec![8, 0])
+		};
+		let second_wave =
+			|schedule| WavePlan::of(&test_tileset, false, &schedule).unwrap().waves.remove(1);
+
+		let kept = second_wave(schedule(100, None));
+		assert!(kept.added.is_empty() && kept.removed.is_empty());
+
+		// Selling blocks for a full refund moves them to lengthen region 0's path as well.
+		let reshaped = second_wave(schedule(100, Some(4)));
+		assert!(!reshaped.removed.is_empty() && reshaped.removed.len() <= 4);
+		assert_eq!(reshaped.added.len(), reshaped.removed.len());
+		assert!(reshaped.shortest_path_length[0] > kept.shortest_path_length[0]);
+		assert_eq!(reshaped.shortest_path_length[1], kept.shortest_path_length[1]);
+
+		// Without any refund, selling only loses blocks.
+		assert_eq!(second_wave(schedule(0, Some(4))), kept);
+	}
+}
@@ -0,0 +1,83 @@
+// `rayon`'s parallel iterators, or ordinary `Iterator`s when the `parallel` feature is disabled
+// (e.g. for targets without threads, such as WASM). Without the feature, most methods of the
+// `ParallelIterator` traits are already on `Iterator`, so modules which import them for `rayon`
+// allow the import to go unused.
+#[cfg(feature = "parallel")]
+pub use rayon::iter::{
+	IndexedParallelIterator,
+	IntoParallelIterator,
+	IntoParallelRefIterator,
+	ParallelIterator,
+};
+#[cfg(not(feature = "parallel"))]
+pub use sequential::{
+	IntoParallelIterator,
+	IntoParallelRefIterator,
+	ParallelIterator,
+	ParallelIterator as IndexedParallelIterator,
+};
+
+#[cfg(not(feature = "parallel"))]
+mod sequential
+{
+	/// # Summary
+	///
+	/// Stands in for [`rayon::iter::IntoParallelIterator`].
+	pub trait IntoParallelIterator: IntoIterator + Sized
+	{
+		/// # Summary
+		///
+		/// See [`IntoIterator::into_iter`].
+		fn into_par_iter(self) -> Self::IntoIter
+		{
+			self.into_iter()
+		}
+	}
+
+	impl<I: IntoIterator> IntoParallelIterator for I {}
+
+	/// # Summary
+	///
+	/// Stands in for [`rayon::iter::IntoParallelRefIterator`].
+	pub trait IntoParallelRefIterator<'data>
+	{
+		type Iter: Iterator;
+
+		/// # Summary
+		///
+		/// Iterate over references to the items of this collection.
+		fn par_iter(&'data self) -> Self::Iter;
+	}
+
+	impl<'data, I: 'data + ?Sized> IntoParallelRefIterator<'data> for I
+	where
+		&'data I: IntoIterator,
+	{
+		type Iter = <&'data I as IntoIterator>::IntoIter;
+
+		fn par_iter(&'data self) -> Self::Iter
+		{
+			self.into_iter()
+		}
+	}
+
+	/// # Summary
+	///
+	/// Stands in for [`rayon::iter::ParallelIterator`] (and `IndexedParallelIterator`). Apart
+	/// from those below, its methods are those of [`Iterator`].
+	pub trait ParallelIterator: Iterator + Sized
+	{
+		/// # Summary
+		///
+		/// See [`Iterator::reduce`].
+		fn reduce_with(
+			self,
+			op: impl FnMut(Self::Item, Self::Item) -> Self::Item,
+		) -> Option<Self::Item>
+		{
+			self.reduce(op)
+		}
+	}
+
+	impl<I: Iterator> ParallelIterator for I {}
+}
@@ -0,0 +1,276 @@
+mod builder;
+mod error;
+mod options;
+mod time_slice;
+
+pub use builder::SolveOptionsBuilder;
+pub use error::{Error, Result};
+pub use options::SolveOptions;
+pub use time_slice::TimeSlice;
+
+use crate::map::{Build, CancellationToken, Map, Progress, Warning};
+
+/// # Summary
+///
+/// A [`Map`] which was [`solve`]d, and the [`Build`] which was applied to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution
+{
+	pub build: Build,
+
+	/// # Summary
+	///
+	/// The `map` which was solved, with the `build` applied and its
+	/// [`Stats`](crate::map::Stats) filled in.
+	pub map: Map,
+
+	/// # Summary
+	///
+	/// The [`Objective::score`](crate::map::Objective::score) of the `build`.
+	pub score: usize,
+}
+
+/// # Summary
+///
+/// Find a [`Build`] for a `map`, and apply it.
+///
+/// # Errors
+///
+/// * [`Error::Tileset`], if the `map` has no spawn or core.
+/// * [`Error::Build`], if some region has no path to a core.
+/// * [`Error::Constraints`], if the [`SolveOptions::constraints`] do not fit the `map`.
+pub fn solve(map: Map, options: SolveOptions) -> Result<Solution>
+{
+	solve_with_progress(map, options, |_| ())
+}
+
+/// # Summary
+///
+/// [`solve_with_progress`], stopping early once the `cancellation` is cancelled (e.g. from
+/// another thread).
+///
+/// # Remarks
+///
+/// A cancelled solve still finishes as usual with the best [`Build`] found before it stopped, and
+/// has a [`Warning::Cancelled`].
+///
+/// # Errors
+///
+/// See [`solve`].
+pub fn solve_cancellable(
+	mut map: Map,
+	options: SolveOptions,
+	cancellation: &CancellationToken,
+	on_progress: impl FnMut(&Progress) + Send,
+) -> Result<Solution>
+{
+	let tileset = options.tileset(&map)?;
+	let objective = options.objective(&map);
+	let (build, strategies) = options.search(&tileset, &objective, cancellation, on_progress)?;
+	if cancellation.is_cancelled()
+	{
+		map.warnings.push(Warning::Cancelled);
+	}
+
+	options.finish(map, tileset, &objective, build, strategies)
+}
+
+/// # Summary
+///
+/// [`solve`] a `map`, calling `on_progress` each time a block is placed.
+///
+/// # Errors
+///
+/// See [`solve`].
+pub fn solve_with_progress(
+	map: Map,
+	options: SolveOptions,
+	on_progress: impl FnMut(&Progress) + Send,
+) -> Result<Solution>
+{
+	solve_cancellable(map, options, &CancellationToken::new(), on_progress)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::time::Instant;
+
+	use super::{solve, solve_cancellable, Error, SolveOptions};
+	use crate::map::{
+		tileset::tests::PARK,
+		Build,
+		CancellationToken,
+		Coordinate,
+		Map,
+		Strategy,
+		Tile,
+		Warning,
+	};
+
+	#[test]
+	fn solve_cancelled()
+	{
+		let map: Map = serde_json::from_value(serde_json::json!({
+			"name": "park",
+			"grid": PARK,
+		}))
+		.unwrap();
+
+		[Strategy::Priority, Strategy::RoundRobin].into_iter().for_each(|strategy| {
+			let options = SolveOptions { strategy, ..Default::default() };
+			let finished = solve(map.clone(), options.clone()).unwrap();
+			assert!(finished.map.warnings.is_empty());
+
+			// Cancelling from another thread (here, while reporting progress) stops the solve
+			// between placements, and it finishes with the blocks placed so far.
+			let cancellation = CancellationToken::new();
+			let cancelled = solve_cancellable(map.clone(), options, &cancellation, |progress| {
+				if progress.blocks >= 2
+				{
+					cancellation.cancel();
+				}
+			})
+			.unwrap();
+
+			assert_eq!(cancelled.map.warnings, vec![Warning::Cancelled]);
+			assert!(cancelled.build.blocks.len() <= 3);
+			assert!(cancelled.build.blocks.len() < finished.build.blocks.len());
+			assert_eq!(Build::take_from(&mut cancelled.map.grid.clone()), cancelled.build);
+		});
+	}
+
+	#[test]
+	fn solve_core_assignments()
+	{
+		// The spawn is nearer the core on the left, but is pinned to the one on the right. No
+		// blocks are placed, so nothing else leads it there.
+		let map: Map = serde_json::from_value(serde_json::json!({
+			"name": "pinned",
+			"grid": [["Core", "Empty", "Spawn", "Empty", "Empty", "Core"]],
+			"core_assignments": {"0": [5, 0]},
+		}))
+		.unwrap();
+
+		let options = SolveOptions { max_blocks: Some(0), ..Default::default() };
+		let solution = solve(map, options).unwrap();
+		let region = &solution.map.regions[0];
+		assert_eq!(region.path.last(), Some(&Coordinate(5, 0)));
+		assert_eq!(region.baseline_length, Some(4));
+		assert_eq!(solution.map.stats.unwrap().core_by_region, vec![Some(Coordinate(5, 0))]);
+	}
+
+	#[test]
+	fn solve_declared_entrances()
+	{
+		let solve_map = |declared_entrances: serde_json::Value| {
+			let map: Map = serde_json::from_value(serde_json::json!({
+				"name": "door",
+				"grid": [["Empty", "Spawn", "Empty", "Core"], ["Empty", "Empty", "Empty", "Empty"]],
+				"declared_entrances": declared_entrances,
+			}))
+			.unwrap();
+			solve(map, SolveOptions { max_blocks: Some(0), ..Default::default() }).unwrap().map
+		};
+
+		let detected = solve_map(serde_json::json!({}));
+		assert_eq!(detected.regions[0].path.first(), Some(&Coordinate(2, 0)));
+
+		// Only the door on the far side of the spawn is open, so the enemies have to walk around.
+		let declared = solve_map(serde_json::json!({"0": [[0, 0]]}));
+		assert_eq!(declared.regions[0].entrances, vec![Coordinate(0, 0)]);
+		assert_eq!(declared.regions[0].path.first(), Some(&Coordinate(0, 0)));
+		assert!(declared.regions[0].final_length > detected.regions[0].final_length);
+	}
+
+	#[test]
+	fn solve_map()
+	{
+		let map: Map = serde_json::from_value(serde_json::json!({
+			"name": "park",
+			"grid": PARK,
+		}))
+		.unwrap();
+
+		let start = Instant::now();
+		let solution = solve(map.clone(), SolveOptions::default()).unwrap();
+		println!("solve {}ms", Instant::now().duration_since(start).as_millis());
+
+		// The build is on the map, and nothing else about the grid changed.
+		assert_eq!(Build::take_from(&mut solution.map.grid.clone()), solution.build);
+		solution.map.grid.iter().flatten().zip(map.grid.iter().flatten()).for_each(
+			|(after, before)| {
+				assert!(after == before || matches!(after, Tile::Block | Tile::TowerBlock))
+			},
+		);
+
+		let lengths = solution.map.shortest_path_length.unwrap();
+		assert_eq!(solution.score, lengths.iter().flatten().sum::<usize>());
+		assert!(solution.map.stats.is_some());
+	}
+
+	#[test]
+	fn solve_named_regions()
+	{
+		let solve_grid = |grid: serde_json::Value, east: [usize; 2]| {
+			let map: Map = serde_json::from_value(serde_json::json!({
+				"name": "named",
+				"grid": grid,
+				"metadata": [{"coordinate": east, "properties": {"region": "east"}}],
+			}))
+			.unwrap();
+			solve(map, SolveOptions { max_blocks: Some(0), ..Default::default() }).unwrap().map
+		};
+
+		let row = ["Spawn", "Empty", "Core", "Empty", "Empty", "Spawn"];
+		let before = solve_grid(serde_json::json!([row]), [5, 0]);
+		assert_eq!(before.regions[0].name.as_deref(), Some("east"));
+		assert_eq!(before.regions[0].entrances, vec![Coordinate(4, 0)]);
+
+		// A spawn added above comes before the unnamed region, but not before the named one.
+		let added = ["Core", "Empty", "Spawn", "Impass", "Impass", "Impass"];
+		let after = solve_grid(serde_json::json!([added, row]), [5, 1]);
+		assert_eq!(after.regions[0].name.as_deref(), Some("east"));
+		assert_eq!(after.regions[0].entrances, vec![Coordinate(4, 1)]);
+		assert_eq!(after.regions[2].entrances, vec![Coordinate(1, 1)]);
+		assert_eq!(after.shortest_path_length.unwrap()[0], before.shortest_path_length.unwrap()[0]);
+		assert_eq!(after.stats.unwrap().region_names, vec![Some("east".into()), None, None]);
+	}
+
+	#[test]
+	fn solve_session()
+	{
+		let map: Map = serde_json::from_value(serde_json::json!({
+			"name": "park",
+			"grid": PARK,
+		}))
+		.unwrap();
+
+		let cancellation = CancellationToken::new();
+		let session = |options: SolveOptions| {
+			let tileset = options.tileset(&map).unwrap();
+			let objective = options.objective(&map);
+			options.session(&tileset, &objective, None, &cancellation).map(|mut session| {
+				while session.step().is_some()
+				{}
+				session.into_build()
+			})
+		};
+
+		// Only a search which places one block at a time can be stepped through.
+		let options = SolveOptions { strategy: Strategy::Priority, ..Default::default() };
+		assert!(matches!(
+			session(options.clone()),
+			Err(Error::NotStepwise { strategy: Strategy::Priority })
+		));
+
+		// Unless the blocks are split, in which case the split is kept to.
+		let split = SolveOptions { max_blocks: Some(4), split: true, ..options };
+		let build = session(split.clone()).unwrap();
+		assert_eq!(build, solve(map.clone(), split).unwrap().build);
+
+		let options = SolveOptions { max_blocks: Some(3), ..Default::default() };
+		let build = session(options.clone()).unwrap();
+		assert_eq!(build, solve(map.clone(), options).unwrap().build);
+	}
+}
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use super::{SolveOptions, TimeSlice};
+use crate::map::{Constraints, Formula, Metric, MovementRules, Strategy};
+
+/// # Summary
+///
+/// Creates [`SolveOptions`], starting from the [`Default`] for anything which is not set.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolveOptionsBuilder
+{
+	options: SolveOptions,
+}
+
+impl SolveOptionsBuilder
+{
+	/// # Summary
+	///
+	/// Create the [`SolveOptions`].
+	pub fn build(self) -> SolveOptions
+	{
+		self.options
+	}
+
+	/// # Summary
+	///
+	/// Whether to include the [`Build::to_code`](crate::map::Build::to_code) in the
+	/// [`Stats`](crate::map::Stats).
+	pub fn build_code(mut self, build_code: bool) -> Self
+	{
+		self.options.build_code = build_code;
+		self
+	}
+
+	/// # Summary
+	///
+	/// How many moves away from the shortest path to look when choosing where to place each block.
+	/// See [`Build::find_scored_block_placement`](crate::map::Build::find_scored_block_placement).
+	pub fn candidate_scope(mut self, candidate_scope: usize) -> Self
+	{
+		self.options.candidate_scope = Some(candidate_scope);
+		self
+	}
+
+	/// # Summary
+	///
+	/// The [`Constraints`] every build must follow.
+	pub fn constraints(mut self, constraints: Constraints) -> Self
+	{
+		self.options.constraints = Some(constraints);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Whether to include the [`Map::distance_to_core`](crate::map::Map::distance_to_core)
+	/// through the build.
+	pub fn distances(mut self, distances: bool) -> Self
+	{
+		self.options.distances = distances;
+		self
+	}
+
+	/// # Summary
+	///
+	/// Whether to include the [`Map::display`](crate::map::Map::display) layer, with each
+	/// region's path marked on it.
+	pub fn display(mut self, display: bool) -> Self
+	{
+		self.options.display = display;
+		self
+	}
+
+	/// # Summary
+	///
+	/// See [`Objective::fairness`](crate::map::Objective::fairness).
+	pub fn fairness(mut self, fairness: usize) -> Self
+	{
+		self.options.fairness = Some(fairness);
+		self
+	}
+
+	/// # Summary
+	///
+	/// See [`Objective::formula`](crate::map::Objective::formula).
+	pub fn formula(mut self, formula: Formula) -> Self
+	{
+		self.options.formula = Some(formula);
+		self
+	}
+
+	/// # Summary
+	///
+	/// The most blocks to place.
+	pub fn max_blocks(mut self, max_blocks: usize) -> Self
+	{
+		self.options.max_blocks = Some(max_blocks);
+		self
+	}
+
+	/// # Summary
+	///
+	/// How to measure the distance from each spawn to its entrances. Defaults to
+	/// [`Metric::for_movement`].
+	pub fn metric(mut self, metric: Metric) -> Self
+	{
+		self.options.metric = Some(metric);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Whether to [`Build::minimize`](crate::map::Build::minimize) the build after solving.
+	pub fn minimize(mut self, minimize: bool) -> Self
+	{
+		self.options.minimize = minimize;
+		self
+	}
+
+	/// # Summary
+	///
+	/// How enemies move from one tile to the next.
+	pub fn movement(mut self, movement: impl Into<MovementRules>) -> Self
+	{
+		self.options.movement = movement.into();
+		self
+	}
+
+	/// # Summary
+	///
+	/// See [`Objective::robust`](crate::map::Objective::robust).
+	pub fn robust(mut self, robust: bool) -> Self
+	{
+		self.options.robust = robust;
+		self
+	}
+
+	/// # Summary
+	///
+	/// Whether to spend the [`max_blocks`](SolveOptionsBuilder::max_blocks) on each region as
+	/// [`BudgetSplit::of`](crate::map::BudgetSplit::of) recommends.
+	pub fn split(mut self, split: bool) -> Self
+	{
+		self.options.split = split;
+		self
+	}
+
+	/// # Summary
+	///
+	/// How to place blocks.
+	pub fn strategy(mut self, strategy: Strategy) -> Self
+	{
+		self.options.strategy = strategy;
+		self
+	}
+
+	/// # Summary
+	///
+	/// The most time to search for, after which the best build found so far is kept.
+	pub fn time_limit(mut self, time_limit: Duration) -> Self
+	{
+		self.options.time_limit = Some(time_limit.as_millis() as u64);
+		self
+	}
+
+	/// # Summary
+	///
+	/// How long some `strategy` of a [`Strategy::Portfolio`] may search for.
+	pub fn time_slice(mut self, strategy: Strategy, slice: TimeSlice) -> Self
+	{
+		self.options.time_slices.insert(strategy, slice);
+		self
+	}
+
+	/// # Summary
+	///
+	/// Start building [`SolveOptions`].
+	pub fn new() -> Self
+	{
+		Self::default()
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::{SolveOptions, SolveOptionsBuilder};
+	use crate::map::{Metric, Strategy};
+
+	#[test]
+	fn build()
+	{
+		let options = SolveOptionsBuilder::new()
+			.max_blocks(10)
+			.metric(Metric::Euclidean)
+			.movement(true)
+			.strategy(Strategy::Priority)
+			.build();
+
+		assert_eq!(options.max_blocks, Some(10));
+		assert!(options.movement.diagonals);
+		assert_eq!(options.metric(), Metric::Euclidean);
+
+		// Anything which was not set is the default, both here and when deserializing.
+		assert_eq!(SolveOptionsBuilder::new().build(), SolveOptions::default());
+		let partial: SolveOptions = serde_json::from_str(r#"{"max_blocks": 10}"#).unwrap();
+		assert_eq!(partial, SolveOptions { max_blocks: Some(10), ..Default::default() });
+
+		let json = serde_json::to_string(&options).unwrap();
+		assert_eq!(serde_json::from_str::<SolveOptions>(&json).unwrap(), options);
+	}
+}
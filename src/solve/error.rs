@@ -0,0 +1,72 @@
+use std::result::Result as StdResult;
+
+use snafu::Snafu;
+
+use crate::map::{build, constraints, tileset, Strategy};
+
+#[derive(Debug, Snafu)]
+pub enum Error
+{
+	#[snafu(display("{}", err))]
+	Build
+	{
+		err: build::Error
+	},
+
+	#[snafu(display("{}", err))]
+	Constraints
+	{
+		err: constraints::Error
+	},
+
+	#[snafu(display(
+		"`{}` does not place one block at a time, so it cannot be traced or resumed; use \
+		 `round-robin` instead",
+		strategy
+	))]
+	NotStepwise
+	{
+		strategy: Strategy
+	},
+
+	#[snafu(display("{}", err))]
+	Tileset
+	{
+		err: tileset::Error
+	},
+
+	#[snafu(display(
+		"{:?} is not a time slice, such as `500ms` or `30s`, or a weight such as `3`",
+		text
+	))]
+	TimeSlice
+	{
+		text: String
+	},
+}
+
+impl From<build::Error> for Error
+{
+	fn from(err: build::Error) -> Self
+	{
+		Self::Build { err }
+	}
+}
+
+impl From<constraints::Error> for Error
+{
+	fn from(err: constraints::Error) -> Self
+	{
+		Self::Constraints { err }
+	}
+}
+
+impl From<tileset::Error> for Error
+{
+	fn from(err: tileset::Error) -> Self
+	{
+		Self::Tileset { err }
+	}
+}
+
+pub type Result<T> = StdResult<T, Error>;
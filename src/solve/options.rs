@@ -0,0 +1,418 @@
+use std::{
+	collections::BTreeMap,
+	time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result, Solution, TimeSlice};
+use crate::map::{
+	tileset::{Tileset, TilesetBuilder},
+	BudgetSplit,
+	Build,
+	CancellationToken,
+	Constraints,
+	DisplayTile,
+	DistanceField,
+	Formula,
+	Map,
+	Metric,
+	MovementRules,
+	Objective,
+	Progress,
+	RegionResult,
+	SolverSession,
+	Stats,
+	Strategy,
+	StrategyStats,
+	Warning,
+};
+
+/// # Summary
+///
+/// How to [`solve`](super::solve) a [`Map`].
+///
+/// # Remarks
+///
+/// The [`Default`] options place as many blocks as help with [`Strategy::RoundRobin`], without
+/// diagonal movement. Any option left out when deserializing is the default.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SolveOptions
+{
+	/// # Summary
+	///
+	/// Whether to include the [`Build::to_code`] in the [`Stats`].
+	pub build_code: bool,
+
+	/// # Summary
+	///
+	/// If [`Some`], choose each block with [`Build::find_scored_block_placement`], looking this
+	/// many moves away from the shortest path.
+	pub candidate_scope: Option<usize>,
+
+	/// # Summary
+	///
+	/// The [`Constraints`] every build must follow. Its `budgets` are spent like a `split`, even
+	/// without `max_blocks`.
+	pub constraints: Option<Constraints>,
+
+	/// # Summary
+	///
+	/// Whether to include the [`Map::distance_to_core`] through the build.
+	pub distances: bool,
+
+	/// # Summary
+	///
+	/// Whether to include the [`Map::display`] layer, with each region's path marked on it.
+	pub display: bool,
+
+	/// # Summary
+	///
+	/// See [`Objective::fairness`].
+	pub fairness: Option<usize>,
+
+	/// # Summary
+	///
+	/// See [`Objective::formula`].
+	pub formula: Option<Formula>,
+
+	/// # Summary
+	///
+	/// The most blocks to place. If [`None`], blocks are placed until none help.
+	pub max_blocks: Option<usize>,
+
+	/// # Summary
+	///
+	/// The [`Metric`] to measure entrance distances with. Defaults to
+	/// [`Metric::for_movement`].
+	pub metric: Option<Metric>,
+
+	/// # Summary
+	///
+	/// Whether to [`Build::minimize`] the build after solving.
+	pub minimize: bool,
+
+	pub movement: MovementRules,
+
+	/// # Summary
+	///
+	/// See [`Objective::robust`].
+	pub robust: bool,
+
+	/// # Summary
+	///
+	/// Whether to spend the `max_blocks` on each region as [`BudgetSplit::of`] recommends.
+	pub split: bool,
+
+	pub strategy: Strategy,
+
+	/// # Summary
+	///
+	/// The most milliseconds to search for. Once they run out, the best build found so far is
+	/// kept.
+	pub time_limit: Option<u64>,
+
+	/// # Summary
+	///
+	/// How long each [`Strategy`] of a [`Strategy::Portfolio`] may search for, in place of the
+	/// `time_limit`.
+	pub time_slices: BTreeMap<Strategy, TimeSlice>,
+}
+
+impl SolveOptions
+{
+	/// # Summary
+	///
+	/// Finish solving a `map` with a `build` found on its `tileset` (e.g. by
+	/// [`SolveOptions::search`]): minimize it, describe it, and apply it to the `map`.
+	///
+	/// # Parameters
+	///
+	/// * `strategies`, how each [`Strategy`] of a [`Strategy::Portfolio`] fared.
+	///
+	/// # Errors
+	///
+	/// * [`build::Error::OutOfBounds`](crate::map::build::Error::OutOfBounds), if the `build` does
+	///   not fit on the `map`.
+	pub fn finish(
+		&self,
+		mut map: Map,
+		tileset: Tileset,
+		objective: &Objective,
+		mut build: Build,
+		strategies: Vec<StrategyStats>,
+	) -> Result<Solution>
+	{
+		if let Some(blocks) = self.max_blocks.filter(|max| build.blocks.len() >= *max)
+		{
+			map.warnings.push(Warning::MaxBlocks { blocks });
+		}
+
+		strategies
+			.iter()
+			.filter(|s| s.out_of_time)
+			.for_each(|s| map.warnings.push(Warning::OutOfTime { strategy: s.strategy }));
+
+		let blocks_saved = (self.minimize || objective.fairness.is_some())
+			.then(|| build.minimize(&tileset, objective, self.movement));
+
+		let score = objective.score(&tileset, &build, self.movement);
+		let stats = Stats::of(&tileset, &build, objective, self.movement, self.metric());
+
+		let regions = RegionResult::of(&tileset, &build, self.movement);
+		regions
+			.iter()
+			.enumerate()
+			.filter(|(_, r)| r.baseline_length >= r.final_length)
+			.for_each(|(region, _)| map.warnings.push(Warning::Unchanged { region }));
+
+		stats.shortest_path_length_by_class.iter().for_each(|(class, lengths)| {
+			lengths.iter().enumerate().filter(|(_, length)| length.is_none()).for_each(
+				|(region, _)| map.warnings.push(Warning::Unreachable { class: *class, region }),
+			)
+		});
+
+		map.shortest_path_length = Some(regions.iter().map(|r| r.final_length).collect());
+		map.regions = regions;
+		map.stats = Some(Stats {
+			build_code: self.build_code.then(|| {
+				let width = tileset.grid.iter().map(Vec::len).max().unwrap_or(0);
+				build.to_code(width, tileset.grid.len())
+			}),
+			blocks_saved,
+			options: Some(self.clone()),
+			strategies: if self.strategy == Strategy::Portfolio { strategies } else { Vec::new() },
+			..stats
+		});
+
+		map.distance_to_core = self.distances.then(|| {
+			DistanceField::to_coordinates(
+				&tileset.grid,
+				Some(&build.blocks),
+				tileset.core_coordinates().iter().copied(),
+				self.movement,
+			)
+		});
+
+		let avoided = tileset.avoided().to_vec();
+		map.grid = tileset.grid;
+		map.metadata = tileset.metadata;
+		for (coord, tile) in avoided
+		{
+			coord.set(&mut map.grid, tile).expect("Expected avoided tiles to be on the grid");
+		}
+
+		build.apply_to(&mut map.grid)?;
+		map.display = self.display.then(|| DisplayTile::layer(&map.grid, &map.regions));
+
+		Ok(Solution { build, map, score })
+	}
+
+	/// # Summary
+	///
+	/// The [`Metric`] to measure entrance distances with.
+	pub fn metric(&self) -> Metric
+	{
+		self.metric.unwrap_or_else(|| Metric::for_movement(self.movement.diagonals))
+	}
+
+	/// # Summary
+	///
+	/// The [`Objective`] which builds for the `map` are scored by.
+	pub fn objective(&self, map: &Map) -> Objective
+	{
+		Objective {
+			fairness: self.fairness,
+			formula: self.formula.clone(),
+			hazard_damage: map.hazard_damage.unwrap_or(1),
+			robust: self.robust,
+		}
+	}
+
+	/// # Summary
+	///
+	/// Find a [`Build`] for a `tileset`.
+	///
+	/// # Parameters
+	///
+	/// * `cancellation`, which stops the search early with the best [`Build`] found so far. The
+	///   search is also stopped by the `time_limit` and `time_slices`.
+	/// * `on_progress`, which is called each time a block is placed.
+	///
+	/// # Returns
+	///
+	/// The [`Build`], and how each [`Strategy`] fared if the `strategy` is a
+	/// [`Strategy::Portfolio`].
+	///
+	/// # Errors
+	///
+	/// See [`Build::from_strategy`].
+	pub fn search(
+		&self,
+		tileset: &Tileset,
+		objective: &Objective,
+		cancellation: &CancellationToken,
+		mut on_progress: impl FnMut(&Progress) + Send,
+	) -> Result<(Build, Vec<StrategyStats>)>
+	{
+		Ok(match self.split(tileset)?
+		{
+			Some(split) =>
+			{
+				let mut session =
+					self.session_with_split(tileset, objective, None, cancellation, Some(split))?;
+				while session.step().is_some()
+				{
+					on_progress(session.progress());
+				}
+
+				(session.into_build(), Vec::new())
+			},
+			None => Build::from_strategy(
+				tileset,
+				self.strategy,
+				objective,
+				self.movement,
+				self.max_blocks,
+				self.candidate_scope,
+				&TimeSlice::durations(
+					match self.strategy
+					{
+						Strategy::Portfolio => &Strategy::PORTFOLIO,
+						_ => std::slice::from_ref(&self.strategy),
+					},
+					&self.time_slices,
+					self.time_limit.map(Duration::from_millis),
+				),
+				cancellation,
+				on_progress,
+			)?,
+		})
+	}
+
+	/// # Summary
+	///
+	/// A [`SolverSession`] which places the same blocks as [`SolveOptions::search`] would, one at
+	/// a time, e.g. so that each step can be traced. If there is a `build`, the session
+	/// [resumes](SolverSession::resume) it.
+	///
+	/// # Remarks
+	///
+	/// The session keeps to the `max_blocks`, `candidate_scope`, `time_limit` (or the
+	/// [`Strategy::RoundRobin`] time slice), and any split of the blocks between regions.
+	///
+	/// # Errors
+	///
+	/// * [`Error::NotStepwise`](super::Error::NotStepwise), if the `strategy` is not
+	///   [`Strategy::RoundRobin`] and the blocks are not split between regions, since the search
+	///   would not place them one at a time.
+	/// * See [`SolverSession::new`] and [`SolverSession::resume`].
+	pub fn session<'tileset>(
+		&self,
+		tileset: &'tileset Tileset,
+		objective: &Objective,
+		build: Option<Build>,
+		cancellation: &CancellationToken,
+	) -> Result<SolverSession<'tileset>>
+	{
+		let split = self.split(tileset)?;
+		self.session_with_split(tileset, objective, build, cancellation, split)
+	}
+
+	/// # Summary
+	///
+	/// [`SolveOptions::session`], given the `split` from [`SolveOptions::split`].
+	fn session_with_split<'tileset>(
+		&self,
+		tileset: &'tileset Tileset,
+		objective: &Objective,
+		build: Option<Build>,
+		cancellation: &CancellationToken,
+		split: Option<Vec<usize>>,
+	) -> Result<SolverSession<'tileset>>
+	{
+		if split.is_none() && self.strategy != Strategy::RoundRobin
+		{
+			return Err(Error::NotStepwise { strategy: self.strategy });
+		}
+
+		let mut session = match build
+		{
+			Some(build) => SolverSession::resume(tileset, build, self.movement, self.max_blocks)?,
+			None => SolverSession::new(tileset, self.movement, self.max_blocks)?,
+		};
+
+		if let Some(scope) = self.candidate_scope
+		{
+			session.set_candidate_scope(scope);
+		}
+
+		let time_limit = TimeSlice::durations(
+			&[Strategy::RoundRobin],
+			&self.time_slices,
+			self.time_limit.map(Duration::from_millis),
+		)
+		.remove(&Strategy::RoundRobin);
+
+		session.set_objective(objective.clone());
+		session.set_cancellation(match time_limit
+		{
+			Some(limit) => cancellation.with_deadline(Instant::now() + limit),
+			None => cancellation.clone(),
+		});
+		if let Some(split) = split
+		{
+			session.set_split(split);
+		}
+
+		Ok(session)
+	}
+
+	/// # Summary
+	///
+	/// How many blocks each region may have, from the `budgets` of the `constraints` or else a
+	/// [`BudgetSplit`] of the `max_blocks` if they are `split`.
+	///
+	/// # Errors
+	///
+	/// See [`BudgetSplit::of`].
+	fn split(&self, tileset: &Tileset) -> Result<Option<Vec<usize>>>
+	{
+		Ok(
+			match (
+				self.constraints.as_ref().and_then(|c| c.budgets.clone()),
+				self.max_blocks.filter(|_| self.split),
+			)
+			{
+				(Some(budgets), _) => Some(budgets),
+				(None, Some(budget)) =>
+				{
+					Some(BudgetSplit::of(tileset, self.movement, budget)?.blocks)
+				},
+				(None, None) => None,
+			},
+		)
+	}
+
+	/// # Summary
+	///
+	/// Make the [`Tileset`] which a `map` is solved on. Any `constraints` are applied to it.
+	///
+	/// # Errors
+	///
+	/// * See [`TilesetBuilder::build`].
+	/// * See [`Constraints::apply`].
+	pub fn tileset(&self, map: &Map) -> Result<Tileset>
+	{
+		let mut tileset =
+			TilesetBuilder::from_map(map, self.movement.diagonals).metric(self.metric()).build()?;
+
+		if let Some(constraints) = self.constraints.as_ref()
+		{
+			constraints.apply(&mut tileset, self.movement)?;
+		}
+
+		Ok(tileset)
+	}
+}
@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, fmt, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result};
+use crate::map::Strategy;
+
+/// # Summary
+///
+/// How long a single [`Strategy`] may search for, before it is stopped with the best build it
+/// found.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeSlice
+{
+	/// # Summary
+	///
+	/// A fixed number of milliseconds.
+	Milliseconds(u64),
+
+	/// # Summary
+	///
+	/// A share of the [`time_limit`](super::SolveOptions::time_limit), in proportion to the
+	/// weights of the other strategies of a [`Strategy::Portfolio`]. Without a time limit, the
+	/// strategy may search for as long as it needs.
+	Weight(u64),
+}
+
+impl TimeSlice
+{
+	/// # Summary
+	///
+	/// How long each of some `strategies` may search for, given their `slices` and the whole
+	/// search's `time_limit`.
+	///
+	/// # Remarks
+	///
+	/// Strategies without a slice get the whole `time_limit`. Those which are not in the returned
+	/// map may search for as long as they need.
+	pub fn durations(
+		strategies: &[Strategy],
+		slices: &BTreeMap<Strategy, TimeSlice>,
+		time_limit: Option<Duration>,
+	) -> BTreeMap<Strategy, Duration>
+	{
+		let total_weight: u64 = strategies
+			.iter()
+			.filter_map(|strategy| match slices.get(strategy)
+			{
+				Some(TimeSlice::Weight(weight)) => Some(weight),
+				_ => None,
+			})
+			.sum();
+
+		strategies
+			.iter()
+			.filter_map(|strategy| {
+				let duration = match slices.get(strategy)
+				{
+					Some(TimeSlice::Milliseconds(milliseconds)) =>
+					{
+						Some(Duration::from_millis(*milliseconds))
+					},
+					Some(TimeSlice::Weight(weight)) => time_limit
+						.map(|limit| limit.mul_f64(*weight as f64 / total_weight.max(1) as f64)),
+					None => time_limit,
+				};
+
+				duration.map(|d| (*strategy, d))
+			})
+			.collect()
+	}
+}
+
+impl fmt::Display for TimeSlice
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			TimeSlice::Milliseconds(milliseconds) => write!(f, "{}ms", milliseconds),
+			TimeSlice::Weight(weight) => write!(f, "{}", weight),
+		}
+	}
+}
+
+impl FromStr for TimeSlice
+{
+	type Err = Error;
+
+	/// # Summary
+	///
+	/// Read a [`TimeSlice`] such as `500ms` or `30s`, or a [`TimeSlice::Weight`] such as `3`.
+	fn from_str(text: &str) -> Result<Self>
+	{
+		let invalid = || Error::TimeSlice { text: text.into() };
+		let number = |digits: &str| digits.parse::<u64>().map_err(|_| invalid());
+
+		if let Some(milliseconds) = text.strip_suffix("ms")
+		{
+			return number(milliseconds).map(TimeSlice::Milliseconds);
+		}
+
+		if let Some(seconds) = text.strip_suffix('s')
+		{
+			return number(seconds)?
+				.checked_mul(1000)
+				.map(TimeSlice::Milliseconds)
+				.ok_or_else(invalid);
+		}
+
+		number(text).map(TimeSlice::Weight)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::{collections::BTreeMap, time::Instant};
+
+	use super::{Duration, Strategy, TimeSlice};
+
+	#[test]
+	fn durations()
+	{
+		let slices: BTreeMap<_, _> = [
+			(Strategy::Priority, TimeSlice::Weight(3)),
+			(Strategy::RoundRobin, TimeSlice::Weight(1)),
+		]
+		.into_iter()
+		.collect();
+
+		let start = Instant::now();
+		let durations =
+			TimeSlice::durations(&Strategy::PORTFOLIO, &slices, Some(Duration::from_secs(8)));
+		println!("TimeSlice::durations {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(durations[&Strategy::Priority], Duration::from_secs(6));
+		assert_eq!(durations[&Strategy::RoundRobin], Duration::from_secs(2));
+
+		// Without a time limit, only fixed slices are kept.
+		let slices: BTreeMap<_, _> = [(Strategy::Priority, TimeSlice::Milliseconds(500))]
+			.into_iter()
+			.chain([(Strategy::RoundRobin, TimeSlice::Weight(1))])
+			.collect();
+		let durations = TimeSlice::durations(&Strategy::PORTFOLIO, &slices, None);
+		assert_eq!(durations.len(), 1);
+		assert_eq!(durations[&Strategy::Priority], Duration::from_millis(500));
+
+		// Strategies without a slice get the whole time limit.
+		let durations = TimeSlice::durations(
+			&Strategy::PORTFOLIO,
+			&BTreeMap::new(),
+			Some(Duration::from_secs(1)),
+		);
+		assert!(durations.values().all(|d| *d == Duration::from_secs(1)));
+	}
+
+	#[test]
+	fn from_str()
+	{
+		let start = Instant::now();
+		let slice = "30s".parse::<TimeSlice>().unwrap();
+		println!("TimeSlice::from_str {}us", Instant::now().duration_since(start).as_micros());
+
+		assert_eq!(slice, TimeSlice::Milliseconds(30_000));
+		assert_eq!("250ms".parse::<TimeSlice>().unwrap(), TimeSlice::Milliseconds(250));
+		assert_eq!("7".parse::<TimeSlice>().unwrap(), TimeSlice::Weight(7));
+		assert!("7%".parse::<TimeSlice>().is_err());
+		assert!("ms".parse::<TimeSlice>().is_err());
+	}
+}